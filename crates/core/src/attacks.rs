@@ -0,0 +1,52 @@
+//! # Attacks Module
+//!
+//! This module detects which pieces attack a given square.
+//! Unlike pseudo moves, attacks follow capture rules only:
+//! pawns attack diagonally (even empty squares) and never by pushing forward,
+//! and squares occupied by own pieces count as attacked (defended).
+//!
+use crate::bitboard;
+use crate::player;
+use crate::square;
+use crate::types;
+
+/// Check whether any piece of a given player attacks the square
+pub fn is_square_attacked(
+    game: types::ParsedFen,
+    target: square::Square,
+    by_player: player::Player,
+) -> bool {
+    !self::attackers_of(game, target, by_player).is_empty()
+}
+
+/// Get squares of all pieces of a given player attacking the square
+pub fn attackers_of(
+    game: types::ParsedFen,
+    target: square::Square,
+    by_player: player::Player,
+) -> Vec<square::Square> {
+    if !game.is_in_bounds(target.row, target.col) {
+        return Vec::new();
+    }
+
+    let position = bitboard::Position::from(game);
+    let attackers =
+        position.attackers_of(bitboard::square_index(target.row, target.col), by_player);
+
+    bitboard::squares(attackers)
+        .map(bitboard::to_square)
+        .collect()
+}
+
+/// Get all squares attacked by a given player
+pub fn get_attacked_squares(
+    game: types::ParsedFen,
+    by_player: player::Player,
+) -> Vec<square::Square> {
+    let position = bitboard::Position::from(game);
+
+    (0..types::BOARD_SIZE * types::BOARD_SIZE)
+        .filter(|sq| position.is_square_attacked(*sq, by_player))
+        .map(bitboard::to_square)
+        .collect()
+}
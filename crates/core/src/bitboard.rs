@@ -0,0 +1,910 @@
+//! # Bitboard Module
+//!
+//! This module holds the internal bitboard representation of a position.
+//! Every piece type has its own `u64` set, where bit `row * 8 + col` represents a square
+//! (bit 0 = a8, bit 63 = h1, matching `Square` indexing).
+//!
+//! Leaper (pawn, knight, king) attacks and sliding rays are precomputed at compile time,
+//! sliding attacks are resolved by cutting each ray at its first blocker.
+//!
+//! The public API keeps working with `ParsedFen`, positions are converted on demand
+//! with `Position::from(game)` and `ParsedFen::from(position)`.
+//!
+use crate::moves;
+use crate::pieces;
+use crate::player;
+use crate::square;
+use crate::types;
+use crate::zobrist;
+
+pub type Bitboard = u64;
+
+pub const EMPTY: Bitboard = 0;
+pub const FULL: Bitboard = u64::MAX;
+
+const SQUARES: usize = types::BOARD_SIZE * types::BOARD_SIZE;
+/// First and last row, pawns can't be dropped there
+const BACK_ROWS: Bitboard = 0xFF | 0xFF << 56;
+
+const KING_DIRS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+const WHITE_PAWN_ATTACK_DIRS: [(isize, isize); 2] = [(-1, -1), (-1, 1)];
+const BLACK_PAWN_ATTACK_DIRS: [(isize, isize); 2] = [(1, -1), (1, 1)];
+
+static KNIGHT_ATTACKS: [Bitboard; SQUARES] = self::leaper_attacks(&moves::KNIGHT_DIRS);
+static KING_ATTACKS: [Bitboard; SQUARES] = self::leaper_attacks(&KING_DIRS);
+static WHITE_PAWN_ATTACKS: [Bitboard; SQUARES] = self::leaper_attacks(&WHITE_PAWN_ATTACK_DIRS);
+static BLACK_PAWN_ATTACKS: [Bitboard; SQUARES] = self::leaper_attacks(&BLACK_PAWN_ATTACK_DIRS);
+static STRAIGHT_RAYS: [[Bitboard; SQUARES]; 4] = self::rays(&moves::STRAIGHT_DIRS);
+static DIAGONAL_RAYS: [[Bitboard; SQUARES]; 4] = self::rays(&moves::DIAGONAL_DIRS);
+
+/// Undo record of a move played with `Position::make_move`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MoveUndo {
+    req_move: types::Move,
+    /// Square and piece captured by the move, differs from the target square on en passant
+    captured: Option<(usize, pieces::PieceType)>,
+    /// Pieces removed by the explosion of the capture, indexed by `PieceType` discriminant
+    exploded: [Bitboard; 12],
+    /// State before the move
+    state: types::ParsedFenState,
+    /// Hash before the move
+    hash: u64,
+}
+
+/// Bitboard position of a game
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Position {
+    /// One bitboard per piece type, indexed by `PieceType` discriminant
+    pieces: [Bitboard; 12],
+    /// Occupancy of white and black pieces
+    colors: [Bitboard; 2],
+    /// Zobrist hash with the built-in keys, updated incrementally
+    hash: u64,
+    pub state: types::ParsedFenState,
+}
+
+impl Position {
+    /// Get piece at square index
+    pub fn piece_at(&self, sq: usize) -> Option<pieces::PieceType> {
+        let bit = self::square_bit(sq);
+
+        if (self.colors[0] | self.colors[1]) & bit == EMPTY {
+            return None;
+        }
+
+        pieces::PieceType::ALL
+            .iter()
+            .enumerate()
+            .find(|(index, _)| self.pieces[*index] & bit != EMPTY)
+            .map(|(_, piece)| *piece)
+    }
+
+    /// Get Zobrist hash of the position, see `zobrist` module
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Get bitboard of a given piece type
+    pub fn pieces(&self, piece: pieces::PieceType) -> Bitboard {
+        self.pieces[piece as usize]
+    }
+
+    /// Get bitboard of all pieces of a given player
+    pub fn occupancy(&self, player: player::Player) -> Bitboard {
+        self.colors[self::color_index(player)]
+    }
+
+    /// Get bitboard of all pieces on the board
+    pub fn all_occupancy(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
+
+    /// Get square index of the king of a given player
+    pub fn king_square(&self, player: player::Player) -> Option<usize> {
+        let king = self.pieces(pieces::PieceType::from_kind(
+            pieces::PieceKind::King,
+            player,
+        ));
+
+        match king {
+            EMPTY => None,
+            _ => Some(king.trailing_zeros() as usize),
+        }
+    }
+
+    /// Get bitboard of all pieces of a given player attacking the square
+    pub fn attackers_of(&self, sq: usize, by_player: player::Player) -> Bitboard {
+        let occupancy = self.all_occupancy();
+        let piece = |kind| self.pieces(pieces::PieceType::from_kind(kind, by_player));
+
+        // a pawn attacks the square if a pawn of the other color would attack the pawn back
+        let pawn_attacks = match by_player {
+            player::Player::White => BLACK_PAWN_ATTACKS[sq],
+            player::Player::Black => WHITE_PAWN_ATTACKS[sq],
+        };
+        let rooks_queens = piece(pieces::PieceKind::Rook) | piece(pieces::PieceKind::Queen);
+        let bishops_queens = piece(pieces::PieceKind::Bishop) | piece(pieces::PieceKind::Queen);
+
+        (pawn_attacks & piece(pieces::PieceKind::Pawn))
+            | (KNIGHT_ATTACKS[sq] & piece(pieces::PieceKind::Knight))
+            | (KING_ATTACKS[sq] & piece(pieces::PieceKind::King))
+            | (self::rook_attacks(sq, occupancy) & rooks_queens)
+            | (self::bishop_attacks(sq, occupancy) & bishops_queens)
+    }
+
+    /// Check whether any piece of a given player attacks the square
+    pub fn is_square_attacked(&self, sq: usize, by_player: player::Player) -> bool {
+        self.attackers_of(sq, by_player) != EMPTY
+    }
+
+    /// Check whether the king of a given player is attacked, `false` if the king is missing
+    pub fn is_king_attacked(&self, player: player::Player) -> bool {
+        self.king_square(player)
+            .is_some_and(|sq| self.is_square_attacked(sq, player.opponent()))
+    }
+
+    /// Check whether the castling king would start from or pass through an attacked square.
+    /// The king leaves its square first, so it doesn't shield the squares behind it.
+    /// The target square is left to the check of the position after the move,
+    /// where the castled rook may shield it.
+    pub fn is_castle_path_attacked(&self, req_move: types::Move) -> bool {
+        let rules = self.state.variant.rules();
+        let player = req_move.piece.color();
+        let from = self::square_index(req_move.from_row_idx, req_move.from_col_idx);
+        let to = self::square_index(req_move.to_row_idx, req_move.to_col_idx);
+        let path = self::row_span(
+            req_move.from_row_idx,
+            req_move.from_col_idx,
+            req_move.to_col_idx,
+        ) & !self::square_bit(to)
+            | self::square_bit(from);
+
+        let mut position = *self;
+        position.remove_piece(from, req_move.piece);
+
+        self::squares(path)
+            .any(|sq| rules.is_king_square_attacked(&position, sq, player.opponent()))
+    }
+
+    /// Get legal moves of a given player for pieces on squares of the `from` mask.
+    ///
+    /// Matches `validation::validate_move`: a move is legal if it doesn't leave the king
+    /// in check and castling doesn't start from or pass through an attacked square.
+    /// Positions without own king or without opponent pieces have no legal moves.
+    pub fn legal_moves(&self, player: player::Player, from: Bitboard) -> Vec<types::Move> {
+        self.filter_legal(player, self.pseudo_moves(player, from))
+    }
+
+    /// Get legal Crazyhouse drops of a given player, in check only drops blocking it are legal
+    pub fn legal_drops(&self, player: player::Player) -> Vec<types::Move> {
+        self.filter_legal(player, self.pseudo_drops(player))
+    }
+
+    /// Get legal moves of all pieces of a given player, followed by drops in Crazyhouse
+    pub fn all_legal_moves(&self, player: player::Player) -> Vec<types::Move> {
+        let mut moves = self.legal_moves(player, FULL);
+        moves.extend(self.legal_drops(player));
+        moves
+    }
+
+    /// Get pseudo moves of a given player for pieces on squares of the `from` mask
+    pub fn pseudo_moves(&self, player: player::Player, from: Bitboard) -> Vec<types::Move> {
+        let mut moves = Vec::new();
+        let own = self.occupancy(player);
+        let occupancy = self.all_occupancy();
+
+        for sq in self::squares(own & from) {
+            let Some(piece) = self.piece_at(sq) else {
+                continue;
+            };
+
+            let targets = match piece.piece_kind() {
+                pieces::PieceKind::Pawn => {
+                    self.push_pawn_moves(&mut moves, sq, piece);
+                    continue;
+                }
+                pieces::PieceKind::Knight => KNIGHT_ATTACKS[sq],
+                pieces::PieceKind::Bishop => self::bishop_attacks(sq, occupancy),
+                pieces::PieceKind::Rook => self::rook_attacks(sq, occupancy),
+                pieces::PieceKind::Queen => {
+                    self::rook_attacks(sq, occupancy) | self::bishop_attacks(sq, occupancy)
+                }
+                pieces::PieceKind::King => {
+                    self.push_castle_moves(&mut moves, sq, piece);
+                    KING_ATTACKS[sq]
+                }
+            };
+
+            for to in self::squares(targets & !own) {
+                moves.push(self::new_move(sq, to, piece));
+            }
+        }
+
+        moves
+    }
+
+    /// Get Crazyhouse drops of a given player, pieces from the pocket to any empty square.
+    /// Pawns can't be dropped on the first and the last row.
+    pub fn pseudo_drops(&self, player: player::Player) -> Vec<types::Move> {
+        let Some(pockets) = self.state.pockets else {
+            return Vec::new();
+        };
+        let pocket = pockets.pocket(player);
+        let empty = !self.all_occupancy();
+        let mut moves = Vec::new();
+
+        for kind in types::Pocket::KINDS {
+            if pocket.count(kind) == 0 {
+                continue;
+            }
+
+            let targets = match kind {
+                pieces::PieceKind::Pawn => empty & !BACK_ROWS,
+                _ => empty,
+            };
+            let piece = pieces::PieceType::from_kind(kind, player);
+
+            for to in self::squares(targets) {
+                moves.push(types::Move {
+                    is_drop: true,
+                    ..self::new_move(to, to, piece)
+                });
+            }
+        }
+
+        moves
+    }
+
+    /// Play a move generated for this position and return the next position.
+    pub fn play(&self, req_move: types::Move) -> Self {
+        let mut next = *self;
+        next.make_move(req_move);
+        next
+    }
+
+    /// Play a move generated for this position in place.
+    /// Mirrors `state::get_next`, a pawn reaching the last rank without
+    /// promotion piece stays a pawn.
+    ///
+    /// Returns an undo record, which restores the position with `unmake_move`.
+    pub fn make_move(&mut self, req_move: types::Move) -> MoveUndo {
+        let piece = req_move.piece;
+        let player = piece.color();
+        let is_white = piece.is_white();
+        let from = self::square_index(req_move.from_row_idx, req_move.from_col_idx);
+        let to = self::square_index(req_move.to_row_idx, req_move.to_col_idx);
+
+        let captured_sq = match req_move.is_passant {
+            true => self::square_index(req_move.from_row_idx, req_move.to_col_idx),
+            false => to,
+        };
+        let captured = self
+            .piece_at(captured_sq)
+            .filter(|captured| captured.color() != player)
+            .map(|captured| (captured_sq, captured));
+
+        let mut undo = MoveUndo {
+            req_move,
+            captured,
+            exploded: [EMPTY; 12],
+            state: self.state,
+            hash: self.hash,
+        };
+        let rules = self.state.variant.rules();
+
+        // state keys are xored out here and back in with the next state at the end
+        self.hash ^= self.state_hash();
+
+        // board
+        if let Some((captured_sq, captured_piece)) = captured {
+            self.remove_piece(captured_sq, captured_piece);
+        }
+        // both castling pieces leave first, in Chess960 the king may land on the rook square
+        let castle_rook = req_move
+            .is_castle
+            .then(|| self::castle_rook_squares(req_move, &self.state));
+        let rook = pieces::PieceType::from_kind(pieces::PieceKind::Rook, player);
+
+        if !req_move.is_drop {
+            self.remove_piece(from, piece);
+        }
+        if let Some((rook_source, _)) = castle_rook {
+            self.remove_piece(rook_source, rook);
+        }
+
+        self.put_piece(to, req_move.promotion_piece.unwrap_or(piece));
+        if let Some((_, rook_target)) = castle_rook {
+            self.put_piece(rook_target, rook);
+        }
+
+        // explosion of the capture in Atomic, including the capturing piece
+        if captured.is_some() {
+            for sq in self::squares(rules.explosion(self, to)) {
+                if let Some(exploded) = self.piece_at(sq) {
+                    self.remove_piece(sq, exploded);
+                    undo.exploded[exploded as usize] |= self::square_bit(sq);
+                }
+            }
+        }
+
+        // castling rights
+        let (initial_row, opponent_initial_row) = match is_white {
+            true => (types::MAX_SIZE_INDEX, 0),
+            false => (0, types::MAX_SIZE_INDEX),
+        };
+
+        match piece.piece_kind() {
+            pieces::PieceKind::King => self.remove_castle_rights(player, true, true),
+            pieces::PieceKind::Rook
+                if req_move.from_row_idx == initial_row && !req_move.is_drop =>
+            {
+                self.remove_castle_rights(
+                    player,
+                    req_move.from_col_idx == self.state.castle_rooks.short_col,
+                    req_move.from_col_idx == self.state.castle_rooks.long_col,
+                )
+            }
+            _ => {}
+        }
+
+        let opponent_rook =
+            pieces::PieceType::from_kind(pieces::PieceKind::Rook, player.opponent());
+        if req_move.to_row_idx == opponent_initial_row
+            && captured.is_some_and(|(_, captured)| captured == opponent_rook)
+        {
+            self.remove_castle_rights(
+                player.opponent(),
+                req_move.to_col_idx == self.state.castle_rooks.short_col,
+                req_move.to_col_idx == self.state.castle_rooks.long_col,
+            );
+        }
+
+        if let Some(pockets) = self.state.pockets.as_mut() {
+            let captured = captured.map(|(captured_sq, captured_piece)| {
+                (
+                    captured_sq / types::BOARD_SIZE,
+                    captured_sq % types::BOARD_SIZE,
+                    captured_piece,
+                )
+            });
+            pockets.apply_move(&req_move, captured);
+        }
+
+        // en passant square is only available right after a double pawn push
+        let is_pawn = piece.piece_kind() == pieces::PieceKind::Pawn;
+        self.state.en_passant_square = None;
+        if is_pawn && req_move.to_row_idx.abs_diff(req_move.from_row_idx) == 2 {
+            self.state.en_passant_square = Some(square::Square::new(
+                (req_move.from_row_idx + req_move.to_row_idx) / 2,
+                req_move.to_col_idx,
+            ));
+        }
+
+        // half moves
+        if is_pawn || captured.is_some() {
+            self.state.half_moves = 0;
+        } else {
+            self.state.half_moves += 1;
+        }
+
+        // full moves & onTurn
+        if !is_white {
+            self.state.full_moves += 1;
+        }
+        self.state.on_turn = player.opponent();
+
+        rules.update_state(self);
+
+        self.hash ^= self.state_hash();
+
+        undo
+    }
+
+    /// Take back the move of the undo record, restoring board, castling rights,
+    /// en passant square, clocks and player on turn exactly.
+    pub fn unmake_move(&mut self, undo: MoveUndo) {
+        for (piece, exploded) in pieces::PieceType::ALL.iter().zip(undo.exploded) {
+            for sq in self::squares(exploded) {
+                self.put_piece(sq, *piece);
+            }
+        }
+
+        let req_move = undo.req_move;
+        let piece = req_move.piece;
+        let from = self::square_index(req_move.from_row_idx, req_move.from_col_idx);
+        let to = self::square_index(req_move.to_row_idx, req_move.to_col_idx);
+
+        let castle_rook = req_move
+            .is_castle
+            .then(|| self::castle_rook_squares(req_move, &undo.state));
+        let rook = pieces::PieceType::from_kind(pieces::PieceKind::Rook, piece.color());
+
+        self.remove_piece(to, req_move.promotion_piece.unwrap_or(piece));
+        if let Some((_, rook_target)) = castle_rook {
+            self.remove_piece(rook_target, rook);
+        }
+
+        if !req_move.is_drop {
+            self.put_piece(from, piece);
+        }
+        if let Some((rook_source, _)) = castle_rook {
+            self.put_piece(rook_source, rook);
+        }
+
+        if let Some((captured_sq, captured_piece)) = undo.captured {
+            self.put_piece(captured_sq, captured_piece);
+        }
+
+        self.state = undo.state;
+        self.hash = undo.hash;
+    }
+
+    /// Keep moves which don't leave the king in check and are allowed by the variant.
+    /// Positions without own king or without opponent pieces have no legal moves.
+    fn filter_legal(&self, player: player::Player, moves: Vec<types::Move>) -> Vec<types::Move> {
+        let rules = self.state.variant.rules();
+        let is_king_missing = rules.is_king_royal() && self.king_square(player).is_none();
+
+        if is_king_missing || self.occupancy(player.opponent()) == EMPTY {
+            return Vec::new();
+        }
+
+        let mut position = *self;
+        let legal = moves
+            .into_iter()
+            .filter(|m| position.is_legal(*m))
+            .collect();
+
+        rules.filter_moves(self, legal)
+    }
+
+    fn is_legal(&mut self, req_move: types::Move) -> bool {
+        let player = req_move.piece.color();
+        let rules = self.state.variant.rules();
+
+        // king can't castle out of check or through an attacked square,
+        // the target square is covered by the check below
+        if req_move.is_castle && self.is_castle_path_attacked(req_move) {
+            return false;
+        }
+
+        let undo = self.make_move(req_move);
+        let is_legal = rules.is_legal_after(self, player);
+        self.unmake_move(undo);
+
+        is_legal
+    }
+
+    fn push_pawn_moves(&self, moves: &mut Vec<types::Move>, sq: usize, piece: pieces::PieceType) {
+        let player = piece.color();
+        let occupancy = self.all_occupancy();
+        let row = sq / types::BOARD_SIZE;
+        let col = sq % types::BOARD_SIZE;
+        let (start_row, promotion_row, attacks) = match player {
+            player::Player::White => (6, 0, WHITE_PAWN_ATTACKS[sq]),
+            player::Player::Black => (1, 7, BLACK_PAWN_ATTACKS[sq]),
+        };
+        let one_step_row = match player {
+            player::Player::White => row.checked_sub(1),
+            player::Player::Black => Some(row + 1).filter(|r| *r <= types::MAX_SIZE_INDEX),
+        };
+
+        let mut push = |to: usize, is_passant: bool| {
+            let pawn_move = types::Move {
+                is_passant,
+                ..self::new_move(sq, to, piece)
+            };
+
+            if to / types::BOARD_SIZE != promotion_row {
+                moves.push(pawn_move);
+                return;
+            }
+
+            for promotion_piece in self.state.variant.rules().promotion_pieces(player) {
+                moves.push(types::Move {
+                    promotion_piece: Some(promotion_piece),
+                    ..pawn_move
+                });
+            }
+        };
+
+        // one and two steps forward
+        if let Some(one_step_row) = one_step_row {
+            let one_step = self::square_index(one_step_row, col);
+
+            if occupancy & self::square_bit(one_step) == EMPTY {
+                push(one_step, false);
+
+                if row == start_row {
+                    let two_step = self::square_index(2 * one_step_row - row, col);
+                    if occupancy & self::square_bit(two_step) == EMPTY {
+                        push(two_step, false);
+                    }
+                }
+            }
+        }
+
+        // captures
+        for to in self::squares(attacks & self.occupancy(player.opponent())) {
+            push(to, false);
+        }
+
+        // en passant, the captured pawn stands next to the capturing one
+        if let Some(en_passant) = self.state.en_passant_square {
+            if self::is_in_bounds(en_passant) {
+                let to = self::square_index(en_passant.row, en_passant.col);
+                let captured_sq = self::square_index(row, en_passant.col);
+                let enemy_pawn =
+                    pieces::PieceType::from_kind(pieces::PieceKind::Pawn, player.opponent());
+
+                if attacks & self::square_bit(to) != EMPTY
+                    && occupancy & self::square_bit(to) == EMPTY
+                    && self.pieces(enemy_pawn) & self::square_bit(captured_sq) != EMPTY
+                {
+                    push(to, true);
+                }
+            }
+        }
+    }
+
+    fn push_castle_moves(&self, moves: &mut Vec<types::Move>, sq: usize, piece: pieces::PieceType) {
+        let player = piece.color();
+        let king_initial_row = match player {
+            player::Player::White => types::MAX_SIZE_INDEX,
+            player::Player::Black => 0,
+        };
+
+        if sq / types::BOARD_SIZE != king_initial_row {
+            return;
+        }
+
+        let (can_castle_short, can_castle_long) = match player {
+            player::Player::White => (self.state.castle_white_short, self.state.castle_white_long),
+            player::Player::Black => (self.state.castle_black_short, self.state.castle_black_long),
+        };
+        let rooks = self.pieces(pieces::PieceType::from_kind(
+            pieces::PieceKind::Rook,
+            player,
+        ));
+        let king_col = sq % types::BOARD_SIZE;
+
+        // (allowed, short side, king target column)
+        let sides = [
+            (can_castle_short, true, types::KING_SHORT_CASTLE_COL),
+            (can_castle_long, false, types::KING_LONG_CASTLE_COL),
+        ];
+
+        for (allowed, is_short, king_target_col) in sides {
+            let rook_col = self.state.castle_rooks.col(is_short);
+            let rook_sq = self::square_index(king_initial_row, rook_col);
+
+            if !allowed
+                || (rook_col > king_col) != is_short
+                || rooks & self::square_bit(rook_sq) == EMPTY
+            {
+                continue;
+            }
+
+            // squares passed by the king and the rook must be empty, except for the two of them
+            let rook_target_col = match is_short {
+                true => king_target_col - 1,
+                false => king_target_col + 1,
+            };
+            let path = (self::row_span(king_initial_row, king_col, king_target_col)
+                | self::row_span(king_initial_row, rook_col, rook_target_col))
+                & !self::square_bit(sq)
+                & !self::square_bit(rook_sq);
+
+            if self.all_occupancy() & path == EMPTY {
+                moves.push(types::Move {
+                    is_castle: true,
+                    ..self::new_move(
+                        sq,
+                        self::square_index(king_initial_row, king_target_col),
+                        piece,
+                    )
+                });
+            }
+        }
+    }
+
+    fn put_piece(&mut self, sq: usize, piece: pieces::PieceType) {
+        let bit = self::square_bit(sq);
+        self.pieces[piece as usize] |= bit;
+        self.colors[self::color_index(piece.color())] |= bit;
+        self.hash ^= zobrist::DEFAULT_KEYS.piece(piece, sq);
+    }
+
+    fn remove_piece(&mut self, sq: usize, piece: pieces::PieceType) {
+        let bit = !self::square_bit(sq);
+        self.pieces[piece as usize] &= bit;
+        self.colors[self::color_index(piece.color())] &= bit;
+        self.hash ^= zobrist::DEFAULT_KEYS.piece(piece, sq);
+    }
+
+    /// Combined hash keys of castling rights, player on turn, pockets, checks and en passant column.
+    /// En passant counts only when a pawn of the player on turn could capture.
+    fn state_hash(&self) -> u64 {
+        let keys = &zobrist::DEFAULT_KEYS;
+        let mut hash = keys.castling(&self.state)
+            ^ keys.turn(self.state.on_turn)
+            ^ keys.pockets(&self.state)
+            ^ keys.checks(&self.state);
+
+        if let Some(en_passant) = self
+            .state
+            .en_passant_square
+            .filter(|sq| self::is_in_bounds(*sq))
+        {
+            let on_turn = self.state.on_turn;
+            let pawns = self.pieces(pieces::PieceType::from_kind(
+                pieces::PieceKind::Pawn,
+                on_turn,
+            ));
+            let capturing_pawns = match on_turn {
+                player::Player::White => BLACK_PAWN_ATTACKS,
+                player::Player::Black => WHITE_PAWN_ATTACKS,
+            }[self::square_index(en_passant.row, en_passant.col)];
+
+            if pawns & capturing_pawns != EMPTY {
+                hash ^= keys.en_passant(en_passant.col);
+            }
+        }
+
+        hash
+    }
+
+    /// Remove castling rights of a given player to the sides
+    pub fn remove_castle_rights(&mut self, player: player::Player, short: bool, long: bool) {
+        let (castle_short, castle_long) = match player {
+            player::Player::White => (
+                &mut self.state.castle_white_short,
+                &mut self.state.castle_white_long,
+            ),
+            player::Player::Black => (
+                &mut self.state.castle_black_short,
+                &mut self.state.castle_black_long,
+            ),
+        };
+
+        if short {
+            *castle_short = false;
+        }
+        if long {
+            *castle_long = false;
+        }
+    }
+}
+
+impl From<types::ParsedFen> for Position {
+    fn from(game: types::ParsedFen) -> Self {
+        let mut position = Self {
+            pieces: [EMPTY; 12],
+            colors: [EMPTY; 2],
+            hash: 0,
+            state: game.state,
+        };
+
+        for (row_idx, row) in game.board.iter().enumerate() {
+            for (col_idx, piece) in row.iter().enumerate() {
+                if let Some(piece) = piece {
+                    position.put_piece(self::square_index(row_idx, col_idx), *piece);
+                }
+            }
+        }
+
+        position.hash ^= position.state_hash();
+
+        position
+    }
+}
+
+impl From<Position> for types::ParsedFen {
+    fn from(position: Position) -> Self {
+        let mut board: types::Board = [[None; types::BOARD_SIZE]; types::BOARD_SIZE];
+
+        for (index, piece) in pieces::PieceType::ALL.iter().enumerate() {
+            for sq in self::squares(position.pieces[index]) {
+                board[sq / types::BOARD_SIZE][sq % types::BOARD_SIZE] = Some(*piece);
+            }
+        }
+
+        types::ParsedFen {
+            board,
+            state: position.state,
+        }
+    }
+}
+
+/// Get square index of row and column
+pub fn square_index(row: usize, col: usize) -> usize {
+    row * types::BOARD_SIZE + col
+}
+
+/// Get bitboard with a single square set
+pub fn square_bit(sq: usize) -> Bitboard {
+    1 << sq
+}
+
+/// Convert square index back to `Square`
+pub fn to_square(sq: usize) -> square::Square {
+    square::Square::new(sq / types::BOARD_SIZE, sq % types::BOARD_SIZE)
+}
+
+/// Iterate over square indexes of set bits, from the lowest
+pub fn squares(mut bitboard: Bitboard) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if bitboard == EMPTY {
+            return None;
+        }
+
+        let sq = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+        Some(sq)
+    })
+}
+
+/// Squares attacked by a rook on the square, including the first blocker in each direction
+pub fn rook_attacks(sq: usize, occupancy: Bitboard) -> Bitboard {
+    self::sliding_attacks(sq, occupancy, &moves::STRAIGHT_DIRS, &STRAIGHT_RAYS)
+}
+
+/// Squares attacked by a bishop on the square, including the first blocker in each direction
+pub fn bishop_attacks(sq: usize, occupancy: Bitboard) -> Bitboard {
+    self::sliding_attacks(sq, occupancy, &moves::DIAGONAL_DIRS, &DIAGONAL_RAYS)
+}
+
+/// Squares attacked by a knight on the square
+pub fn knight_attacks(sq: usize) -> Bitboard {
+    KNIGHT_ATTACKS[sq]
+}
+
+/// Squares attacked by a king on the square
+pub fn king_attacks(sq: usize) -> Bitboard {
+    KING_ATTACKS[sq]
+}
+
+/// Squares attacked by a pawn of a given player on the square
+pub fn pawn_attacks(sq: usize, player: player::Player) -> Bitboard {
+    match player {
+        player::Player::White => WHITE_PAWN_ATTACKS[sq],
+        player::Player::Black => BLACK_PAWN_ATTACKS[sq],
+    }
+}
+
+fn sliding_attacks(
+    sq: usize,
+    occupancy: Bitboard,
+    directions: &[(isize, isize); 4],
+    rays: &[[Bitboard; SQUARES]; 4],
+) -> Bitboard {
+    let mut attacks = EMPTY;
+
+    for (dir_idx, &(row_dir, col_dir)) in directions.iter().enumerate() {
+        let ray = rays[dir_idx][sq];
+        let blockers = ray & occupancy;
+
+        if blockers == EMPTY {
+            attacks |= ray;
+            continue;
+        }
+
+        // rays growing in square index are blocked by the lowest bit, the others by the highest
+        let is_increasing = row_dir > 0 || (row_dir == 0 && col_dir > 0);
+        let blocker = match is_increasing {
+            true => blockers.trailing_zeros() as usize,
+            false => 63 - blockers.leading_zeros() as usize,
+        };
+
+        attacks |= ray & !rays[dir_idx][blocker];
+    }
+
+    attacks
+}
+
+/// Get rook source and target squares of a castling move, rook columns come from the state
+/// before the move
+fn castle_rook_squares(req_move: types::Move, state: &types::ParsedFenState) -> (usize, usize) {
+    let is_short = req_move.is_short_castle();
+    let rook_source_col = state.castle_rooks.col(is_short);
+    let rook_target_col = match is_short {
+        true => types::KING_SHORT_CASTLE_COL - 1,
+        false => types::KING_LONG_CASTLE_COL + 1,
+    };
+
+    (
+        self::square_index(req_move.to_row_idx, rook_source_col),
+        self::square_index(req_move.to_row_idx, rook_target_col),
+    )
+}
+
+/// Get squares of the row between two columns, both included
+fn row_span(row: usize, col_a: usize, col_b: usize) -> Bitboard {
+    (col_a.min(col_b)..=col_a.max(col_b)).fold(EMPTY, |span, col| {
+        span | self::square_bit(self::square_index(row, col))
+    })
+}
+
+fn new_move(from: usize, to: usize, piece: pieces::PieceType) -> types::Move {
+    types::Move {
+        from_col_idx: from % types::BOARD_SIZE,
+        from_row_idx: from / types::BOARD_SIZE,
+        to_col_idx: to % types::BOARD_SIZE,
+        to_row_idx: to / types::BOARD_SIZE,
+        is_castle: false,
+        is_passant: false,
+        is_drop: false,
+        promotion_piece: None,
+        piece,
+    }
+}
+
+fn is_in_bounds(sq: square::Square) -> bool {
+    sq.row <= types::MAX_SIZE_INDEX && sq.col <= types::MAX_SIZE_INDEX
+}
+
+/// Index of the player in per color tables, White first
+pub fn color_index(player: player::Player) -> usize {
+    match player {
+        player::Player::White => 0,
+        player::Player::Black => 1,
+    }
+}
+
+const fn leaper_attacks(directions: &[(isize, isize)]) -> [Bitboard; SQUARES] {
+    let mut table = [EMPTY; SQUARES];
+    let mut sq = 0;
+
+    while sq < SQUARES {
+        let row = (sq / types::BOARD_SIZE) as isize;
+        let col = (sq % types::BOARD_SIZE) as isize;
+        let mut dir_idx = 0;
+
+        while dir_idx < directions.len() {
+            let (row_dir, col_dir) = directions[dir_idx];
+            let (new_row, new_col) = (row + row_dir, col + col_dir);
+
+            if new_row >= 0 && new_row < 8 && new_col >= 0 && new_col < 8 {
+                table[sq] |= 1 << (new_row * 8 + new_col);
+            }
+            dir_idx += 1;
+        }
+        sq += 1;
+    }
+
+    table
+}
+
+const fn rays(directions: &[(isize, isize); 4]) -> [[Bitboard; SQUARES]; 4] {
+    let mut table = [[EMPTY; SQUARES]; 4];
+    let mut dir_idx = 0;
+
+    while dir_idx < 4 {
+        let (row_dir, col_dir) = directions[dir_idx];
+        let mut sq = 0;
+
+        while sq < SQUARES {
+            let mut row = (sq / types::BOARD_SIZE) as isize + row_dir;
+            let mut col = (sq % types::BOARD_SIZE) as isize + col_dir;
+
+            while row >= 0 && row < 8 && col >= 0 && col < 8 {
+                table[dir_idx][sq] |= 1 << (row * 8 + col);
+                row += row_dir;
+                col += col_dir;
+            }
+            sq += 1;
+        }
+        dir_idx += 1;
+    }
+
+    table
+}
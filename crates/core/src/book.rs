@@ -0,0 +1,184 @@
+//! # Book Module
+//!
+//! This module reads opening books in the Polyglot `.bin` format.
+//!
+//! A book is a list of 16 byte big-endian entries sorted by position hash:
+//! - `u64` Zobrist hash of the position
+//! - `u16` move: to file (bits 0-2), to rank (3-5), from file (6-8), from rank (9-11),
+//!   promotion piece (12-14, 1 = knight, 2 = bishop, 3 = rook, 4 = queen)
+//! - `u16` weight of the move
+//! - `u32` learn value (unused by most tools)
+//!
+//! Castling is encoded as the king taking its own rook (eg. e1h1 for short castling).
+//! Standard books are hashed with the Polyglot `Random64` keys,
+//! pass `ZobristKeys::from_polyglot_random64` keys to read them.
+//!
+use crate::bitboard;
+use crate::err;
+use crate::pieces;
+use crate::random;
+use crate::types;
+use crate::zobrist;
+use serde::{Deserialize, Serialize};
+
+/// Size of a single book entry in bytes
+pub const ENTRY_SIZE: usize = 16;
+
+/// Legal move of a position found in the book
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct BookMove {
+    pub req_move: types::Move,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct BookEntry {
+    key: u64,
+    raw_move: u16,
+    weight: u16,
+    learn: u32,
+}
+
+/// Polyglot opening book loaded into memory
+#[derive(Debug, Clone)]
+pub struct PolyglotBook {
+    entries: Vec<BookEntry>,
+    keys: zobrist::ZobristKeys,
+}
+
+impl PolyglotBook {
+    /// Read a book from the content of a `.bin` file, positions are hashed with the keys
+    pub fn from_bytes(bytes: &[u8], keys: zobrist::ZobristKeys) -> types::ChessResult<Self> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(err::ChessError::InvalidBook(format!(
+                "Length {} is not a multiple of {} bytes",
+                bytes.len(),
+                ENTRY_SIZE
+            )));
+        }
+
+        let mut entries: Vec<BookEntry> = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|chunk| BookEntry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                raw_move: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+                learn: u32::from_be_bytes(chunk[12..16].try_into().unwrap()),
+            })
+            .collect();
+
+        // lookup relies on sorted keys, stable sort keeps the order of moves in the file
+        entries.sort_by_key(|entry| entry.key);
+
+        Ok(Self { entries, keys })
+    }
+
+    /// Number of entries in the book
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get legal book moves of the position, sorted from the highest weight.
+    /// Entries which don't decode into a legal move are skipped.
+    pub fn moves(&self, game: types::ParsedFen) -> Vec<BookMove> {
+        let key = zobrist::hash(game, &self.keys);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+
+        let mut moves: Vec<BookMove> = self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter_map(|entry| {
+                self::decode_move(entry.raw_move, game).map(|req_move| BookMove {
+                    req_move,
+                    weight: entry.weight,
+                    learn: entry.learn,
+                })
+            })
+            .collect();
+
+        moves.sort_by_key(|book_move| std::cmp::Reverse(book_move.weight));
+        moves
+    }
+
+    /// Pick a book move of the position randomly, with chances proportional to the weights.
+    /// The same seed always picks the same move, `None` if the book has no move with weight.
+    pub fn pick_move(&self, game: types::ParsedFen, seed: u64) -> Option<types::Move> {
+        let moves = self.moves(game);
+        let total: u64 = moves
+            .iter()
+            .map(|book_move| u64::from(book_move.weight))
+            .sum();
+
+        if total == 0 {
+            return None;
+        }
+
+        let mut target = random::Rng::new(seed).below(total);
+
+        for book_move in moves {
+            let weight = u64::from(book_move.weight);
+            if target < weight {
+                return Some(book_move.req_move);
+            }
+            target -= weight;
+        }
+
+        None
+    }
+}
+
+/// Convert a Polyglot move into the matching legal move of the position
+fn decode_move(raw_move: u16, game: types::ParsedFen) -> Option<types::Move> {
+    let field = |shift: u16| usize::from((raw_move >> shift) & 0b111);
+
+    let to_col = field(0);
+    let to_row = types::MAX_SIZE_INDEX - field(3);
+    let from_col = field(6);
+    let from_row = types::MAX_SIZE_INDEX - field(9);
+    let promotion_kind = match field(12) {
+        0 => None,
+        1 => Some(pieces::PieceKind::Knight),
+        2 => Some(pieces::PieceKind::Bishop),
+        3 => Some(pieces::PieceKind::Rook),
+        4 => Some(pieces::PieceKind::Queen),
+        _ => return None,
+    };
+
+    let piece = game.safe_access_square(from_row, from_col).ok()??;
+
+    // king taking its own rook is castling, in standard chess moves of the king by two squares too
+    let is_king = piece.piece_kind() == pieces::PieceKind::King;
+    let takes_own_rook = is_king
+        && game.safe_access_square(to_row, to_col).ok()?
+            == Some(pieces::PieceType::from_kind(
+                pieces::PieceKind::Rook,
+                piece.color(),
+            ));
+    let is_castle =
+        takes_own_rook || (is_king && !game.state.chess960 && from_col.abs_diff(to_col) == 2);
+    let to_col = match (takes_own_rook, to_col > from_col) {
+        (true, true) => types::KING_SHORT_CASTLE_COL,
+        (true, false) => types::KING_LONG_CASTLE_COL,
+        (false, _) => to_col,
+    };
+
+    let from = bitboard::square_bit(bitboard::square_index(from_row, from_col));
+
+    bitboard::Position::from(game)
+        .legal_moves(game.state.on_turn, from)
+        .into_iter()
+        .find(|req_move| {
+            req_move.to_row_idx == to_row
+                && req_move.to_col_idx == to_col
+                && req_move.is_castle == is_castle
+                && req_move
+                    .promotion_piece
+                    .map(|promotion| promotion.piece_kind())
+                    == promotion_kind
+        })
+}
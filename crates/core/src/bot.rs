@@ -0,0 +1,91 @@
+//! # Bot Module
+//!
+//! This module picks moves of a computer opponent with adjustable strength.
+//!
+//! Every level limits the search depth, adds random noise to the move scores
+//! and sometimes picks one of the top moves instead of the best one.
+//! Randomness comes from a seeded generator, so the same position, level
+//! and seed always produce the same move.
+//!
+use crate::err;
+use crate::random;
+use crate::search;
+use crate::transposition;
+use crate::types;
+
+/// Weakest bot level
+pub const MIN_LEVEL: u8 = 1;
+/// Strongest bot level
+pub const MAX_LEVEL: u8 = 10;
+
+/// Search settings of a single level
+struct LevelSettings {
+    /// Search depth including the bot move
+    depth: u32,
+    /// Maximal noise in centipawns added to or subtracted from every move score
+    noise: i32,
+    /// Chance in percent to pick a random move from the top moves
+    random_move_percent: u64,
+    /// Number of best moves to pick the random move from
+    top_moves: usize,
+}
+
+#[rustfmt::skip]
+const LEVELS: [LevelSettings; MAX_LEVEL as usize] = [
+    LevelSettings { depth: 1, noise: 200, random_move_percent: 50, top_moves: 5 },
+    LevelSettings { depth: 1, noise: 150, random_move_percent: 40, top_moves: 4 },
+    LevelSettings { depth: 2, noise: 100, random_move_percent: 30, top_moves: 4 },
+    LevelSettings { depth: 2, noise: 75, random_move_percent: 25, top_moves: 3 },
+    LevelSettings { depth: 3, noise: 50, random_move_percent: 20, top_moves: 3 },
+    LevelSettings { depth: 3, noise: 35, random_move_percent: 15, top_moves: 2 },
+    LevelSettings { depth: 3, noise: 20, random_move_percent: 10, top_moves: 2 },
+    LevelSettings { depth: 4, noise: 10, random_move_percent: 5, top_moves: 2 },
+    LevelSettings { depth: 4, noise: 0, random_move_percent: 0, top_moves: 1 },
+    LevelSettings { depth: 5, noise: 0, random_move_percent: 0, top_moves: 1 },
+];
+
+/// Pick a move of the player on turn for the bot level, reusing results stored in the table
+pub fn bot_move(
+    game: types::ParsedFen,
+    level: u8,
+    seed: u64,
+    table: &mut transposition::TranspositionTable,
+) -> types::ChessResult<types::Move> {
+    if !(MIN_LEVEL..=MAX_LEVEL).contains(&level) {
+        return Err(err::ChessError::InvalidBotLevel(level));
+    }
+
+    let settings = &LEVELS[(level - MIN_LEVEL) as usize];
+    let no_legal_moves = || err::ChessError::InvalidMove("No legal moves available".to_string());
+
+    // without any randomness the plain search is faster than scoring every move
+    if settings.noise == 0 && settings.random_move_percent == 0 {
+        let limits = search::SearchLimits {
+            depth: Some(settings.depth),
+            ..search::SearchLimits::default()
+        };
+        return search::search(game, limits, search::system_clock, table)
+            .best_move
+            .ok_or_else(no_legal_moves);
+    }
+
+    let mut rng = random::Rng::new(seed);
+    let mut scored: Vec<(types::Move, i32)> = search::score_moves(game, settings.depth, table)
+        .into_iter()
+        .map(|(req_move, score)| (req_move, score + rng.noise(settings.noise)))
+        .collect();
+
+    // stable sort keeps the search order of moves with equal scores
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+    let candidates = scored.len().min(settings.top_moves);
+    let idx = match rng.below(100) < settings.random_move_percent && candidates > 0 {
+        true => rng.below(candidates as u64) as usize,
+        false => 0,
+    };
+
+    scored
+        .get(idx)
+        .map(|(req_move, _)| *req_move)
+        .ok_or_else(no_legal_moves)
+}
@@ -0,0 +1,114 @@
+//! # Chess960 Module
+//!
+//! This module generates start positions of Chess960 (Fischer Random Chess).
+//!
+//! Pieces of the first rank are shuffled, bishops stand on squares of opposite colors and
+//! the king between the rooks. Black mirrors the setup of White. Positions are numbered
+//! from 0 to 959 by the Scharnagl scheme, position 518 is the standard setup.
+//!
+use crate::err;
+use crate::pieces;
+use crate::player;
+use crate::types;
+use crate::variant;
+
+/// Number of Chess960 start positions
+pub const POSITION_COUNT: u16 = 960;
+
+/// Knight columns among the five free columns left after placing bishops and queen
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+/// Get start position of the index, both players can castle to both sides
+pub fn start_position(index: u16) -> types::ChessResult<types::ParsedFen> {
+    if index >= POSITION_COUNT {
+        return Err(err::ChessError::InvalidChess960Index(index));
+    }
+
+    let kinds = self::first_rank(usize::from(index));
+    let mut board: types::Board = [[None; types::BOARD_SIZE]; types::BOARD_SIZE];
+
+    for (col, kind) in kinds.iter().enumerate() {
+        board[0][col] = Some(pieces::PieceType::from_kind(*kind, player::Player::Black));
+        board[1][col] = Some(pieces::PieceType::BlackPawn);
+        board[6][col] = Some(pieces::PieceType::WhitePawn);
+        board[7][col] = Some(pieces::PieceType::from_kind(*kind, player::Player::White));
+    }
+
+    let rook_cols: Vec<usize> = (0..types::BOARD_SIZE)
+        .filter(|col| kinds[*col] == pieces::PieceKind::Rook)
+        .collect();
+
+    Ok(types::ParsedFen {
+        board,
+        state: types::ParsedFenState {
+            en_passant_square: None,
+            on_turn: player::Player::White,
+            castle_white_short: true,
+            castle_white_long: true,
+            castle_black_short: true,
+            castle_black_long: true,
+            half_moves: 0,
+            full_moves: 1,
+            castle_rooks: types::CastleRooks {
+                short_col: rook_cols[1],
+                long_col: rook_cols[0],
+            },
+            chess960: true,
+            pockets: None,
+            variant: variant::VariantKind::Standard,
+            checks: types::Checks::default(),
+        },
+    })
+}
+
+/// Decode the index into piece kinds of the first rank, from column a
+fn first_rank(index: usize) -> [pieces::PieceKind; types::BOARD_SIZE] {
+    let mut rank: [Option<pieces::PieceKind>; types::BOARD_SIZE] = [None; types::BOARD_SIZE];
+
+    // light squared bishop on columns b, d, f, h, dark squared one on a, c, e, g
+    let (index, light_bishop) = (index / 4, index % 4);
+    let (index, dark_bishop) = (index / 4, index % 4);
+    rank[light_bishop * 2 + 1] = Some(pieces::PieceKind::Bishop);
+    rank[dark_bishop * 2] = Some(pieces::PieceKind::Bishop);
+
+    let (index, queen) = (index / 6, index % 6);
+    self::place_free(&mut rank, queen, pieces::PieceKind::Queen);
+
+    // the second knight is placed first, so the first one keeps its free column index
+    let (first_knight, second_knight) = KNIGHT_PLACEMENTS[index];
+    self::place_free(&mut rank, second_knight, pieces::PieceKind::Knight);
+    self::place_free(&mut rank, first_knight, pieces::PieceKind::Knight);
+
+    // remaining three columns take rook, king and rook
+    for kind in [
+        pieces::PieceKind::Rook,
+        pieces::PieceKind::King,
+        pieces::PieceKind::Rook,
+    ] {
+        self::place_free(&mut rank, 0, kind);
+    }
+
+    rank.map(|kind| kind.unwrap())
+}
+
+/// Place the piece on the n-th free column
+fn place_free(
+    rank: &mut [Option<pieces::PieceKind>; types::BOARD_SIZE],
+    n: usize,
+    kind: pieces::PieceKind,
+) {
+    if let Some(square) = rank.iter_mut().filter(|square| square.is_none()).nth(n) {
+        *square = Some(kind);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::bot;
+use crate::chess960;
 use crate::types;
 use std::fmt;
 
@@ -7,7 +9,15 @@ pub enum ChessError {
     InvalidParsedFen(types::ParsedFen),
     InvalidSquare(String),
     OutOfBounds(usize, usize),
-    InvalidMove(String)
+    InvalidMove(String),
+    InvalidSan(String),
+    InvalidPgn(String),
+    InvalidPgnMove(usize, String),
+    InvalidHistory(String),
+    InvalidBotLevel(u8),
+    InvalidBook(String),
+    InvalidTablebase(String),
+    InvalidChess960Index(u16),
 }
 
 impl fmt::Display for ChessError {
@@ -22,6 +32,27 @@ impl fmt::Display for ChessError {
                 write!(f, "Out of bounds. Row: {}, Col: {}", row, col)
             }
             ChessError::InvalidMove(msg) => write!(f, "Invalid move: {}", msg),
+            ChessError::InvalidSan(san) => write!(f, "Invalid SAN: {}", san),
+            ChessError::InvalidPgn(msg) => write!(f, "Invalid PGN: {}", msg),
+            ChessError::InvalidPgnMove(ply, token) => {
+                write!(f, "Invalid PGN move at ply {}: {}", ply, token)
+            }
+            ChessError::InvalidHistory(msg) => write!(f, "Invalid history: {}", msg),
+            ChessError::InvalidBotLevel(level) => write!(
+                f,
+                "Invalid bot level: {}, expected {} to {}",
+                level,
+                bot::MIN_LEVEL,
+                bot::MAX_LEVEL
+            ),
+            ChessError::InvalidBook(msg) => write!(f, "Invalid opening book: {}", msg),
+            ChessError::InvalidTablebase(msg) => write!(f, "Invalid tablebase: {}", msg),
+            ChessError::InvalidChess960Index(index) => write!(
+                f,
+                "Invalid Chess960 position index: {}, expected 0 to {}",
+                index,
+                chess960::POSITION_COUNT - 1
+            ),
         }
     }
 }
@@ -0,0 +1,627 @@
+//! # Evaluation Module
+//!
+//! This module holds the static evaluation of a position in centipawns from White's perspective.
+//!
+//! The score is built from:
+//! - material
+//! - piece-square tables
+//! - pawn structure (doubled, isolated and passed pawns)
+//! - king safety (pawn shield, open files next to the king, attacks on the king zone)
+//! - mobility of knights, bishops, rooks and queens
+//! - material in Crazyhouse pockets
+//!
+//! Every term has a middlegame and an endgame value, blended by the game phase
+//! computed from the remaining non-pawn material.
+//!
+//! All weights live in `EvaluationWeights`, which can be serialized, tuned and loaded at runtime.
+//! The evaluation is static, it doesn't detect checkmate, stalemate or draws.
+//!
+use crate::bitboard;
+use crate::pieces;
+use crate::player;
+use crate::types;
+use serde::{Deserialize, Serialize};
+
+/// Game phase of the starting position, reached with all minor and major pieces on the board
+pub const MAX_PHASE: i32 = 24;
+
+const KNIGHT_PHASE: i32 = 1;
+const BISHOP_PHASE: i32 = 1;
+const ROOK_PHASE: i32 = 2;
+const QUEEN_PHASE: i32 = 4;
+
+/// Weights used by `Chess::evaluate`
+pub static DEFAULT_WEIGHTS: EvaluationWeights = EvaluationWeights {
+    material: PieceValues {
+        pawn: PhaseScore::new(82, 94),
+        knight: PhaseScore::new(337, 281),
+        bishop: PhaseScore::new(365, 297),
+        rook: PhaseScore::new(477, 512),
+        queen: PhaseScore::new(1025, 936),
+    },
+    piece_square_tables: PieceSquareTables {
+        pawn: PhaseTable {
+            middlegame: PAWN_MIDDLEGAME_TABLE,
+            endgame: PAWN_ENDGAME_TABLE,
+        },
+        knight: PhaseTable {
+            middlegame: KNIGHT_TABLE,
+            endgame: KNIGHT_TABLE,
+        },
+        bishop: PhaseTable {
+            middlegame: BISHOP_TABLE,
+            endgame: BISHOP_TABLE,
+        },
+        rook: PhaseTable {
+            middlegame: ROOK_TABLE,
+            endgame: ROOK_TABLE,
+        },
+        queen: PhaseTable {
+            middlegame: QUEEN_TABLE,
+            endgame: QUEEN_TABLE,
+        },
+        king: PhaseTable {
+            middlegame: KING_MIDDLEGAME_TABLE,
+            endgame: KING_ENDGAME_TABLE,
+        },
+    },
+    doubled_pawn: PhaseScore::new(-10, -20),
+    isolated_pawn: PhaseScore::new(-10, -15),
+    passed_pawn: [
+        PhaseScore::new(0, 0),
+        PhaseScore::new(5, 10),
+        PhaseScore::new(5, 15),
+        PhaseScore::new(10, 25),
+        PhaseScore::new(20, 45),
+        PhaseScore::new(35, 75),
+        PhaseScore::new(60, 120),
+        PhaseScore::new(0, 0),
+    ],
+    pawn_shield: PhaseScore::new(10, 0),
+    king_open_file: PhaseScore::new(-15, 0),
+    king_zone_attack: PhaseScore::new(-8, 0),
+    mobility: MobilityWeights {
+        knight: PhaseScore::new(4, 4),
+        bishop: PhaseScore::new(5, 5),
+        rook: PhaseScore::new(2, 4),
+        queen: PhaseScore::new(1, 2),
+    },
+};
+
+/// Piece-square table indexed by `[row][col]` from White's perspective (row 0 is rank 8),
+/// mirrored vertically for Black
+pub type SquareTable = [[i32; types::BOARD_SIZE]; types::BOARD_SIZE];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+/// Middlegame and endgame value of an evaluation term
+pub struct PhaseScore {
+    pub middlegame: i32,
+    pub endgame: i32,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+/// Middlegame and endgame piece-square tables of a piece kind
+pub struct PhaseTable {
+    pub middlegame: SquareTable,
+    pub endgame: SquareTable,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+/// Material value of every piece kind except the king
+pub struct PieceValues {
+    pub pawn: PhaseScore,
+    pub knight: PhaseScore,
+    pub bishop: PhaseScore,
+    pub rook: PhaseScore,
+    pub queen: PhaseScore,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+/// Piece-square tables of every piece kind
+pub struct PieceSquareTables {
+    pub pawn: PhaseTable,
+    pub knight: PhaseTable,
+    pub bishop: PhaseTable,
+    pub rook: PhaseTable,
+    pub queen: PhaseTable,
+    pub king: PhaseTable,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+/// Value of every square a piece can move to, excluding squares of own pieces
+/// and squares attacked by opponent pawns
+pub struct MobilityWeights {
+    pub knight: PhaseScore,
+    pub bishop: PhaseScore,
+    pub rook: PhaseScore,
+    pub queen: PhaseScore,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+/// Weights of all evaluation terms. Positive values are bonuses, negative values penalties.
+pub struct EvaluationWeights {
+    pub material: PieceValues,
+    pub piece_square_tables: PieceSquareTables,
+    /// Every pawn on a file beyond the first one
+    pub doubled_pawn: PhaseScore,
+    /// Pawn without own pawns on adjacent files
+    pub isolated_pawn: PhaseScore,
+    /// Pawn without opponent pawns in front of it on the same or adjacent files,
+    /// indexed by rank from the owner's perspective (0 is the first rank)
+    pub passed_pawn: [PhaseScore; types::BOARD_SIZE],
+    /// Own pawn one or two ranks in front of the king, on its file or adjacent files
+    pub pawn_shield: PhaseScore,
+    /// File of the king or adjacent file without own pawns
+    pub king_open_file: PhaseScore,
+    /// Square next to the king attacked by an opponent knight, bishop, rook or queen
+    pub king_zone_attack: PhaseScore,
+    pub mobility: MobilityWeights,
+}
+
+impl PhaseScore {
+    pub const fn new(middlegame: i32, endgame: i32) -> Self {
+        Self {
+            middlegame,
+            endgame,
+        }
+    }
+}
+
+impl std::ops::AddAssign for PhaseScore {
+    fn add_assign(&mut self, other: Self) {
+        self.middlegame += other.middlegame;
+        self.endgame += other.endgame;
+    }
+}
+
+impl std::ops::SubAssign for PhaseScore {
+    fn sub_assign(&mut self, other: Self) {
+        self.middlegame -= other.middlegame;
+        self.endgame -= other.endgame;
+    }
+}
+
+impl std::ops::Mul<i32> for PhaseScore {
+    type Output = Self;
+
+    fn mul(self, count: i32) -> Self {
+        Self::new(self.middlegame * count, self.endgame * count)
+    }
+}
+
+impl Default for EvaluationWeights {
+    fn default() -> Self {
+        DEFAULT_WEIGHTS
+    }
+}
+
+/// Evaluate a position in centipawns from White's perspective
+pub fn evaluate(game: types::ParsedFen, weights: &EvaluationWeights) -> i32 {
+    self::evaluate_position(&bitboard::Position::from(game), weights)
+}
+
+/// Evaluate a bitboard position in centipawns from White's perspective
+pub fn evaluate_position(position: &bitboard::Position, weights: &EvaluationWeights) -> i32 {
+    let mut score = self::evaluate_player(position, player::Player::White, weights);
+    score -= self::evaluate_player(position, player::Player::Black, weights);
+
+    let phase = self::game_phase(position);
+    (score.middlegame * phase + score.endgame * (MAX_PHASE - phase)) / MAX_PHASE
+}
+
+/// Get phase of the game from `MAX_PHASE` (opening) down to 0 (only pawns and kings)
+pub fn game_phase(position: &bitboard::Position) -> i32 {
+    let count = |kind| {
+        let white = position.pieces(pieces::PieceType::from_kind(kind, player::Player::White));
+        let black = position.pieces(pieces::PieceType::from_kind(kind, player::Player::Black));
+        (white | black).count_ones() as i32
+    };
+
+    let phase = count(pieces::PieceKind::Knight) * KNIGHT_PHASE
+        + count(pieces::PieceKind::Bishop) * BISHOP_PHASE
+        + count(pieces::PieceKind::Rook) * ROOK_PHASE
+        + count(pieces::PieceKind::Queen) * QUEEN_PHASE;
+
+    phase.min(MAX_PHASE)
+}
+
+fn evaluate_player(
+    position: &bitboard::Position,
+    player: player::Player,
+    weights: &EvaluationWeights,
+) -> PhaseScore {
+    let mut score = PhaseScore::default();
+
+    score += self::evaluate_pieces(position, player, weights);
+    score += self::evaluate_pawn_structure(position, player, weights);
+    score += self::evaluate_king_shelter(position, player, weights);
+
+    // pieces in the Crazyhouse pocket count as material
+    if let Some(pockets) = position.state.pockets {
+        let pocket = pockets.pocket(player);
+
+        for kind in types::Pocket::KINDS {
+            score += self::material(kind, weights) * i32::from(pocket.count(kind));
+        }
+    }
+
+    score
+}
+
+/// Material, piece-square tables, mobility and attacks on the opponent king zone
+fn evaluate_pieces(
+    position: &bitboard::Position,
+    player: player::Player,
+    weights: &EvaluationWeights,
+) -> PhaseScore {
+    let opponent = player.opponent();
+    let occupancy = position.all_occupancy();
+    let own = position.occupancy(player);
+
+    let opponent_pawns = position.pieces(pieces::PieceType::from_kind(
+        pieces::PieceKind::Pawn,
+        opponent,
+    ));
+    let opponent_pawn_attacks = bitboard::squares(opponent_pawns)
+        .fold(bitboard::EMPTY, |attacks, sq| {
+            attacks | bitboard::pawn_attacks(sq, opponent)
+        });
+    let opponent_king_zone = position
+        .king_square(opponent)
+        .map_or(bitboard::EMPTY, |sq| {
+            bitboard::king_attacks(sq) | bitboard::square_bit(sq)
+        });
+
+    let mut score = PhaseScore::default();
+
+    for kind in [
+        pieces::PieceKind::Pawn,
+        pieces::PieceKind::Knight,
+        pieces::PieceKind::Bishop,
+        pieces::PieceKind::Rook,
+        pieces::PieceKind::Queen,
+        pieces::PieceKind::King,
+    ] {
+        let piece = pieces::PieceType::from_kind(kind, player);
+        let table = self::piece_square_table(kind, weights);
+
+        for sq in bitboard::squares(position.pieces(piece)) {
+            score += self::material(kind, weights);
+            score += self::square_value(table, sq, player);
+
+            let (attacks, mobility) = match kind {
+                pieces::PieceKind::Knight => {
+                    (bitboard::knight_attacks(sq), weights.mobility.knight)
+                }
+                pieces::PieceKind::Bishop => (
+                    bitboard::bishop_attacks(sq, occupancy),
+                    weights.mobility.bishop,
+                ),
+                pieces::PieceKind::Rook => {
+                    (bitboard::rook_attacks(sq, occupancy), weights.mobility.rook)
+                }
+                pieces::PieceKind::Queen => (
+                    bitboard::rook_attacks(sq, occupancy) | bitboard::bishop_attacks(sq, occupancy),
+                    weights.mobility.queen,
+                ),
+                pieces::PieceKind::Pawn | pieces::PieceKind::King => continue,
+            };
+
+            let moves = attacks & !own & !opponent_pawn_attacks;
+            score += mobility * moves.count_ones() as i32;
+
+            // penalty of the opponent is a bonus of the attacking player
+            score -= weights.king_zone_attack * (attacks & opponent_king_zone).count_ones() as i32;
+        }
+    }
+
+    score
+}
+
+/// Doubled, isolated and passed pawns
+fn evaluate_pawn_structure(
+    position: &bitboard::Position,
+    player: player::Player,
+    weights: &EvaluationWeights,
+) -> PhaseScore {
+    let pawns = position.pieces(pieces::PieceType::from_kind(
+        pieces::PieceKind::Pawn,
+        player,
+    ));
+    let opponent_pawns = position.pieces(pieces::PieceType::from_kind(
+        pieces::PieceKind::Pawn,
+        player.opponent(),
+    ));
+
+    let mut score = PhaseScore::default();
+
+    for file in FILE_MASKS {
+        let count = (pawns & file).count_ones() as i32;
+        if count > 1 {
+            score += weights.doubled_pawn * (count - 1);
+        }
+    }
+
+    for sq in bitboard::squares(pawns) {
+        let col = sq % types::BOARD_SIZE;
+
+        if pawns & ADJACENT_FILE_MASKS[col] == bitboard::EMPTY {
+            score += weights.isolated_pawn;
+        }
+
+        if opponent_pawns & PASSED_PAWN_MASKS[bitboard::color_index(player)][sq] == bitboard::EMPTY
+        {
+            score += weights.passed_pawn[self::relative_rank(sq, player)];
+        }
+    }
+
+    score
+}
+
+/// Pawn shield and open files around the king
+fn evaluate_king_shelter(
+    position: &bitboard::Position,
+    player: player::Player,
+    weights: &EvaluationWeights,
+) -> PhaseScore {
+    let Some(king_sq) = position.king_square(player) else {
+        return PhaseScore::default();
+    };
+
+    let pawns = position.pieces(pieces::PieceType::from_kind(
+        pieces::PieceKind::Pawn,
+        player,
+    ));
+    let king_row = (king_sq / types::BOARD_SIZE) as isize;
+    let king_col = (king_sq % types::BOARD_SIZE) as isize;
+    let forward = match player {
+        player::Player::White => -1,
+        player::Player::Black => 1,
+    };
+
+    let mut score = PhaseScore::default();
+
+    for col in king_col - 1..=king_col + 1 {
+        if !(0..types::BOARD_SIZE as isize).contains(&col) {
+            continue;
+        }
+
+        if pawns & FILE_MASKS[col as usize] == bitboard::EMPTY {
+            score += weights.king_open_file;
+        }
+
+        for distance in 1..=2 {
+            let row = king_row + forward * distance;
+            if !(0..types::BOARD_SIZE as isize).contains(&row) {
+                continue;
+            }
+
+            let sq = bitboard::square_index(row as usize, col as usize);
+            if pawns & bitboard::square_bit(sq) != bitboard::EMPTY {
+                score += weights.pawn_shield;
+            }
+        }
+    }
+
+    score
+}
+
+fn material(kind: pieces::PieceKind, weights: &EvaluationWeights) -> PhaseScore {
+    match kind {
+        pieces::PieceKind::Pawn => weights.material.pawn,
+        pieces::PieceKind::Knight => weights.material.knight,
+        pieces::PieceKind::Bishop => weights.material.bishop,
+        pieces::PieceKind::Rook => weights.material.rook,
+        pieces::PieceKind::Queen => weights.material.queen,
+        pieces::PieceKind::King => PhaseScore::default(),
+    }
+}
+
+fn piece_square_table(kind: pieces::PieceKind, weights: &EvaluationWeights) -> &PhaseTable {
+    let tables = &weights.piece_square_tables;
+
+    match kind {
+        pieces::PieceKind::Pawn => &tables.pawn,
+        pieces::PieceKind::Knight => &tables.knight,
+        pieces::PieceKind::Bishop => &tables.bishop,
+        pieces::PieceKind::Rook => &tables.rook,
+        pieces::PieceKind::Queen => &tables.queen,
+        pieces::PieceKind::King => &tables.king,
+    }
+}
+
+fn square_value(table: &PhaseTable, sq: usize, player: player::Player) -> PhaseScore {
+    let col = sq % types::BOARD_SIZE;
+    let row = match player {
+        player::Player::White => sq / types::BOARD_SIZE,
+        player::Player::Black => types::MAX_SIZE_INDEX - sq / types::BOARD_SIZE,
+    };
+
+    PhaseScore::new(table.middlegame[row][col], table.endgame[row][col])
+}
+
+/// Rank of the square from the player's perspective, 0 is the first rank
+fn relative_rank(sq: usize, player: player::Player) -> usize {
+    let row = sq / types::BOARD_SIZE;
+
+    match player {
+        player::Player::White => types::MAX_SIZE_INDEX - row,
+        player::Player::Black => row,
+    }
+}
+
+//
+//
+// # Masks
+//
+//
+
+static FILE_MASKS: [bitboard::Bitboard; types::BOARD_SIZE] = self::file_masks();
+static ADJACENT_FILE_MASKS: [bitboard::Bitboard; types::BOARD_SIZE] = self::adjacent_file_masks();
+static PASSED_PAWN_MASKS: [[bitboard::Bitboard; 64]; 2] = self::passed_pawn_masks();
+
+const fn file_masks() -> [bitboard::Bitboard; types::BOARD_SIZE] {
+    let mut masks = [bitboard::EMPTY; types::BOARD_SIZE];
+    let mut col = 0;
+
+    while col < types::BOARD_SIZE {
+        let mut row = 0;
+        while row < types::BOARD_SIZE {
+            masks[col] |= 1 << (row * types::BOARD_SIZE + col);
+            row += 1;
+        }
+        col += 1;
+    }
+
+    masks
+}
+
+const fn adjacent_file_masks() -> [bitboard::Bitboard; types::BOARD_SIZE] {
+    let files = self::file_masks();
+    let mut masks = [bitboard::EMPTY; types::BOARD_SIZE];
+    let mut col = 0;
+
+    while col < types::BOARD_SIZE {
+        if col > 0 {
+            masks[col] |= files[col - 1];
+        }
+        if col < types::MAX_SIZE_INDEX {
+            masks[col] |= files[col + 1];
+        }
+        col += 1;
+    }
+
+    masks
+}
+
+/// Squares in front of a pawn on the same and adjacent files, indexed by color and square
+const fn passed_pawn_masks() -> [[bitboard::Bitboard; 64]; 2] {
+    let mut masks = [[bitboard::EMPTY; 64]; 2];
+    let mut sq = 0;
+
+    while sq < 64 {
+        let row = sq / types::BOARD_SIZE;
+        let col = sq % types::BOARD_SIZE;
+
+        let mut target_row = 0;
+        while target_row < types::BOARD_SIZE {
+            let mut target_col = col.saturating_sub(1);
+            while target_col <= col + 1 && target_col < types::BOARD_SIZE {
+                let bit = 1 << (target_row * types::BOARD_SIZE + target_col);
+                if target_row < row {
+                    masks[0][sq] |= bit;
+                }
+                if target_row > row {
+                    masks[1][sq] |= bit;
+                }
+                target_col += 1;
+            }
+            target_row += 1;
+        }
+        sq += 1;
+    }
+
+    masks
+}
+
+//
+//
+// # Default piece-square tables
+//
+//
+
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME_TABLE: SquareTable = [
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [ 50,  50,  50,  50,  50,  50,  50,  50],
+    [ 10,  10,  20,  30,  30,  20,  10,  10],
+    [  5,   5,  10,  25,  25,  10,   5,   5],
+    [  0,   0,   0,  20,  20,   0,   0,   0],
+    [  5,  -5, -10,   0,   0, -10,  -5,   5],
+    [  5,  10,  10, -20, -20,  10,  10,   5],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+];
+
+#[rustfmt::skip]
+const PAWN_ENDGAME_TABLE: SquareTable = [
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [ 40,  40,  40,  40,  40,  40,  40,  40],
+    [ 25,  25,  25,  25,  25,  25,  25,  25],
+    [ 15,  15,  15,  15,  15,  15,  15,  15],
+    [  5,   5,   5,   5,   5,   5,   5,   5],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: SquareTable = [
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+    [-40, -20,   0,   0,   0,   0, -20, -40],
+    [-30,   0,  10,  15,  15,  10,   0, -30],
+    [-30,   5,  15,  20,  20,  15,   5, -30],
+    [-30,   0,  15,  20,  20,  15,   0, -30],
+    [-30,   5,  10,  15,  15,  10,   5, -30],
+    [-40, -20,   0,   5,   5,   0, -20, -40],
+    [-50, -40, -30, -30, -30, -30, -40, -50],
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: SquareTable = [
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+    [-10,   0,   0,   0,   0,   0,   0, -10],
+    [-10,   0,   5,  10,  10,   5,   0, -10],
+    [-10,   5,   5,  10,  10,   5,   5, -10],
+    [-10,   0,  10,  10,  10,  10,   0, -10],
+    [-10,  10,  10,  10,  10,  10,  10, -10],
+    [-10,   5,   0,   0,   0,   0,   5, -10],
+    [-20, -10, -10, -10, -10, -10, -10, -20],
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: SquareTable = [
+    [  0,   0,   0,   0,   0,   0,   0,   0],
+    [  5,  10,  10,  10,  10,  10,  10,   5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [ -5,   0,   0,   0,   0,   0,   0,  -5],
+    [  0,   0,   0,   5,   5,   0,   0,   0],
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: SquareTable = [
+    [-20, -10, -10,  -5,  -5, -10, -10, -20],
+    [-10,   0,   0,   0,   0,   0,   0, -10],
+    [-10,   0,   5,   5,   5,   5,   0, -10],
+    [ -5,   0,   5,   5,   5,   5,   0,  -5],
+    [  0,   0,   5,   5,   5,   5,   0,  -5],
+    [-10,   5,   5,   5,   5,   5,   0, -10],
+    [-10,   0,   5,   0,   0,   0,   0, -10],
+    [-20, -10, -10,  -5,  -5, -10, -10, -20],
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME_TABLE: SquareTable = [
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-30, -40, -40, -50, -50, -40, -40, -30],
+    [-20, -30, -30, -40, -40, -30, -30, -20],
+    [-10, -20, -20, -20, -20, -20, -20, -10],
+    [ 20,  20,   0,   0,   0,   0,  20,  20],
+    [ 20,  30,  10,   0,   0,  10,  30,  20],
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME_TABLE: SquareTable = [
+    [-50, -40, -30, -20, -20, -30, -40, -50],
+    [-30, -20, -10,   0,   0, -10, -20, -30],
+    [-30, -10,  20,  30,  30,  20, -10, -30],
+    [-30, -10,  30,  40,  40,  30, -10, -30],
+    [-30, -10,  30,  40,  40,  30, -10, -30],
+    [-30, -10,  20,  30,  30,  20, -10, -30],
+    [-30, -30,   0,   0,   0,   0, -30, -30],
+    [-50, -30, -30, -30, -30, -30, -30, -50],
+];
@@ -12,33 +12,104 @@
 //! - Are there no pawns on the first or eighth rank?
 //! - Are kings at least 1 square apart?
 //!
+//! King rules follow the variant, eg. Atomic allows adjacent kings and a missing exploded king.
+//!
+//! ## Castling
+//! Besides the standard `KQkq`, castling rights of Chess960 positions are read in both common forms:
+//! - X-FEN: `K` / `Q` is the outermost rook on the side of the king, a file letter picks another rook
+//! - Shredder-FEN: file letters of the castling rooks only (eg. `HAha`)
+//!
+//! Chess960 positions are written in X-FEN, see `stringify_shredder` for Shredder-FEN.
+//! When the king and the castling rooks stand on the standard squares (eg. Chess960 position 518),
+//! X-FEN would read back as standard chess, so rook file letters are written instead (`HAha`).
+//!
+//! ## Crazyhouse
+//! Pockets follow the board in brackets, white pieces first (eg. `.../RNBQKBNR[Qp] w ...`,
+//! `[]` for empty pockets). Pieces promoted from pawns are marked with `~` (eg. `Q~`).
+//! FEN without brackets is a game without drops.
+//!
+//! ## Three-check
+//! Checks given by white and black follow the move counters (eg. `... 0 1 +2+1`),
+//! FEN with the checks is a Three-check game.
+//!
 use crate::err;
 use crate::pieces;
 use crate::player;
 use crate::square;
 use crate::types;
+use crate::variant;
 
 /// Initial starting game position
 pub const INITIAL_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
-const EXACT_KINGS_COUNT: u8 = 1;
 const MAX_DISTANCE_BETWEEN_KINGS: u8 = 2;
+const KING_INITIAL_COL: usize = 4;
+const POCKET_START: char = '[';
+const POCKET_END: char = ']';
+const PROMOTED: char = '~';
+const CHECKS_PREFIX: char = '+';
 
 /// Parse FEN string into structured data.
 pub(super) fn parse(fen: &str) -> types::ChessResult<types::ParsedFen> {
+    self::parse_game(fen, variant::VariantKind::Standard)
+}
+
+/// Parse FEN string into a game of the variant, checks given are allowed in Three-check only.
+pub(super) fn parse_with_variant(
+    fen: &str,
+    variant: variant::VariantKind,
+) -> types::ChessResult<types::ParsedFen> {
+    let mut game = self::parse_game(fen, variant)?;
+
+    if game.state.variant == variant::VariantKind::ThreeCheck
+        && variant != variant::VariantKind::ThreeCheck
+    {
+        return Err(get_fen_error(fen));
+    }
+    game.state.variant = variant;
+
+    Ok(game)
+}
+
+/// Parse FEN string with kings placed by the rules of the variant
+fn parse_game(fen: &str, variant: variant::VariantKind) -> types::ChessResult<types::ParsedFen> {
     let splitted_fen = self::split_by_first_whitespace(fen)?;
-    let board = self::fen_to_board(splitted_fen.0)?;
-    let state = self::fen_to_state(splitted_fen.1)?;
+    let (board_part, pocket_part) = match splitted_fen.0.strip_suffix(POCKET_END) {
+        Some(rest) => rest
+            .split_once(POCKET_START)
+            .map(|(board, pocket)| (board, Some(pocket)))
+            .ok_or_else(|| get_fen_error(splitted_fen.0))?,
+        None => (splitted_fen.0, None),
+    };
+    let (board, promoted) = self::fen_to_board(board_part, variant)?;
+    let mut state = self::fen_to_state(splitted_fen.1, &board)?;
+
+    state.pockets = match pocket_part {
+        Some(pocket_part) => Some(self::fen_to_pockets(pocket_part, promoted)?),
+        None if promoted.iter().flatten().any(|is_promoted| *is_promoted) => {
+            return Err(get_fen_error(board_part))
+        }
+        None => None,
+    };
 
-    return Ok(types::ParsedFen { board, state });
+    Ok(types::ParsedFen { board, state })
 }
 
 /// Convert structured FEN data back to FEN notation string.
 pub(super) fn stringify(parsed_fen: &types::ParsedFen) -> types::ChessResult<String> {
     let board = self::stringify_to_board(parsed_fen)?;
-    let state = self::stringify_to_state(parsed_fen)?;
+    let state = self::stringify_to_state(parsed_fen, false)?;
+
+    Ok(format!("{} {}", board, state))
+}
+
+/// Convert structured FEN data to FEN notation string with Shredder-FEN castling rights,
+/// written as file letters of the castling rooks (eg. `HAha`).
+pub(super) fn stringify_shredder(parsed_fen: &types::ParsedFen) -> types::ChessResult<String> {
+    let board = self::stringify_to_board(parsed_fen)?;
+    let state = self::stringify_to_state(parsed_fen, true)?;
 
-    return Ok(format!("{} {}", board, state));
+    Ok(format!("{} {}", board, state))
 }
 
 /// Stringify the parsed_fen.board into fen string
@@ -67,6 +138,14 @@ fn stringify_to_board(parsed_fen: &types::ParsedFen) -> types::ChessResult<Strin
 
                 fen.push(piece_char.to_char());
 
+                if parsed_fen
+                    .state
+                    .pockets
+                    .is_some_and(|pockets| pockets.promoted[row_idx][col])
+                {
+                    fen.push(PROMOTED);
+                }
+
                 if validate_piece_placement(
                     piece_char.to_char(),
                     col,
@@ -95,17 +174,33 @@ fn stringify_to_board(parsed_fen: &types::ParsedFen) -> types::ChessResult<Strin
         &black_king_position,
         &mut black_kings_count,
         &mut white_kings_count,
+        parsed_fen.state.variant,
     )
     .is_err()
     {
         return Err(get_parsed_fen_error(parsed_fen));
     }
 
-    return Ok(fen);
+    if let Some(pockets) = parsed_fen.state.pockets {
+        fen.push(POCKET_START);
+
+        for player in [player::Player::White, player::Player::Black] {
+            let pocket = pockets.pocket(player);
+
+            for kind in types::Pocket::KINDS {
+                let piece = pieces::PieceType::from_kind(kind, player).to_char();
+                fen.extend(std::iter::repeat_n(piece, usize::from(pocket.count(kind))));
+            }
+        }
+
+        fen.push(POCKET_END);
+    }
+
+    Ok(fen)
 }
 
 // Stringify the parsed_fen.state into fen string
-fn stringify_to_state(parsed_fen: &types::ParsedFen) -> types::ChessResult<String> {
+fn stringify_to_state(parsed_fen: &types::ParsedFen, shredder: bool) -> types::ChessResult<String> {
     let types::ParsedFenState {
         full_moves,
         half_moves,
@@ -115,6 +210,7 @@ fn stringify_to_state(parsed_fen: &types::ParsedFen) -> types::ChessResult<Strin
         castle_black_short,
         castle_white_long,
         castle_white_short,
+        ..
     } = parsed_fen.state;
 
     let mut fen = String::new();
@@ -123,20 +219,29 @@ fn stringify_to_state(parsed_fen: &types::ParsedFen) -> types::ChessResult<Strin
 
     fen.push(' ');
 
-    if !castle_black_long && !castle_black_short && !castle_white_long && !castle_white_short {
+    let rights = [
+        (castle_white_short, player::Player::White, true),
+        (castle_white_long, player::Player::White, false),
+        (castle_black_short, player::Player::Black, true),
+        (castle_black_long, player::Player::Black, false),
+    ];
+
+    // X-FEN of a Chess960 game with standard king and rook squares reads back as standard chess
+    let shredder = shredder
+        || (parsed_fen.state.chess960
+            && parsed_fen.state.castle_rooks == types::CastleRooks::default()
+            && rights
+                .iter()
+                .filter(|(allowed, _, _)| *allowed)
+                .all(|(_, player, _)| {
+                    self::king_col(&parsed_fen.board, *player) == Some(KING_INITIAL_COL)
+                }));
+
+    if rights.iter().all(|(allowed, _, _)| !allowed) {
         fen.push('-')
     } else {
-        if castle_white_short {
-            fen.push(pieces::PieceType::WhiteKing.to_char())
-        }
-        if castle_white_long {
-            fen.push(pieces::PieceType::WhiteQueen.to_char())
-        }
-        if castle_black_short {
-            fen.push(pieces::PieceType::BlackKing.to_char())
-        }
-        if castle_black_long {
-            fen.push(pieces::PieceType::BlackQueen.to_char())
+        for (_, player, is_short) in rights.into_iter().filter(|(allowed, _, _)| *allowed) {
+            fen.push(self::castling_char(parsed_fen, player, is_short, shredder));
         }
     }
 
@@ -159,14 +264,26 @@ fn stringify_to_state(parsed_fen: &types::ParsedFen) -> types::ChessResult<Strin
 
     fen.push_str(&full_moves.to_string());
 
-    return Ok(fen);
+    if parsed_fen.state.variant == variant::VariantKind::ThreeCheck {
+        let checks = parsed_fen.state.checks;
+        fen.push_str(&format!(
+            " {}{}{}{}",
+            CHECKS_PREFIX, checks.white, CHECKS_PREFIX, checks.black
+        ));
+    }
+
+    Ok(fen)
 }
 
 ///
-/// Parse board from first part of fen.
+/// Parse board from first part of fen, together with squares of pieces marked as promoted.
 ///
-fn fen_to_board(first_fen_part: &str) -> types::ChessResult<types::Board> {
+fn fen_to_board(
+    first_fen_part: &str,
+    variant: variant::VariantKind,
+) -> types::ChessResult<(types::Board, [[bool; types::BOARD_SIZE]; types::BOARD_SIZE])> {
     let mut board: types::Board = [[None; types::BOARD_SIZE]; types::BOARD_SIZE];
+    let mut promoted = [[false; types::BOARD_SIZE]; types::BOARD_SIZE];
     let mut row = 0;
     let mut col = 0;
 
@@ -190,6 +307,24 @@ fn fen_to_board(first_fen_part: &str) -> types::ChessResult<types::Board> {
                     return Err(get_fen_error(first_fen_part));
                 }
             }
+            PROMOTED => {
+                // marks the preceding piece, kings and pawns are never promoted
+                let is_promotable = col
+                    .checked_sub(1)
+                    .and_then(|prev_col| board[row][prev_col])
+                    .is_some_and(|piece| {
+                        !matches!(
+                            piece.piece_kind(),
+                            pieces::PieceKind::King | pieces::PieceKind::Pawn
+                        )
+                    });
+
+                if !is_promotable || promoted[row][col - 1] {
+                    return Err(get_fen_error(first_fen_part));
+                }
+
+                promoted[row][col - 1] = true;
+            }
             '1'..='8' => {
                 col += ch.to_digit(10).unwrap() as usize;
                 if col > types::BOARD_SIZE {
@@ -234,19 +369,46 @@ fn fen_to_board(first_fen_part: &str) -> types::ChessResult<types::Board> {
         &black_king_position,
         &mut black_kings_count,
         &mut white_kings_count,
+        variant,
     )
     .is_err()
     {
         return Err(get_fen_error(first_fen_part));
     }
 
-    Ok(board)
+    Ok((board, promoted))
+}
+
+///
+/// Parse Crazyhouse pockets from the bracketed part of fen, eg. `Qp` in `[Qp]`.
+///
+fn fen_to_pockets(
+    pocket_part: &str,
+    promoted: [[bool; types::BOARD_SIZE]; types::BOARD_SIZE],
+) -> types::ChessResult<types::Pockets> {
+    let mut pockets = types::Pockets {
+        promoted,
+        ..types::Pockets::default()
+    };
+
+    for ch in pocket_part.chars() {
+        let piece = pieces::PieceType::from_char(ch)
+            .filter(|piece| piece.piece_kind() != pieces::PieceKind::King)
+            .ok_or_else(|| get_fen_error(pocket_part))?;
+
+        pockets.pocket_mut(piece.color()).add(piece.piece_kind());
+    }
+
+    Ok(pockets)
 }
 
 ///
 /// Parse state from second part of fen.
 ///
-fn fen_to_state(second_fen_part: &str) -> types::ChessResult<types::ParsedFenState> {
+fn fen_to_state(
+    second_fen_part: &str,
+    board: &types::Board,
+) -> types::ChessResult<types::ParsedFenState> {
     let array_from_values: Vec<&str> = second_fen_part.split_whitespace().collect();
 
     if array_from_values.len() < 5 {
@@ -262,6 +424,11 @@ fn fen_to_state(second_fen_part: &str) -> types::ChessResult<types::ParsedFenSta
         castle_black_long: false,
         half_moves: 0,
         full_moves: 0,
+        castle_rooks: types::CastleRooks::default(),
+        chess960: false,
+        pockets: None,
+        variant: variant::VariantKind::Standard,
+        checks: types::Checks::default(),
     };
 
     // Parse current turn
@@ -276,23 +443,62 @@ fn fen_to_state(second_fen_part: &str) -> types::ChessResult<types::ParsedFenSta
     // Parse castling availability
     let castling = array_from_values[1];
     if castling != "-" {
+        // rook columns of the short and long side, shared by both players
+        let mut rook_cols: [Option<usize>; 2] = [None, None];
+
         for ch in castling.chars() {
-            match ch {
-                c if c == pieces::PieceType::WhiteKing.to_char() => {
-                    initial_state.castle_white_short = true
-                }
-                c if c == pieces::PieceType::WhiteQueen.to_char() => {
-                    initial_state.castle_white_long = true
-                }
-                c if c == pieces::PieceType::BlackKing.to_char() => {
-                    initial_state.castle_black_short = true
-                }
-                c if c == pieces::PieceType::BlackQueen.to_char() => {
-                    initial_state.castle_black_long = true
+            let player = match ch.is_ascii_uppercase() {
+                true => player::Player::White,
+                false => player::Player::Black,
+            };
+            let king_col = self::king_col(board, player);
+
+            let (is_short, rook_col) = match ch.to_ascii_uppercase() {
+                'K' => (
+                    true,
+                    self::outermost_rook_col(board, player, king_col, true),
+                ),
+                'Q' => (
+                    false,
+                    self::outermost_rook_col(board, player, king_col, false),
+                ),
+                file @ 'A'..='H' => {
+                    let rook_col = (file as u8 - b'A') as usize;
+                    let king_col = king_col
+                        .filter(|king_col| *king_col != rook_col)
+                        .ok_or_else(|| get_fen_error(second_fen_part))?;
+
+                    initial_state.chess960 = true;
+                    (rook_col > king_col, rook_col)
                 }
                 _ => return Err(get_fen_error(second_fen_part)),
+            };
+
+            let side_col = &mut rook_cols[usize::from(!is_short)];
+            if side_col.is_some_and(|col| col != rook_col) {
+                return Err(get_fen_error(second_fen_part));
+            }
+            *side_col = Some(rook_col);
+
+            // castling from another than the standard king column happens only in Chess960
+            if king_col.is_some_and(|king_col| king_col != KING_INITIAL_COL) {
+                initial_state.chess960 = true;
+            }
+
+            match (player, is_short) {
+                (player::Player::White, true) => initial_state.castle_white_short = true,
+                (player::Player::White, false) => initial_state.castle_white_long = true,
+                (player::Player::Black, true) => initial_state.castle_black_short = true,
+                (player::Player::Black, false) => initial_state.castle_black_long = true,
             }
         }
+
+        let default_rooks = types::CastleRooks::default();
+        initial_state.castle_rooks = types::CastleRooks {
+            short_col: rook_cols[0].unwrap_or(default_rooks.short_col),
+            long_col: rook_cols[1].unwrap_or(default_rooks.long_col),
+        };
+        initial_state.chess960 |= initial_state.castle_rooks != default_rooks;
     }
 
     // Parse en passant target square
@@ -313,21 +519,134 @@ fn fen_to_state(second_fen_part: &str) -> types::ChessResult<types::ParsedFenSta
         .parse::<u32>()
         .map_err(|_| get_fen_error(second_fen_part))?;
 
+    // Parse Three-check checks given
+    if let Some(checks) = array_from_values
+        .get(5)
+        .filter(|checks| checks.starts_with(CHECKS_PREFIX))
+    {
+        initial_state.checks = self::fen_to_checks(checks)?;
+        initial_state.variant = variant::VariantKind::ThreeCheck;
+    }
+
     Ok(initial_state)
 }
 
+///
+/// Parse Three-check checks given by white and black, eg. `+2+1`.
+///
+fn fen_to_checks(checks_part: &str) -> types::ChessResult<types::Checks> {
+    let (white, black) = checks_part
+        .strip_prefix(CHECKS_PREFIX)
+        .and_then(|checks| checks.split_once(CHECKS_PREFIX))
+        .ok_or_else(|| get_fen_error(checks_part))?;
+    let parse_count = |count: &str| {
+        count
+            .parse::<u8>()
+            .ok()
+            .filter(|count| *count <= variant::THREE_CHECK_WIN)
+            .ok_or_else(|| get_fen_error(checks_part))
+    };
+
+    Ok(types::Checks {
+        white: parse_count(white)?,
+        black: parse_count(black)?,
+    })
+}
+
+///
+/// Get castling right character, `K` / `Q` (lowercase for black) if the castling rook is
+/// the outermost rook on its side of the king, its file letter otherwise.
+///
+fn castling_char(
+    parsed_fen: &types::ParsedFen,
+    player: player::Player,
+    is_short: bool,
+    shredder: bool,
+) -> char {
+    let state = parsed_fen.state;
+    let rook_col = state.castle_rooks.col(is_short);
+    let king_col = self::king_col(&parsed_fen.board, player);
+    let is_outermost =
+        self::outermost_rook_col(&parsed_fen.board, player, king_col, is_short) == rook_col;
+
+    let ch = match (state.chess960 && (shredder || !is_outermost), is_short) {
+        (true, _) => (b'A' + rook_col as u8) as char,
+        (false, true) => pieces::PieceType::WhiteKing.to_char(),
+        (false, false) => pieces::PieceType::WhiteQueen.to_char(),
+    };
+
+    match player {
+        player::Player::White => ch,
+        player::Player::Black => ch.to_ascii_lowercase(),
+    }
+}
+
+///
+/// Get column of the player's king on its back row.
+///
+fn king_col(board: &types::Board, player: player::Player) -> Option<usize> {
+    let king = Some(pieces::PieceType::from_kind(
+        pieces::PieceKind::King,
+        player,
+    ));
+    board[self::back_row(player)]
+        .iter()
+        .position(|piece| *piece == king)
+}
+
+///
+/// Get column of the rook farthest from the king on the side, standard rook column
+/// if there is no such rook or king.
+///
+fn outermost_rook_col(
+    board: &types::Board,
+    player: player::Player,
+    king_col: Option<usize>,
+    is_short: bool,
+) -> usize {
+    let rook = Some(pieces::PieceType::from_kind(
+        pieces::PieceKind::Rook,
+        player,
+    ));
+    let row = &board[self::back_row(player)];
+    let default_col = types::CastleRooks::default().col(is_short);
+
+    let Some(king_col) = king_col else {
+        return default_col;
+    };
+
+    let rook_col = match is_short {
+        true => (king_col + 1..types::BOARD_SIZE)
+            .rev()
+            .find(|col| row[*col] == rook),
+        false => (0..king_col).find(|col| row[*col] == rook),
+    };
+
+    rook_col.unwrap_or(default_col)
+}
+
+///
+/// Get row of the first rank of the player.
+///
+fn back_row(player: player::Player) -> usize {
+    match player {
+        player::Player::White => types::MAX_SIZE_INDEX,
+        player::Player::Black => 0,
+    }
+}
+
 ///
 /// Get error for invalid fen.
 ///
 fn get_fen_error(fen: &str) -> err::ChessError {
-    return err::ChessError::InvalidFen(fen.to_string());
+    err::ChessError::InvalidFen(fen.to_string())
 }
 
 ///
 /// Get error for invalid parsed fen.
 ///
 fn get_parsed_fen_error(parsed_fen: &types::ParsedFen) -> err::ChessError {
-    return err::ChessError::InvalidParsedFen(parsed_fen.clone());
+    err::ChessError::InvalidParsedFen(*parsed_fen)
 }
 
 ///
@@ -335,7 +654,7 @@ fn get_parsed_fen_error(parsed_fen: &types::ParsedFen) -> err::ChessError {
 /// First part is board, second part is state.
 ///
 fn split_by_first_whitespace(fen: &str) -> types::ChessResult<(&str, &str)> {
-    return fen.split_once(" ").ok_or(get_fen_error(fen));
+    fen.split_once(" ").ok_or(get_fen_error(fen))
 }
 
 /// Validates a piece and updates king counts/positions
@@ -358,12 +677,11 @@ fn validate_piece_placement(
             *black_kings_count += 1;
             *black_king_position = square::Square::new(row_idx, col);
         }
-        c if c == pieces::PieceType::WhitePawn.to_char()
-            || c == pieces::PieceType::BlackPawn.to_char() =>
+        c if (c == pieces::PieceType::WhitePawn.to_char()
+            || c == pieces::PieceType::BlackPawn.to_char())
+            && (row_idx == 0 || row_idx == types::MAX_SIZE_INDEX) =>
         {
-            if row_idx == 0 || row_idx == types::MAX_SIZE_INDEX {
-                return Err(());
-            }
+            return Err(());
         }
         _ => {}
     }
@@ -372,19 +690,24 @@ fn validate_piece_placement(
 
 /// Validate that kings are at least 1 square apart
 /// Validate if there is at least 1 king for each color
+/// Both follow the rules of the variant, eg. Atomic allows adjacent or missing kings
 fn validate_king_placement(
     white_king_position: &square::Square,
     black_king_position: &square::Square,
     black_king_count: &mut u8,
     white_king_count: &mut u8,
+    variant: variant::VariantKind,
 ) -> Result<(), ()> {
-    if *white_king_count != EXACT_KINGS_COUNT || *black_king_count != EXACT_KINGS_COUNT {
-        return Err(());
-    }
-
     let row_diff = (white_king_position.row as i32 - black_king_position.row as i32).abs();
     let col_diff = (white_king_position.col as i32 - black_king_position.col as i32).abs();
-    if row_diff.max(col_diff) < MAX_DISTANCE_BETWEEN_KINGS as i32 {
+    let are_adjacent = *white_king_count > 0
+        && *black_king_count > 0
+        && row_diff.max(col_diff) < MAX_DISTANCE_BETWEEN_KINGS as i32;
+
+    if !variant
+        .rules()
+        .are_kings_valid(*white_king_count, *black_king_count, are_adjacent)
+    {
         return Err(());
     }
     Ok(())
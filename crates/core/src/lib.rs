@@ -1,25 +1,57 @@
+// `ChessError::InvalidParsedFen` carries the whole position, boxing it would break the public API
+#![allow(clippy::result_large_err)]
+
+mod attacks;
+mod bitboard;
+mod book;
+mod bot;
+mod chess960;
 mod err;
+mod evaluation;
 mod fen;
 mod moves;
+mod perft;
+mod pgn;
 mod pieces;
 mod player;
+mod random;
 mod result;
+mod san;
+mod search;
 mod square;
 mod state;
+mod tablebase;
+mod transposition;
 mod types;
+mod uci;
 mod validation;
+mod variant;
+mod zobrist;
 
 #[cfg(test)]
 mod tests;
 
 // Public API
+pub use book::{BookMove, PolyglotBook};
 pub use err::ChessError;
+pub use evaluation::{
+    EvaluationWeights, MobilityWeights, PhaseScore, PhaseTable, PieceSquareTables, PieceValues,
+    SquareTable,
+};
 pub use fen::INITIAL_FEN;
+pub use pgn::{PgnGame, PgnTag};
 pub use pieces::{PieceKind, PieceType};
 pub use player::Player;
-pub use result::GameResult;
+pub use result::{GameResult, RepetitionKey};
+pub use search::{Clock, SearchLimits, SearchResult};
 pub use square::Square;
-pub use types::{ChessResult, Move, ParsedFen, ParsedFenState};
+pub use tablebase::{TableFiles, Tablebase, TablebaseProbe, Wdl};
+pub use transposition::{Bound, PackedMove, TranspositionEntry, TranspositionTable};
+pub use types::{
+    CastleRooks, Checks, ChessResult, Move, ParsedFen, ParsedFenState, Pocket, Pockets, SquareMoves,
+};
+pub use variant::VariantKind;
+pub use zobrist::{ZobristKeys, POLYGLOT_RANDOM64_LENGTH};
 
 pub struct Chess {}
 
@@ -30,17 +62,68 @@ impl Chess {
         fen::parse(fen)
     }
 
+    /// Parses a FEN string into a game of the variant.
+    /// Three-check games without checks in the FEN start with no checks given,
+    /// checks given are accepted only in Three-check.
+    pub fn parse_fen_with_variant(
+        fen: &str,
+        variant: VariantKind,
+    ) -> types::ChessResult<types::ParsedFen> {
+        fen::parse_with_variant(fen, variant)
+    }
+
     /// Converts a parsed chess game state back into a FEN string.
     /// Useful for serializing game positions for storage or transmission.
     pub fn stringify(parsed_fen: &types::ParsedFen) -> types::ChessResult<String> {
         fen::stringify(parsed_fen)
     }
 
+    /// Converts a parsed chess game state into a FEN string with Shredder-FEN castling rights,
+    /// written as file letters of the castling rooks (eg. `HAha`), common in Chess960 tools.
+    pub fn stringify_shredder(parsed_fen: &types::ParsedFen) -> types::ChessResult<String> {
+        fen::stringify_shredder(parsed_fen)
+    }
+
+    /// Creates the Chess960 start position of the index from 0 to 959 (Scharnagl numbering,
+    /// 518 is the standard setup). Castling moves of the game are written as the king
+    /// taking its own rook in FEN and UCI.
+    pub fn chess960_position(index: u16) -> types::ChessResult<types::ParsedFen> {
+        chess960::start_position(index)
+    }
+
     /// Gets all legal moves for a piece at the given square.
     pub fn get_moves(square: square::Square, game: types::ParsedFen) -> Vec<types::Move> {
-        let pseudo_moves = moves::get_pseudo_moves(square, game);
+        let piece = match game.safe_access_square(square.row, square.col) {
+            Ok(Some(piece)) => piece,
+            _ => return Vec::new(),
+        };
+        let from = bitboard::square_bit(bitboard::square_index(square.row, square.col));
+
+        bitboard::Position::from(game).legal_moves(piece.color(), from)
+    }
+
+    /// Gets all legal moves for the player on turn, including drops in Crazyhouse.
+    pub fn get_all_moves(game: types::ParsedFen) -> Vec<types::Move> {
+        bitboard::Position::from(game).all_legal_moves(game.state.on_turn)
+    }
+
+    /// Gets legal Crazyhouse drops of pieces from the pocket of the player on turn.
+    /// Games without pockets have no drops.
+    pub fn get_drop_moves(game: types::ParsedFen) -> Vec<types::Move> {
+        bitboard::Position::from(game).legal_drops(game.state.on_turn)
+    }
 
-        validation::validate_moves(pseudo_moves, game)
+    /// Gets all legal moves for the player on turn, grouped by the square they start from.
+    /// Squares without any legal move are omitted, drops are not included.
+    pub fn get_all_moves_by_square(game: types::ParsedFen) -> Vec<types::SquareMoves> {
+        moves::get_player_squares(game, game.state.on_turn)
+            .into_iter()
+            .map(|square| types::SquareMoves {
+                square,
+                moves: Self::get_moves(square, game),
+            })
+            .filter(|square_moves| !square_moves.moves.is_empty())
+            .collect()
     }
 
     /// Gets all pseudo-legal moves for a piece at the given square.
@@ -49,6 +132,33 @@ impl Chess {
         moves::get_pseudo_moves(square, game)
     }
 
+    /// Checks whether any piece of the given player attacks the square.
+    /// Pawns attack diagonally only, squares occupied by own pieces count as defended.
+    pub fn is_square_attacked(
+        square: square::Square,
+        game: types::ParsedFen,
+        by_player: player::Player,
+    ) -> bool {
+        attacks::is_square_attacked(game, square, by_player)
+    }
+
+    /// Gets squares of all pieces of the given player attacking the square.
+    pub fn attackers_of(
+        square: square::Square,
+        game: types::ParsedFen,
+        by_player: player::Player,
+    ) -> Vec<square::Square> {
+        attacks::attackers_of(game, square, by_player)
+    }
+
+    /// Gets all squares attacked by the given player, eg. for highlighting attacked squares.
+    pub fn get_attacked_squares(
+        game: types::ParsedFen,
+        by_player: player::Player,
+    ) -> Vec<square::Square> {
+        attacks::get_attacked_squares(game, by_player)
+    }
+
     /// Executes a move and returns the resulting game state.
     ///
     /// 1. Validates the move against the game state.
@@ -59,7 +169,7 @@ impl Chess {
         game: types::ParsedFen,
     ) -> types::ChessResult<types::ParsedFen> {
         validation::validate_move_against_state(req_move, game)?;
-        Ok(validation::validate_move(req_move, game)?)
+        validation::validate_move(req_move, game)
     }
 
     /// Validates the move against the game state and chess rules.
@@ -81,7 +191,211 @@ impl Chess {
 
     /// Gets the game result for a given game state.
     /// Returns the game result if the game is over, or None if the game is not over.
+    /// Draws that must be claimed (fifty move rule, threefold repetition) are reported by `get_claimable_draw`.
     pub fn get_game_result(game: types::ParsedFen) -> types::ChessResult<Option<GameResult>> {
         result::get_game_result(game)
     }
+
+    /// Gets the game result for the last position of a game history (oldest position first).
+    /// Besides the results of `get_game_result`, detects automatic fivefold repetition.
+    pub fn get_game_result_from_history(
+        history: &[types::ParsedFen],
+    ) -> types::ChessResult<Option<GameResult>> {
+        result::get_game_result_from_history(history)
+    }
+
+    /// Gets the draw that can be claimed in the last position of a game history, if any.
+    /// Returns `GameResult::ThreefoldRepetition` or `GameResult::FiftyMoveRule`.
+    /// Pass a single position to only check the fifty move rule.
+    pub fn get_claimable_draw(
+        history: &[types::ParsedFen],
+    ) -> types::ChessResult<Option<GameResult>> {
+        result::get_claimable_draw(history)
+    }
+
+    /// Counts how many times the last position of a game history occurred, including itself.
+    pub fn get_repetition_count(history: &[types::ParsedFen]) -> usize {
+        result::get_repetition_count(history)
+    }
+
+    /// Gets the key identifying the position in repetitions.
+    /// Games keeping one key per ply can get results without passing the whole history again.
+    pub fn get_repetition_key(game: types::ParsedFen) -> RepetitionKey {
+        result::get_repetition_key(game)
+    }
+
+    /// Same as `get_game_result_from_history`, with the repetition keys of the history
+    /// (oldest first, the key of `game` last).
+    pub fn get_game_result_with_repetitions(
+        game: types::ParsedFen,
+        repetitions: &[RepetitionKey],
+    ) -> types::ChessResult<Option<GameResult>> {
+        result::get_game_result_with_repetitions(game, repetitions)
+    }
+
+    /// Same as `get_claimable_draw`, with the repetition keys of the history
+    /// (oldest first, the key of `game` last).
+    pub fn get_claimable_draw_with_repetitions(
+        game: types::ParsedFen,
+        repetitions: &[RepetitionKey],
+    ) -> types::ChessResult<Option<GameResult>> {
+        result::get_claimable_draw_with_repetitions(game, repetitions)
+    }
+
+    /// Converts a legal move into Standard Algebraic Notation (eg. "Nbxd7+", "O-O-O", "e8=N#").
+    pub fn move_to_san(
+        req_move: types::Move,
+        game: types::ParsedFen,
+    ) -> types::ChessResult<String> {
+        san::move_to_san(req_move, game)
+    }
+
+    /// Parses a move in Standard Algebraic Notation into a legal move for the given game.
+    pub fn san_to_move(san: &str, game: types::ParsedFen) -> types::ChessResult<types::Move> {
+        san::san_to_move(san, game)
+    }
+
+    /// Parses a move in UCI long algebraic notation (eg. "e2e4", "e1g1", "e7e8q") into
+    /// a legal move for the given game. Castling and en passant flags are resolved from the position,
+    /// castling can be written as the king taking its own rook too (required in Chess960).
+    pub fn uci_to_move(uci: &str, game: types::ParsedFen) -> types::ChessResult<types::Move> {
+        uci::uci_to_move(uci, game)
+    }
+
+    /// Converts a move into UCI long algebraic notation for the given game.
+    /// Castling in Chess960 games is written as the king taking its own rook (eg. "e1h1"),
+    /// other moves match `Move::to_uci`.
+    pub fn move_to_uci(req_move: types::Move, game: types::ParsedFen) -> Option<String> {
+        uci::move_to_uci(req_move, game)
+    }
+
+    /// Parses the first game of a PGN (Portable Game Notation) string.
+    /// Mainline moves are replayed and returned together with every intermediate position.
+    /// Fails with `ChessError::InvalidPgnMove` carrying the ply and token of the first illegal move.
+    pub fn parse_pgn(pgn: &str) -> types::ChessResult<PgnGame> {
+        pgn::parse(pgn)
+    }
+
+    /// Parses every game of a PGN string, eg. a whole database export.
+    pub fn parse_pgn_games(pgn: &str) -> types::ChessResult<Vec<PgnGame>> {
+        pgn::parse_all(pgn)
+    }
+
+    /// Converts a game into a PGN string in export format.
+    /// Every move is re-encoded as SAN from the position it was played in.
+    pub fn to_pgn(game: &PgnGame) -> types::ChessResult<String> {
+        pgn::stringify(game)
+    }
+
+    /// Counts leaf nodes of the legal move tree at the given depth (performance test).
+    /// Used to verify move generation against known results of standard positions.
+    pub fn perft(game: types::ParsedFen, depth: u32) -> u64 {
+        perft::perft(game, depth)
+    }
+
+    /// Counts perft leaf nodes separately for every legal move of the position.
+    /// Depth includes the root move, so the counts sum up to `perft(game, depth)`.
+    pub fn perft_divide(game: types::ParsedFen, depth: u32) -> Vec<(types::Move, u64)> {
+        perft::perft_divide(game, depth)
+    }
+
+    /// Evaluates the position in centipawns from White's perspective with the default weights.
+    /// Positive score favours White, negative Black. Checkmate and draws are not detected.
+    pub fn evaluate(game: types::ParsedFen) -> i32 {
+        evaluation::evaluate(game, &evaluation::DEFAULT_WEIGHTS)
+    }
+
+    /// Evaluates the position like `evaluate` with custom weights
+    pub fn evaluate_with_weights(game: types::ParsedFen, weights: &EvaluationWeights) -> i32 {
+        evaluation::evaluate(game, weights)
+    }
+
+    /// Searches the best move for the player on turn within the limits.
+    /// Without any limit the search runs to `search::DEFAULT_DEPTH`.
+    ///
+    /// Time limit is measured with `std::time::Instant`, which is not available
+    /// on `wasm32-unknown-unknown`, use `best_move_with_clock` there.
+    pub fn best_move(game: types::ParsedFen, limits: SearchLimits) -> SearchResult {
+        let mut table = TranspositionTable::new(transposition::ONE_OFF_SIZE_MB);
+        search::search(game, limits, search::system_clock, &mut table)
+    }
+
+    /// Searches the best move like `best_move`, measuring time limit with a custom clock
+    /// returning milliseconds (eg. `Date.now` in JavaScript).
+    pub fn best_move_with_clock(
+        game: types::ParsedFen,
+        limits: SearchLimits,
+        clock: Clock,
+    ) -> SearchResult {
+        let mut table = TranspositionTable::new(transposition::ONE_OFF_SIZE_MB);
+        search::search(game, limits, clock, &mut table)
+    }
+
+    /// Searches the best move like `best_move_with_clock`, keeping results in the table
+    /// so later searches of the same or following positions finish faster.
+    pub fn best_move_with_table(
+        game: types::ParsedFen,
+        limits: SearchLimits,
+        clock: Clock,
+        table: &mut TranspositionTable,
+    ) -> SearchResult {
+        search::search(game, limits, clock, table)
+    }
+
+    /// Searches the best move like `best_move_with_table`, positions covered by the tablebase
+    /// are answered by it instantly, winning with the fastest zeroing move.
+    pub fn best_move_with_tablebase(
+        game: types::ParsedFen,
+        limits: SearchLimits,
+        clock: Clock,
+        table: &mut TranspositionTable,
+        tablebase: &Tablebase,
+    ) -> SearchResult {
+        search::search_with_tablebase(game, limits, clock, table, tablebase)
+    }
+
+    /// Looks up win, draw or loss of the player on turn and distance to zeroing in the tablebase.
+    /// Returns `None` if the position is not covered by loaded tables (too many pieces,
+    /// castling rights, possible en passant or a missing table).
+    pub fn probe_tablebase(
+        game: types::ParsedFen,
+        tablebase: &Tablebase,
+    ) -> Option<TablebaseProbe> {
+        tablebase.probe(game)
+    }
+
+    /// Gets the game result like `get_game_result`, positions covered by the tablebase are
+    /// adjudicated by their result with the best play (`GameResult::WhiteTablebaseWin`,
+    /// `GameResult::BlackTablebaseWin` or `GameResult::TablebaseDraw`).
+    /// Wins the fifty-move rule comes before, considering the half-move clock, are draws.
+    pub fn get_game_result_with_tablebase(
+        game: types::ParsedFen,
+        tablebase: &Tablebase,
+    ) -> types::ChessResult<Option<GameResult>> {
+        result::get_tablebase_result(game, tablebase)
+    }
+
+    /// Picks a move of the player on turn for a bot of the level from 1 (weakest) to 10 (strongest).
+    /// Lower levels search shallower, add noise to move scores and sometimes pick one of
+    /// the top moves instead of the best one. The same seed always picks the same move.
+    pub fn bot_move(
+        game: types::ParsedFen,
+        level: u8,
+        seed: u64,
+    ) -> types::ChessResult<types::Move> {
+        let mut table = TranspositionTable::new(transposition::ONE_OFF_SIZE_MB);
+        bot::bot_move(game, level, seed, &mut table)
+    }
+
+    /// Picks a bot move like `bot_move`, keeping search results in the table.
+    /// Stored results may change scores of moves, so the picked move depends on the table
+    /// contents too, use an empty table for reproducible moves.
+    pub fn bot_move_with_table(
+        game: types::ParsedFen,
+        level: u8,
+        seed: u64,
+        table: &mut TranspositionTable,
+    ) -> types::ChessResult<types::Move> {
+        bot::bot_move(game, level, seed, table)
+    }
 }
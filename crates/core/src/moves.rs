@@ -9,9 +9,9 @@ use crate::player;
 use crate::square;
 use crate::types;
 
-const DIAGONAL_DIRS: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
-const STRAIGHT_DIRS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-const KNIGHT_DIRS: [(isize, isize); 8] = [
+pub(super) const DIAGONAL_DIRS: [(isize, isize); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+pub(super) const STRAIGHT_DIRS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+pub(super) const KNIGHT_DIRS: [(isize, isize); 8] = [
     (-2, -1),
     (-2, 1),
     (-1, -2),
@@ -39,6 +39,21 @@ pub fn get_pseudo_moves(target: square::Square, game: types::ParsedFen) -> Vec<t
     }
 }
 
+/// Get squares occupied by pieces of a given player
+pub fn get_player_squares(game: types::ParsedFen, player: player::Player) -> Vec<square::Square> {
+    let mut squares = Vec::new();
+
+    for (row_idx, row) in game.board.iter().enumerate() {
+        for (col_idx, piece) in row.iter().enumerate() {
+            if piece.is_some_and(|piece| piece.color() == player) {
+                squares.push(square::Square::new(row_idx, col_idx));
+            }
+        }
+    }
+
+    squares
+}
+
 /// Pawn pseudo moves
 fn pawn_moves(
     target: square::Square,
@@ -48,23 +63,30 @@ fn pawn_moves(
     let mut moves = Vec::new();
     let diagonals = [1, -1];
     let piece_color = piece.color();
-    let (direction, start_row) = match piece_color {
-        player::Player::White => (-1, 6),
-        player::Player::Black => (1, 1),
+    let (direction, start_row, promotion_row) = match piece_color {
+        player::Player::White => (-1, 6, 0),
+        player::Player::Black => (1, 1, 7),
     };
 
     if let Some(one_step) = target.row.checked_add_signed(direction) {
         // One step forward
         if game.is_square_empty(one_step, target.col) {
-            moves.push(types::Move {
-                from_col_idx: target.col,
-                from_row_idx: target.row,
-                to_col_idx: target.col,
-                to_row_idx: one_step,
-                is_castle: false,
-                is_passant: false,
-                piece,
-            });
+            self::push_pawn_move(
+                &mut moves,
+                types::Move {
+                    from_col_idx: target.col,
+                    from_row_idx: target.row,
+                    to_col_idx: target.col,
+                    to_row_idx: one_step,
+                    is_castle: false,
+                    is_passant: false,
+                    is_drop: false,
+                    promotion_piece: None,
+                    piece,
+                },
+                promotion_row,
+                game,
+            );
         }
         // Second step forward
         if target.row == start_row {
@@ -79,6 +101,8 @@ fn pawn_moves(
                         to_row_idx: second_step,
                         is_castle: false,
                         is_passant: false,
+                        is_drop: false,
+                        promotion_piece: None,
                         piece,
                     });
                 }
@@ -92,15 +116,22 @@ fn pawn_moves(
                 target.col.checked_add_signed(diagonal),
             ) {
                 if game.is_enemy_square(new_row, new_col, piece_color) {
-                    moves.push(types::Move {
-                        from_col_idx: target.col,
-                        from_row_idx: target.row,
-                        to_col_idx: new_col,
-                        to_row_idx: new_row,
-                        is_castle: false,
-                        is_passant: false,
-                        piece,
-                    });
+                    self::push_pawn_move(
+                        &mut moves,
+                        types::Move {
+                            from_col_idx: target.col,
+                            from_row_idx: target.row,
+                            to_col_idx: new_col,
+                            to_row_idx: new_row,
+                            is_castle: false,
+                            is_passant: false,
+                            is_drop: false,
+                            promotion_piece: None,
+                            piece,
+                        },
+                        promotion_row,
+                        game,
+                    );
                 } else if let Some(en_passant_square) = game.state.en_passant_square {
                     if new_row == en_passant_square.row && new_col == en_passant_square.col {
                         let captured_pawn_row = target.row;
@@ -112,6 +143,8 @@ fn pawn_moves(
                                 to_row_idx: new_row,
                                 is_castle: false,
                                 is_passant: true,
+                                is_drop: false,
+                                promotion_piece: None,
                                 piece,
                             });
                         }
@@ -124,6 +157,28 @@ fn pawn_moves(
     moves
 }
 
+/// Pushes a pawn move, expanding it into one move per promotion choice of the game variant
+/// when the pawn reaches the last rank
+fn push_pawn_move(
+    moves: &mut Vec<types::Move>,
+    pawn_move: types::Move,
+    promotion_row: usize,
+    game: types::ParsedFen,
+) {
+    if pawn_move.to_row_idx != promotion_row {
+        moves.push(pawn_move);
+        return;
+    }
+
+    let rules = game.state.variant.rules();
+    for promotion_piece in rules.promotion_pieces(pawn_move.piece.color()) {
+        moves.push(types::Move {
+            promotion_piece: Some(promotion_piece),
+            ..pawn_move
+        });
+    }
+}
+
 /// Bishop pseudo moves
 fn bishop_moves(
     target: square::Square,
@@ -166,6 +221,8 @@ fn knight_moves(
                     to_row_idx: new_row,
                     is_castle: false,
                     is_passant: false,
+                    is_drop: false,
+                    promotion_piece: None,
                     piece,
                 });
             }
@@ -203,6 +260,8 @@ fn king_moves(
                     piece,
                     is_castle: false,
                     is_passant: false,
+                    is_drop: false,
+                    promotion_piece: None,
                 })
             }
         }
@@ -214,54 +273,50 @@ fn king_moves(
         player::Player::White => 7,
         player::Player::Black => 0,
     };
-    let king_initial_col = 4;
-    let rook_short_col = 7;
-    let rook_long_col = 0;
     let rook_piece = match piece.color() {
         player::Player::White => pieces::PieceType::WhiteRook,
         player::Player::Black => pieces::PieceType::BlackRook,
     };
 
-    // king is at initial position
-    if target.col == king_initial_col && target.row == king_initial_row {
-        // short castle
-        if can_castle_short {
-            let short_path_clear = game.is_square_empty(king_initial_row, king_initial_col + 1)
-                && game.is_square_empty(king_initial_row, king_initial_col + 2);
-            let is_rook_in_place = game
-                .safe_access_square(king_initial_row, rook_short_col)
-                .is_ok_and(|piece| piece.is_some() && piece.unwrap() == rook_piece);
-
-            if short_path_clear && is_rook_in_place {
-                moves.push(types::Move {
-                    from_col_idx: target.col,
-                    from_row_idx: target.row,
-                    to_col_idx: king_initial_col + 2,
-                    to_row_idx: king_initial_row,
-                    is_castle: true,
-                    is_passant: false,
-                    piece,
-                });
-            }
-        }
+    // king is on its initial row, rook columns come from the state (they differ in Chess960)
+    if target.row == king_initial_row {
+        let sides = [
+            (can_castle_short, true, types::KING_SHORT_CASTLE_COL),
+            (can_castle_long, false, types::KING_LONG_CASTLE_COL),
+        ];
 
-        // long castle
-        if can_castle_long {
-            let long_path_clear = game.is_square_empty(king_initial_row, king_initial_col - 1)
-                && game.is_square_empty(king_initial_row, king_initial_col - 2)
-                && game.is_square_empty(king_initial_row, king_initial_col - 3);
-            let is_rook_in_place = game
-                .safe_access_square(king_initial_row, rook_long_col)
-                .is_ok_and(|piece| piece.is_some() && piece.unwrap() == rook_piece);
+        for (can_castle, is_short, king_target_col) in sides {
+            let rook_col = game.state.castle_rooks.col(is_short);
+            let rook_target_col = match is_short {
+                true => king_target_col - 1,
+                false => king_target_col + 1,
+            };
 
-            if long_path_clear && is_rook_in_place {
+            let is_rook_in_place = (rook_col > target.col) == is_short
+                && game
+                    .safe_access_square(king_initial_row, rook_col)
+                    .is_ok_and(|piece| piece.is_some() && piece.unwrap() == rook_piece);
+
+            // squares passed by the king and the rook are empty, except for the two of them
+            let is_path_clear = [(target.col, king_target_col), (rook_col, rook_target_col)]
+                .iter()
+                .flat_map(|(from, to)| *from.min(to)..=*from.max(to))
+                .all(|col| {
+                    col == target.col
+                        || col == rook_col
+                        || game.is_square_empty(king_initial_row, col)
+                });
+
+            if can_castle && is_rook_in_place && is_path_clear {
                 moves.push(types::Move {
                     from_col_idx: target.col,
                     from_row_idx: target.row,
-                    to_col_idx: king_initial_col - 2,
+                    to_col_idx: king_target_col,
                     to_row_idx: king_initial_row,
                     is_castle: true,
                     is_passant: false,
+                    is_drop: false,
+                    promotion_piece: None,
                     piece,
                 });
             }
@@ -307,45 +362,44 @@ fn get_direction_moves(
         let mut current_row = target.row;
         let mut current_col = target.col;
 
-        loop {
-            if let (Some(new_row), Some(new_col)) = (
-                current_row.checked_add_signed(row_dir),
-                current_col.checked_add_signed(col_dir),
-            ) {
-                if !game.is_in_bounds(new_row, new_col) {
-                    break;
-                }
+        while let (Some(new_row), Some(new_col)) = (
+            current_row.checked_add_signed(row_dir),
+            current_col.checked_add_signed(col_dir),
+        ) {
+            if !game.is_in_bounds(new_row, new_col) {
+                break;
+            }
 
-                if game.is_square_empty(new_row, new_col) {
-                    moves.push(types::Move {
-                        from_col_idx: target.col,
-                        from_row_idx: target.row,
-                        to_col_idx: new_col,
-                        to_row_idx: new_row,
-                        is_castle: false,
-                        is_passant: false,
-                        piece,
-                    });
-                    current_row = new_row;
-                    current_col = new_col;
-                } else if game.is_enemy_square(new_row, new_col, piece_color) {
-                    moves.push(types::Move {
-                        from_col_idx: target.col,
-                        from_row_idx: target.row,
-                        to_col_idx: new_col,
-                        to_row_idx: new_row,
-                        is_castle: false,
-                        is_passant: false,
-                        piece,
-                    });
-                    // stop in this direction after capture
-                    break;
-                } else {
-                    // friendly piece - stop in this direction (no move added)
-                    break;
-                }
+            if game.is_square_empty(new_row, new_col) {
+                moves.push(types::Move {
+                    from_col_idx: target.col,
+                    from_row_idx: target.row,
+                    to_col_idx: new_col,
+                    to_row_idx: new_row,
+                    is_castle: false,
+                    is_passant: false,
+                    is_drop: false,
+                    promotion_piece: None,
+                    piece,
+                });
+                current_row = new_row;
+                current_col = new_col;
+            } else if game.is_enemy_square(new_row, new_col, piece_color) {
+                moves.push(types::Move {
+                    from_col_idx: target.col,
+                    from_row_idx: target.row,
+                    to_col_idx: new_col,
+                    to_row_idx: new_row,
+                    is_castle: false,
+                    is_passant: false,
+                    is_drop: false,
+                    promotion_piece: None,
+                    piece,
+                });
+                // stop in this direction after capture
+                break;
             } else {
-                // checked_add_signed returned None (overflow), out of bounds
+                // friendly piece - stop in this direction (no move added)
                 break;
             }
         }
@@ -0,0 +1,95 @@
+//! # Perft Module
+//!
+//! This module counts leaf nodes of the legal move tree (performance test).
+//! Comparing the counts with known values of standard positions verifies move generation
+//! and validation, see <https://www.chessprogramming.org/Perft_Results>.
+//!
+use crate::bitboard;
+#[cfg(test)]
+use crate::moves;
+use crate::types;
+#[cfg(test)]
+use crate::validation;
+
+/// Count leaf nodes of the legal move tree at the given depth.
+pub(super) fn perft(game: types::ParsedFen, depth: u32) -> u64 {
+    self::perft_position(&mut bitboard::Position::from(game), depth)
+}
+
+/// Count leaf nodes at the given depth separately for every legal move of the position.
+pub(super) fn perft_divide(game: types::ParsedFen, depth: u32) -> Vec<(types::Move, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    let mut position = bitboard::Position::from(game);
+
+    self::legal_moves(&position)
+        .into_iter()
+        .map(|m| {
+            let undo = position.make_move(m);
+            let nodes = self::perft_position(&mut position, depth - 1);
+            position.unmake_move(undo);
+
+            (m, nodes)
+        })
+        .collect()
+}
+
+/// Count leaf nodes through the original move generation: pseudo moves of every square
+/// validated and played like `Chess::move_piece`. Drops are not generated.
+/// It is much slower than `perft` and serves as a reference for the bitboard generator.
+#[cfg(test)]
+pub(super) fn perft_validated(game: types::ParsedFen, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    self::validated_moves(game)
+        .into_iter()
+        .map(|(_, next_game)| self::perft_validated(next_game, depth - 1))
+        .sum()
+}
+
+/// Legal moves of the player on turn and the positions they lead to, generated by `moves`
+/// and checked by `validation`.
+#[cfg(test)]
+pub(super) fn validated_moves(game: types::ParsedFen) -> Vec<(types::Move, types::ParsedFen)> {
+    moves::get_player_squares(game, game.state.on_turn)
+        .into_iter()
+        .flat_map(|square| moves::get_pseudo_moves(square, game))
+        .filter_map(|m| {
+            validation::validate_move_against_state(m, game).ok()?;
+            validation::validate_move(m, game)
+                .ok()
+                .map(|next_game| (m, next_game))
+        })
+        .collect()
+}
+
+fn perft_position(position: &mut bitboard::Position, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = self::legal_moves(position);
+
+    // bulk counting, leaf moves don't need to be played
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    let mut nodes = 0;
+
+    for m in moves {
+        let undo = position.make_move(m);
+        nodes += self::perft_position(position, depth - 1);
+        position.unmake_move(undo);
+    }
+
+    nodes
+}
+
+fn legal_moves(position: &bitboard::Position) -> Vec<types::Move> {
+    position.all_legal_moves(position.state.on_turn)
+}
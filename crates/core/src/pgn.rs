@@ -0,0 +1,524 @@
+//! # PGN Module
+//!
+//! This module handles parsing PGN (Portable Game Notation) game files.
+//!
+//! ## Supported syntax
+//! - Tag pairs (eg. `[Event "Casual game"]`), including `FEN` and `SetUp` for custom start positions
+//!   and `Variant` for Chess960, Crazyhouse and the variants of `VariantKind`
+//! - SAN movetext with move numbers (`1.`, `1...`)
+//! - Comments (`{ ... }` and `; ...` until end of line)
+//! - Numeric annotation glyphs (`$1`)
+//! - Nested variations (`( ... )`), which are skipped - only the mainline is replayed
+//! - Game termination markers (`1-0`, `0-1`, `1/2-1/2`, `*`)
+//!
+//! Every mainline move is replayed through `Chess::move_piece`. When a move fails,
+//! the error reports the ply (1-based, counted from the start of the game) and the token.
+//!
+//! ## Export
+//! Games are written in PGN export format: the Seven Tag Roster first (missing tags get
+//! their default unknown value), a `SetUp`/`FEN` pair when the game doesn't start from the
+//! initial position, other tags in their original order and SAN movetext wrapped at 80 columns.
+//! Games of other variants than standard chess get a `Variant` tag after the Seven Tag Roster.
+//!
+use serde::{Deserialize, Serialize};
+
+use crate::err;
+use crate::fen;
+use crate::player;
+use crate::types;
+use crate::variant;
+use crate::Chess;
+
+pub const FEN_TAG: &str = "FEN";
+pub const SET_UP_TAG: &str = "SetUp";
+pub const VARIANT_TAG: &str = "Variant";
+
+pub const RESULT_TAG: &str = "Result";
+pub const UNKNOWN_RESULT: &str = "*";
+
+const RESULT_TOKENS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", UNKNOWN_RESULT];
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    (RESULT_TAG, UNKNOWN_RESULT),
+];
+const MAX_LINE_LENGTH: usize = 80;
+
+const CHESS960_VARIANT: &str = "Chess960";
+const CRAZYHOUSE_VARIANT: &str = "Crazyhouse";
+/// Names of the `Variant` tag, the standard chess names are accepted on import only
+const VARIANT_NAMES: [(&str, variant::VariantKind); 7] = [
+    ("Standard", variant::VariantKind::Standard),
+    ("Chess", variant::VariantKind::Standard),
+    ("Three-check", variant::VariantKind::ThreeCheck),
+    ("King of the Hill", variant::VariantKind::KingOfTheHill),
+    ("Atomic", variant::VariantKind::Atomic),
+    ("Antichess", variant::VariantKind::Antichess),
+    (CRAZYHOUSE_VARIANT, variant::VariantKind::Standard),
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+/// Represents a single PGN tag pair, eg. `[White "Carlsen, Magnus"]`
+pub struct PgnTag {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+/// Represents a parsed PGN game
+pub struct PgnGame {
+    /// Tag pairs in the order they appear in the file
+    pub tags: Vec<PgnTag>,
+    /// Mainline moves
+    pub moves: Vec<types::Move>,
+    /// Positions of the game, starting with the initial position.
+    /// `positions[i + 1]` is the position after `moves[i]`.
+    pub positions: Vec<types::ParsedFen>,
+    /// Game termination marker from the movetext (eg. "1-0"), if present
+    pub result: Option<String>,
+}
+
+impl PgnGame {
+    /// Returns the value of the first tag with the given name
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|tag| tag.name == name)
+            .map(|tag| tag.value.as_str())
+    }
+}
+
+/// Parse the first game of PGN string.
+pub(super) fn parse(pgn: &str) -> types::ChessResult<PgnGame> {
+    self::parse_all(pgn)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| self::get_pgn_error("No game found"))
+}
+
+/// Parse all games of PGN string.
+pub(super) fn parse_all(pgn: &str) -> types::ChessResult<Vec<PgnGame>> {
+    let mut tokenizer = Tokenizer::new(pgn);
+    let mut games = Vec::new();
+
+    loop {
+        let mut tags = Vec::new();
+        let mut san_moves = Vec::new();
+        let mut result = None;
+
+        // Tag section
+        while let Some(token) = tokenizer.peek()? {
+            match token {
+                Token::Tag(_) => {
+                    if let Some(Token::Tag(tag)) = tokenizer.next()? {
+                        tags.push(tag);
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        // Movetext section
+        while let Some(token) = tokenizer.peek()? {
+            match token {
+                Token::Tag(_) => break,
+                _ => match tokenizer.next()? {
+                    Some(Token::San(san)) => san_moves.push(san),
+                    Some(Token::Result(token)) => {
+                        result = Some(token);
+                        break;
+                    }
+                    _ => {}
+                },
+            }
+        }
+
+        if tags.is_empty() && san_moves.is_empty() && result.is_none() {
+            break;
+        }
+
+        games.push(self::replay(tags, san_moves, result)?);
+    }
+
+    Ok(games)
+}
+
+/// Convert a game into PGN export format.
+///
+/// The result token is taken from `game.result`, then from the `Result` tag, defaulting to `*`.
+pub(super) fn stringify(game: &PgnGame) -> types::ChessResult<String> {
+    let start = *game
+        .positions
+        .first()
+        .ok_or_else(|| self::get_pgn_error("Game has no start position"))?;
+
+    if game.positions.len() != game.moves.len() + 1 {
+        return Err(self::get_pgn_error(
+            "Game must have exactly one more position than moves",
+        ));
+    }
+
+    let result = game
+        .result
+        .as_deref()
+        .or_else(|| game.tag(RESULT_TAG))
+        .unwrap_or(UNKNOWN_RESULT);
+
+    // Tag section
+    let mut tags: Vec<(&str, String)> = SEVEN_TAG_ROSTER
+        .iter()
+        .map(|&(name, default)| {
+            let value = match name {
+                RESULT_TAG => result,
+                _ => game.tag(name).unwrap_or(default),
+            };
+            (name, value.to_string())
+        })
+        .collect();
+
+    if let Some(name) = self::variant_name(start) {
+        tags.push((VARIANT_TAG, name.to_string()));
+    }
+
+    let start_fen = Chess::stringify(&start)?;
+    if start_fen != fen::INITIAL_FEN {
+        tags.push((SET_UP_TAG, "1".to_string()));
+        tags.push((FEN_TAG, start_fen));
+    }
+
+    for tag in &game.tags {
+        let is_reserved = tag.name == SET_UP_TAG
+            || tag.name == FEN_TAG
+            || tag.name == VARIANT_TAG
+            || SEVEN_TAG_ROSTER.iter().any(|&(name, _)| name == tag.name);
+
+        if !is_reserved {
+            tags.push((tag.name.as_str(), tag.value.clone()));
+        }
+    }
+
+    let mut pgn = String::new();
+    for (name, value) in tags {
+        pgn.push_str(&format!(
+            "[{} \"{}\"]\n",
+            name,
+            value.replace('\\', "\\\\").replace('"', "\\\"")
+        ));
+    }
+    pgn.push('\n');
+
+    // Movetext section
+    let mut tokens = Vec::with_capacity(game.moves.len() * 2 + 1);
+    for (idx, req_move) in game.moves.iter().enumerate() {
+        let position = game.positions[idx];
+        let move_number = position.state.full_moves;
+
+        match position.state.on_turn {
+            player::Player::White => tokens.push(format!("{}.", move_number)),
+            player::Player::Black if idx == 0 => tokens.push(format!("{}...", move_number)),
+            player::Player::Black => {}
+        }
+
+        tokens.push(Chess::move_to_san(*req_move, position)?);
+    }
+    tokens.push(result.to_string());
+
+    let mut line_length = 0;
+    for token in tokens {
+        if line_length > 0 && line_length + 1 + token.len() > MAX_LINE_LENGTH {
+            pgn.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            pgn.push(' ');
+            line_length += 1;
+        }
+
+        line_length += token.len();
+        pgn.push_str(&token);
+    }
+    pgn.push('\n');
+
+    Ok(pgn)
+}
+
+/// Replay SAN moves from the start position given by tags
+fn replay(
+    tags: Vec<PgnTag>,
+    san_moves: Vec<String>,
+    result: Option<String>,
+) -> types::ChessResult<PgnGame> {
+    // SetUp "0" explicitly says the game starts from the initial position
+    let is_set_up = tags
+        .iter()
+        .find(|tag| tag.name == SET_UP_TAG)
+        .is_none_or(|tag| tag.value != "0");
+    let start_fen = tags
+        .iter()
+        .find(|tag| tag.name == FEN_TAG && is_set_up)
+        .map(|tag| tag.value.as_str())
+        .unwrap_or(fen::INITIAL_FEN);
+
+    let mut game = match tags.iter().find(|tag| tag.name == VARIANT_TAG) {
+        Some(tag) => self::parse_variant_start(start_fen, &tag.value)?,
+        None => Chess::parse_fen(start_fen)?,
+    };
+    let mut moves = Vec::with_capacity(san_moves.len());
+    let mut positions = Vec::with_capacity(san_moves.len() + 1);
+    positions.push(game);
+
+    for (idx, san) in san_moves.into_iter().enumerate() {
+        let ply = idx + 1;
+        let req_move = Chess::san_to_move(&san, game)
+            .map_err(|_| err::ChessError::InvalidPgnMove(ply, san.clone()))?;
+        game = Chess::move_piece(req_move, game)
+            .map_err(|_| err::ChessError::InvalidPgnMove(ply, san.clone()))?;
+
+        moves.push(req_move);
+        positions.push(game);
+    }
+
+    Ok(PgnGame {
+        tags,
+        moves,
+        positions,
+        result,
+    })
+}
+
+/// Name of the game variant written in the `Variant` tag, `None` for standard chess
+fn variant_name(game: types::ParsedFen) -> Option<&'static str> {
+    if game.state.variant == variant::VariantKind::Standard {
+        return match (game.state.pockets.is_some(), game.state.chess960) {
+            (true, _) => Some(CRAZYHOUSE_VARIANT),
+            (false, true) => Some(CHESS960_VARIANT),
+            (false, false) => None,
+        };
+    }
+
+    VARIANT_NAMES
+        .iter()
+        .find(|(_, kind)| *kind == game.state.variant)
+        .map(|(name, _)| *name)
+}
+
+/// Parse the start position of a game with the `Variant` tag (names are case insensitive)
+fn parse_variant_start(start_fen: &str, name: &str) -> types::ChessResult<types::ParsedFen> {
+    if name.eq_ignore_ascii_case(CHESS960_VARIANT) {
+        let mut game = Chess::parse_fen(start_fen)?;
+        game.state.chess960 = true;
+        return Ok(game);
+    }
+
+    let kind = VARIANT_NAMES
+        .iter()
+        .find(|(variant_name, _)| variant_name.eq_ignore_ascii_case(name))
+        .map(|(_, kind)| *kind)
+        .ok_or_else(|| self::get_pgn_error(&format!("Unsupported variant {}", name)))?;
+    let mut game = Chess::parse_fen_with_variant(start_fen, kind)?;
+
+    // Crazyhouse games from the initial position start with empty pockets
+    if name.eq_ignore_ascii_case(CRAZYHOUSE_VARIANT) && game.state.pockets.is_none() {
+        game.state.pockets = Some(types::Pockets::default());
+    }
+
+    Ok(game)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Token {
+    Tag(PgnTag),
+    San(String),
+    Result(String),
+}
+
+/// Splits PGN text into tags, SAN moves and results.
+/// Move numbers, comments, NAGs and variations are skipped.
+struct Tokenizer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    /// Last character read, `%` escapes a line only in its first column
+    previous: Option<char>,
+    peeked: Option<Token>,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(pgn: &'a str) -> Self {
+        Self {
+            chars: pgn.chars().peekable(),
+            previous: None,
+            peeked: None,
+        }
+    }
+
+    fn peek(&mut self) -> types::ChessResult<Option<&Token>> {
+        if self.peeked.is_none() {
+            self.peeked = self.read_token()?;
+        }
+
+        Ok(self.peeked.as_ref())
+    }
+
+    fn next(&mut self) -> types::ChessResult<Option<Token>> {
+        match self.peeked.take() {
+            Some(token) => Ok(Some(token)),
+            None => self.read_token(),
+        }
+    }
+
+    fn read_token(&mut self) -> types::ChessResult<Option<Token>> {
+        let mut variation_depth = 0;
+
+        loop {
+            let is_line_start = matches!(self.previous, None | Some('\n'));
+            let Some(c) = self.next_char() else {
+                break;
+            };
+
+            match c {
+                c if c.is_whitespace() => {}
+                '{' => self.skip_until('}')?,
+                ';' => self.skip_line(),
+                '%' if is_line_start => self.skip_line(),
+                '(' => variation_depth += 1,
+                ')' => {
+                    if variation_depth == 0 {
+                        return Err(self::get_pgn_error("Unexpected ')'"));
+                    }
+                    variation_depth -= 1;
+                }
+                '[' if variation_depth == 0 => return self.read_tag().map(Some),
+                _ => {
+                    let word = self.read_word(c);
+
+                    if variation_depth > 0 {
+                        continue;
+                    }
+
+                    if RESULT_TOKENS.contains(&word.as_str()) {
+                        return Ok(Some(Token::Result(word)));
+                    }
+
+                    if let Some(san) = self::strip_move_number(&word) {
+                        return Ok(Some(Token::San(san.to_string())));
+                    }
+                }
+            }
+        }
+
+        if variation_depth > 0 {
+            return Err(self::get_pgn_error("Unterminated variation"));
+        }
+
+        Ok(None)
+    }
+
+    /// Read tag pair after opening bracket, eg. `Event "Casual game"]`
+    fn read_tag(&mut self) -> types::ChessResult<Token> {
+        let mut name = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || c == '"' || c == ']' {
+                break;
+            }
+            name.push(c);
+            self.next_char();
+        }
+
+        self.skip_whitespace();
+
+        if self.next_char() != Some('"') || name.is_empty() {
+            return Err(self::get_pgn_error(&format!("Invalid tag {}", name)));
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.next_char() {
+                Some('\\') => match self.next_char() {
+                    Some(escaped) => value.push(escaped),
+                    None => break,
+                },
+                Some('"') => {
+                    self.skip_whitespace();
+                    if self.next_char() == Some(']') {
+                        return Ok(Token::Tag(PgnTag { name, value }));
+                    }
+                    break;
+                }
+                Some(c) => value.push(c),
+                None => break,
+            }
+        }
+
+        Err(self::get_pgn_error(&format!("Unterminated tag {}", name)))
+    }
+
+    fn read_word(&mut self, first: char) -> String {
+        let mut word = String::from(first);
+
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || matches!(c, '{' | '}' | '(' | ')' | '[' | ']' | ';') {
+                break;
+            }
+            word.push(c);
+            self.next_char();
+        }
+
+        word
+    }
+
+    fn skip_until(&mut self, end: char) -> types::ChessResult<()> {
+        while let Some(c) = self.next_char() {
+            if c == end {
+                return Ok(());
+            }
+        }
+
+        Err(self::get_pgn_error("Unterminated comment"))
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.next_char() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let next = self.chars.next();
+        self.previous = next.or(self.previous);
+        next
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.next_char();
+        }
+    }
+}
+
+/// Strip move number prefix (eg. `12.`, `12...`) from a word.
+/// Returns `None` if nothing besides the move number or a NAG is left.
+fn strip_move_number(word: &str) -> Option<&str> {
+    if word.starts_with('$') {
+        return None;
+    }
+
+    let san = word.trim_start_matches(|c: char| c.is_ascii_digit());
+    let san = match san.len() != word.len() && san.starts_with('.') {
+        true => san.trim_start_matches('.'),
+        false => word,
+    };
+    let san = san.trim_start_matches('.');
+
+    match san.is_empty() {
+        true => None,
+        false => Some(san),
+    }
+}
+
+fn get_pgn_error(msg: &str) -> err::ChessError {
+    err::ChessError::InvalidPgn(msg.to_string())
+}
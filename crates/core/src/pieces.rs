@@ -31,7 +31,7 @@ pub enum PieceType {
     BlackKing,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum PieceKind {
     Pawn,
     Rook,
@@ -42,6 +42,22 @@ pub enum PieceKind {
 }
 
 impl PieceType {
+    /// All pieces in the order of declaration
+    pub const ALL: [PieceType; 12] = [
+        PieceType::WhitePawn,
+        PieceType::WhiteRook,
+        PieceType::WhiteBishop,
+        PieceType::WhiteKnight,
+        PieceType::WhiteQueen,
+        PieceType::WhiteKing,
+        PieceType::BlackPawn,
+        PieceType::BlackRook,
+        PieceType::BlackBishop,
+        PieceType::BlackKnight,
+        PieceType::BlackQueen,
+        PieceType::BlackKing,
+    ];
+
     pub fn to_char(&self) -> char {
         match self {
             PieceType::WhitePawn => WHITE_PAWN,
@@ -77,6 +93,23 @@ impl PieceType {
         }
     }
 
+    pub fn from_kind(kind: PieceKind, color: player::Player) -> PieceType {
+        match (kind, color) {
+            (PieceKind::Pawn, player::Player::White) => PieceType::WhitePawn,
+            (PieceKind::Rook, player::Player::White) => PieceType::WhiteRook,
+            (PieceKind::Bishop, player::Player::White) => PieceType::WhiteBishop,
+            (PieceKind::Knight, player::Player::White) => PieceType::WhiteKnight,
+            (PieceKind::Queen, player::Player::White) => PieceType::WhiteQueen,
+            (PieceKind::King, player::Player::White) => PieceType::WhiteKing,
+            (PieceKind::Pawn, player::Player::Black) => PieceType::BlackPawn,
+            (PieceKind::Rook, player::Player::Black) => PieceType::BlackRook,
+            (PieceKind::Bishop, player::Player::Black) => PieceType::BlackBishop,
+            (PieceKind::Knight, player::Player::Black) => PieceType::BlackKnight,
+            (PieceKind::Queen, player::Player::Black) => PieceType::BlackQueen,
+            (PieceKind::King, player::Player::Black) => PieceType::BlackKing,
+        }
+    }
+
     pub fn is_valid_piece_char(c: char) -> bool {
         Self::from_char(c).is_some()
     }
@@ -104,6 +137,24 @@ impl PieceType {
         }
     }
 
+    /// Returns the pieces a pawn of the given color can promote to
+    pub fn promotion_pieces(color: player::Player) -> [PieceType; 4] {
+        match color {
+            player::Player::White => [
+                PieceType::WhiteQueen,
+                PieceType::WhiteRook,
+                PieceType::WhiteBishop,
+                PieceType::WhiteKnight,
+            ],
+            player::Player::Black => [
+                PieceType::BlackQueen,
+                PieceType::BlackRook,
+                PieceType::BlackBishop,
+                PieceType::BlackKnight,
+            ],
+        }
+    }
+
     pub fn is_white(&self) -> bool {
         match self.color() {
             player::Player::White => true,
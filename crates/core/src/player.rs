@@ -18,6 +18,13 @@ impl Player {
         }
     }
 
+    pub fn opponent(&self) -> Player {
+        match &self {
+            Player::White => Player::Black,
+            Player::Black => Player::White,
+        }
+    }
+
     pub fn from_char(c: char) -> Option<Player> {
         match c {
             BLACK_PLAYER => Some(Player::Black),
@@ -0,0 +1,36 @@
+//! # Random Module
+//!
+//! This module holds a small seeded pseudo random generator (SplitMix64),
+//! used wherever moves are picked randomly but must stay reproducible.
+//!
+use crate::zobrist;
+
+/// Deterministic SplitMix64 pseudo random generator
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(zobrist::SPLIT_MIX_INCREMENT);
+        zobrist::split_mix(self.state)
+    }
+
+    /// Random number from `0` to `bound - 1`
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// Random number from `-amplitude` to `amplitude`
+    pub fn noise(&mut self, amplitude: i32) -> i32 {
+        if amplitude == 0 {
+            return 0;
+        }
+
+        self.below(2 * amplitude as u64 + 1) as i32 - amplitude
+    }
+}
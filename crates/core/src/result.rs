@@ -2,27 +2,53 @@
 //!
 //! This module handles game result.
 //!
-//! Valid game results:
+//! Game over results (automatic):
+//! - Variant win (third check in Three-check, king on the hill in King of the Hill)
 //! - Checkmate
 //! - Stalemate
-//! - Insufficient material
+//! - Insufficient material (dead position)
+//! - Seventy-five move rule (unless the last move was checkmate)
+//! - Fivefold repetition (see `get_game_result_from_history`)
+//!
+//! Draws the player on turn can claim (see `get_claimable_draw`):
 //! - Fifty move rule
-//! - Threefold repetition (TODO)
+//! - Threefold repetition
+//!
+//! Endgames covered by a tablebase can be adjudicated by their result with the best play,
+//! see `get_tablebase_result`.
+//!
+//! Repetitions need the game history. Two positions are the same when they have the same board,
+//! player on turn, castling rights (including the castling rooks), Crazyhouse pockets, Three-check checks and effective en passant square
+//! (the en passant square only counts when an en passant capture is actually legal).
 //!
 //! Insufficient material list:
 //! 1. King vs. king
 //! 2. King and bishop vs. king
 //! 3. King and knight vs. king
 //! 4. King and bishop vs. king and bishop (bishops have to be on the same color)
+//!
+//! In Crazyhouse captured pieces return to the board, only bare kings with empty pockets
+//! are insufficient. Variants may change the list, see `Variant::is_insufficient_material`.
 use serde::{Serialize, Deserialize};
 
 use crate::Chess;
+use crate::bitboard;
 use crate::err;
 use crate::pieces;
 use crate::player;
 use crate::square;
+use crate::tablebase;
 use crate::types;
 
+pub const FIFTY_MOVE_RULE_HALF_MOVES: u32 = 100;
+const SEVENTY_FIVE_MOVE_RULE_HALF_MOVES: u32 = 150;
+const THREEFOLD_REPETITION_COUNT: usize = 3;
+const FIVEFOLD_REPETITION_COUNT: usize = 5;
+
+/// Position without the move counters and with the effective en passant square.
+/// Positions with equal keys are repetitions, see `get_repetition_key`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RepetitionKey(types::ParsedFen);
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum GameResult {
@@ -31,14 +57,108 @@ pub enum GameResult {
     Stalemate,
     InsufficientMaterial,
     FiftyMoveRule,
+    SeventyFiveMoveRule,
     ThreefoldRepetition,
+    FivefoldRepetition,
+    /// White resigned, black wins
     WhiteResignation,
+    /// Black resigned, white wins
     BlackResignation,
+    /// Players agreed to a draw
+    DrawByAgreement,
+    /// Game was aborted, there is no winner
+    Aborted,
+    /// White gave the third check in Three-check, white wins
+    WhiteThreeCheck,
+    /// Black gave the third check in Three-check, black wins
+    BlackThreeCheck,
+    /// White king reached the hill in King of the Hill, white wins
+    WhiteKingOfTheHill,
+    /// Black king reached the hill in King of the Hill, black wins
+    BlackKingOfTheHill,
+    /// White exploded the black king in Atomic, white wins
+    WhiteExplosion,
+    /// Black exploded the white king in Atomic, black wins
+    BlackExplosion,
+    /// White lost all pieces or got stalemated in Antichess, white wins
+    WhiteAntichess,
+    /// Black lost all pieces or got stalemated in Antichess, black wins
+    BlackAntichess,
+    /// Tablebase proves a white win with the best play
+    WhiteTablebaseWin,
+    /// Tablebase proves a black win with the best play
+    BlackTablebaseWin,
+    /// Tablebase proves a draw with the best play, including wins spoiled by the fifty-move rule
+    TablebaseDraw,
+}
+
+impl GameResult {
+    /// Returns the PGN game termination marker for the result ("1-0", "0-1", "1/2-1/2" or "*")
+    pub fn to_pgn_result(&self) -> &'static str {
+        match self {
+            GameResult::Aborted => "*",
+            _ => match self.winner() {
+                Some(player::Player::White) => "1-0",
+                Some(player::Player::Black) => "0-1",
+                None => "1/2-1/2",
+            },
+        }
+    }
+
+    /// Returns the player who won the game, `None` for draws and aborted games
+    pub fn winner(&self) -> Option<player::Player> {
+        match self {
+            GameResult::WhiteCheckmate
+            | GameResult::BlackResignation
+            | GameResult::WhiteThreeCheck
+            | GameResult::WhiteKingOfTheHill
+            | GameResult::WhiteExplosion
+            | GameResult::WhiteAntichess
+            | GameResult::WhiteTablebaseWin => Some(player::Player::White),
+            GameResult::BlackCheckmate
+            | GameResult::WhiteResignation
+            | GameResult::BlackThreeCheck
+            | GameResult::BlackKingOfTheHill
+            | GameResult::BlackExplosion
+            | GameResult::BlackAntichess
+            | GameResult::BlackTablebaseWin => Some(player::Player::Black),
+            GameResult::Stalemate
+            | GameResult::InsufficientMaterial
+            | GameResult::FiftyMoveRule
+            | GameResult::SeventyFiveMoveRule
+            | GameResult::ThreefoldRepetition
+            | GameResult::FivefoldRepetition
+            | GameResult::DrawByAgreement
+            | GameResult::TablebaseDraw
+            | GameResult::Aborted => None,
+        }
+    }
+
+    /// Returns the result of the given player resigning
+    pub fn resignation(player: player::Player) -> GameResult {
+        match player {
+            player::Player::White => GameResult::WhiteResignation,
+            player::Player::Black => GameResult::BlackResignation,
+        }
+    }
+
+    /// Returns true for draws that only end the game when claimed by a player
+    pub fn is_claimable_draw(&self) -> bool {
+        matches!(
+            self,
+            GameResult::FiftyMoveRule | GameResult::ThreefoldRepetition
+        )
+    }
 }
 
+/// Gets the game over result for a single position.
+/// Claimable draws are not reported, see `get_claimable_draw`.
 pub fn get_game_result(game: types::ParsedFen) -> types::ChessResult<Option<GameResult>> {
-    if game.state.half_moves >= 100 {
-        return Ok(Some(GameResult::FiftyMoveRule));
+    let rules = game.state.variant.rules();
+    let position = bitboard::Position::from(game);
+
+    if let Some(result) = rules.get_result(&position) {
+        return Ok(Some(result));
     }
 
     let target_king_piece = match game.state.on_turn {
@@ -102,7 +222,7 @@ pub fn get_game_result(game: types::ParsedFen) -> types::ChessResult<Option<Game
         }
     }
 
-    if king_position.is_none() {
+    if king_position.is_none() && rules.is_king_royal() {
         return Err(err::ChessError::InvalidParsedFen(game));
     }
 
@@ -115,21 +235,21 @@ pub fn get_game_result(game: types::ParsedFen) -> types::ChessResult<Option<Game
     }
 
     let is_insufficient = match (player_pieces.len(), opponent_pieces.len()) {
+        _ if game.state.pockets.is_some_and(|pockets| {
+            player_pieces.len() + opponent_pieces.len() > 2
+                || !pockets.white.is_empty()
+                || !pockets.black.is_empty()
+        }) =>
+        {
+            false
+        }
         // King vs King
         (1, 1) => player_has_king && opponent_has_king,
         // King vs King + Bishop || King vs King + Knight
         (1, 2) => {
-            (player_has_king && opponent_has_king && (opponent_has_bishop || opponent_has_knight))
-                || (opponent_has_king
-                    && player_has_king
-                    && (player_has_bishop || player_has_knight))
-        }
-        (2, 1) => {
-            (player_has_king && opponent_has_king && (player_has_bishop || player_has_knight))
-                || (opponent_has_king
-                    && player_has_king
-                    && (opponent_has_bishop || opponent_has_knight))
+            player_has_king && opponent_has_king && (opponent_has_bishop || opponent_has_knight)
         }
+        (2, 1) => player_has_king && opponent_has_king && (player_has_bishop || player_has_knight),
         // King + Bishop || King + Bishop (same color bishops)
         (2, 2) => {
             player_has_king
@@ -148,40 +268,159 @@ pub fn get_game_result(game: types::ParsedFen) -> types::ChessResult<Option<Game
         _ => false,
     };
 
-    if is_insufficient {
+    if rules.is_insufficient_material(&position, is_insufficient) {
         return Ok(Some(GameResult::InsufficientMaterial));
     }
 
-    for piece in player_pieces {
-        let valid_moves = Chess::get_moves(piece, game);
-        if !valid_moves.is_empty() {
-            return Ok(None);
+    if !Chess::get_all_moves(game).is_empty() {
+        if game.state.half_moves >= SEVENTY_FIVE_MOVE_RULE_HALF_MOVES {
+            return Ok(Some(GameResult::SeventyFiveMoveRule));
         }
+
+        return Ok(None);
     }
 
-    for piece in opponent_pieces {
-        let valid_moves = Chess::get_moves(piece, game);
+    if rules.is_check(&position, game.state.on_turn) {
+        let checkmate_by = match game.state.on_turn {
+            player::Player::White => GameResult::BlackCheckmate,
+            player::Player::Black => GameResult::WhiteCheckmate,
+        };
 
-        if valid_moves.is_empty() {
-            continue;
-        }
+        return Ok(Some(checkmate_by));
+    }
 
-        if valid_moves
-            .iter()
-            .find(|m| {
-                m.to_col_idx == king_position.unwrap().col
-                    && m.to_row_idx == king_position.unwrap().row
-            })
-            .is_some()
-        {
-            let checkmate_by = match game.state.on_turn {
-                player::Player::White => GameResult::BlackCheckmate,
-                player::Player::Black => GameResult::WhiteCheckmate,
-            };
+    Ok(Some(GameResult::Stalemate))
+}
 
-            return Ok(Some(checkmate_by));
-        }
+/// Gets the game over result like `get_game_result`, positions covered by the tablebase
+/// are adjudicated by their result with the best play.
+/// Wins the fifty-move rule comes before are adjudicated as draws.
+pub fn get_tablebase_result(
+    game: types::ParsedFen,
+    tablebase: &tablebase::Tablebase,
+) -> types::ChessResult<Option<GameResult>> {
+    if let Some(result) = self::get_game_result(game)? {
+        return Ok(Some(result));
+    }
+
+    let winner = match tablebase.probe_wdl(game) {
+        None => return Ok(None),
+        Some(tablebase::Wdl::Win) => game.state.on_turn,
+        Some(tablebase::Wdl::Loss) => game.state.on_turn.opponent(),
+        Some(_) => return Ok(Some(GameResult::TablebaseDraw)),
+    };
+
+    Ok(Some(match winner {
+        player::Player::White => GameResult::WhiteTablebaseWin,
+        player::Player::Black => GameResult::BlackTablebaseWin,
+    }))
+}
+
+/// Gets the game result for the last position of the history.
+/// On top of `get_game_result`, the game ends automatically with fivefold repetition.
+pub fn get_game_result_from_history(
+    history: &[types::ParsedFen],
+) -> types::ChessResult<Option<GameResult>> {
+    let game = *history.last().ok_or_else(self::get_empty_history_error)?;
+
+    self::get_game_result_with_repetitions(game, &self::get_repetition_keys(history))
+}
+
+/// Gets the game result for the position, which is the last of the repetition keys.
+/// Same as `get_game_result_from_history` for callers keeping one key per ply.
+pub fn get_game_result_with_repetitions(
+    game: types::ParsedFen,
+    repetitions: &[RepetitionKey],
+) -> types::ChessResult<Option<GameResult>> {
+    if let Some(result) = self::get_game_result(game)? {
+        return Ok(Some(result));
+    }
+
+    if self::count_repetitions(repetitions) >= FIVEFOLD_REPETITION_COUNT {
+        return Ok(Some(GameResult::FivefoldRepetition));
+    }
+
+    Ok(None)
+}
+
+/// Gets the draw the player on turn can claim in the last position of the history.
+///
+/// 1. `ThreefoldRepetition` when the position occurred at least three times
+/// 2. `FiftyMoveRule` when no capture or pawn move was made in the last fifty moves
+///
+/// Returns `None` if the game is already over.
+pub fn get_claimable_draw(history: &[types::ParsedFen]) -> types::ChessResult<Option<GameResult>> {
+    let game = *history.last().ok_or_else(self::get_empty_history_error)?;
+
+    self::get_claimable_draw_with_repetitions(game, &self::get_repetition_keys(history))
+}
+
+/// Gets the draw the player on turn can claim in the position, which is the last of the repetition keys.
+/// Same as `get_claimable_draw` for callers keeping one key per ply.
+pub fn get_claimable_draw_with_repetitions(
+    game: types::ParsedFen,
+    repetitions: &[RepetitionKey],
+) -> types::ChessResult<Option<GameResult>> {
+    if self::get_game_result_with_repetitions(game, repetitions)?.is_some() {
+        return Ok(None);
     }
 
-    return Ok(Some(GameResult::Stalemate));
+    if self::count_repetitions(repetitions) >= THREEFOLD_REPETITION_COUNT {
+        return Ok(Some(GameResult::ThreefoldRepetition));
+    }
+
+    if game.state.half_moves >= FIFTY_MOVE_RULE_HALF_MOVES {
+        return Ok(Some(GameResult::FiftyMoveRule));
+    }
+
+    Ok(None)
+}
+
+/// Counts how many times the last position of the history occurred, including itself.
+pub fn get_repetition_count(history: &[types::ParsedFen]) -> usize {
+    self::count_repetitions(&self::get_repetition_keys(history))
+}
+
+/// Gets the part of the position deciding repetitions.
+/// Computing it needs move generation, keep the key of every ply instead of recomputing it.
+pub fn get_repetition_key(game: types::ParsedFen) -> RepetitionKey {
+    let mut key = game;
+
+    key.state.en_passant_square = self::get_effective_en_passant(game);
+    key.state.half_moves = 0;
+    key.state.full_moves = 0;
+
+    RepetitionKey(key)
+}
+
+/// Counts how many times the last key occurred, including itself.
+pub fn count_repetitions(repetitions: &[RepetitionKey]) -> usize {
+    let Some(last) = repetitions.last() else {
+        return 0;
+    };
+
+    repetitions.iter().filter(|key| *key == last).count()
+}
+
+fn get_repetition_keys(history: &[types::ParsedFen]) -> Vec<RepetitionKey> {
+    history
+        .iter()
+        .map(|game| self::get_repetition_key(*game))
+        .collect()
+}
+
+/// Returns the en passant square only if an en passant capture is legal for the player on turn
+fn get_effective_en_passant(game: types::ParsedFen) -> Option<square::Square> {
+    let en_passant_square = game.state.en_passant_square?;
+
+    let is_capture_legal = Chess::get_all_moves(game).iter().any(|m| m.is_passant);
+
+    match is_capture_legal {
+        true => Some(en_passant_square),
+        false => None,
+    }
+}
+
+fn get_empty_history_error() -> err::ChessError {
+    err::ChessError::InvalidHistory("History is empty".to_string())
 }
@@ -0,0 +1,336 @@
+//! # SAN Module
+//!
+//! This module handles encoding and decoding moves in Standard Algebraic Notation (SAN).
+//!
+//! ## Supported notation
+//! - Piece moves with disambiguation by file, rank or both (eg. `Nbd7`, `R1a3`, `Qh4e1`)
+//! - Captures (eg. `exd5`, `Nxe5`)
+//! - Castling (`O-O`, `O-O-O`, `0-0` and `0-0-0` are accepted when decoding)
+//! - Promotions (eg. `e8=Q`, `e8Q` is accepted when decoding)
+//! - Check and checkmate suffixes (`+`, `#`)
+//! - Crazyhouse drops (eg. `N@f3`, `P@e4`, `@e4` is accepted when decoding)
+//!
+//! When decoding, annotations (`!`, `?`) and the `e.p.` suffix are ignored.
+//!
+use crate::err;
+use crate::pieces;
+use crate::player;
+use crate::result;
+use crate::square;
+use crate::types;
+use crate::validation;
+use crate::Chess;
+
+const SHORT_CASTLE: &str = "O-O";
+const LONG_CASTLE: &str = "O-O-O";
+const CAPTURE: char = 'x';
+const PROMOTION: char = '=';
+const CHECK: char = '+';
+const CHECKMATE: char = '#';
+const DROP: char = '@';
+const EN_PASSANT_SUFFIX: &str = "e.p.";
+
+/// Convert a legal move into SAN string.
+pub(super) fn move_to_san(
+    req_move: types::Move,
+    game: types::ParsedFen,
+) -> types::ChessResult<String> {
+    let legal_moves = Chess::get_all_moves(game);
+
+    if !legal_moves.contains(&req_move) {
+        return Err(err::ChessError::InvalidMove(format!(
+            "Cannot convert {:?} to SAN because it's not a legal move",
+            req_move
+        )));
+    }
+
+    let mut san = String::new();
+
+    if req_move.is_castle {
+        san.push_str(match req_move.is_short_castle() {
+            true => SHORT_CASTLE,
+            false => LONG_CASTLE,
+        });
+    } else if req_move.is_drop {
+        let to = square::Square::new(req_move.to_row_idx, req_move.to_col_idx);
+
+        san.push(self::piece_letter(req_move.piece.piece_kind()));
+        san.push(DROP);
+        san.push_str(&self::square_notation(to, game)?);
+    } else {
+        let from = square::Square::new(req_move.from_row_idx, req_move.from_col_idx);
+        let to = square::Square::new(req_move.to_row_idx, req_move.to_col_idx);
+        let from_notation = self::square_notation(from, game)?;
+        let to_notation = self::square_notation(to, game)?;
+        let is_capture =
+            req_move.is_passant || game.is_enemy_square(to.row, to.col, req_move.piece.color());
+
+        match req_move.piece.piece_kind() {
+            pieces::PieceKind::Pawn => {
+                if is_capture {
+                    san.push_str(&from_notation[..1]);
+                }
+            }
+            kind => {
+                san.push(self::piece_letter(kind));
+                san.push_str(&self::disambiguation(
+                    req_move,
+                    &legal_moves,
+                    &from_notation,
+                ));
+            }
+        }
+
+        if is_capture {
+            san.push(CAPTURE);
+        }
+
+        san.push_str(&to_notation);
+
+        if let Some(promotion_piece) = req_move.promotion_piece {
+            san.push(PROMOTION);
+            san.push(self::piece_letter(promotion_piece.piece_kind()));
+        }
+    }
+
+    let next_game = validation::validate_move(req_move, game)?;
+
+    match result::get_game_result(next_game)? {
+        Some(result::GameResult::WhiteCheckmate) | Some(result::GameResult::BlackCheckmate) => {
+            san.push(CHECKMATE)
+        }
+        _ => {
+            if validation::is_king_in_check(next_game, next_game.state.on_turn) {
+                san.push(CHECK)
+            }
+        }
+    }
+
+    Ok(san)
+}
+
+/// Parse SAN string into a legal move for the given game.
+pub(super) fn san_to_move(san: &str, game: types::ParsedFen) -> types::ChessResult<types::Move> {
+    let notation = self::strip_suffixes(san);
+
+    if notation.is_empty() {
+        return Err(self::get_san_error(san));
+    }
+
+    let legal_moves = Chess::get_all_moves(game);
+
+    let candidates: Vec<types::Move> = match notation.replace('0', "O").as_str() {
+        SHORT_CASTLE => legal_moves
+            .into_iter()
+            .filter(|m| m.is_short_castle())
+            .collect(),
+        LONG_CASTLE => legal_moves
+            .into_iter()
+            .filter(|m| m.is_castle && !m.is_short_castle())
+            .collect(),
+        _ if notation.contains(DROP) => {
+            let (kind, to) = self::parse_drop(notation).ok_or_else(|| self::get_san_error(san))?;
+
+            legal_moves
+                .into_iter()
+                .filter(|m| {
+                    m.is_drop
+                        && m.piece.piece_kind() == kind
+                        && m.to_row_idx == to.row
+                        && m.to_col_idx == to.col
+                })
+                .collect()
+        }
+        _ => {
+            let parsed = self::parse_notation(notation, game.state.on_turn)
+                .ok_or_else(|| self::get_san_error(san))?;
+
+            legal_moves
+                .into_iter()
+                .filter(|m| parsed.matches(m))
+                .collect()
+        }
+    };
+
+    match candidates.as_slice() {
+        [single] => Ok(*single),
+        [] => Err(err::ChessError::InvalidMove(format!(
+            "{} is not a legal move",
+            san
+        ))),
+        _ => Err(err::ChessError::InvalidMove(format!(
+            "{} is ambiguous",
+            san
+        ))),
+    }
+}
+
+/// Parsed parts of non castling SAN move
+struct ParsedSan {
+    piece_kind: pieces::PieceKind,
+    from_col: Option<usize>,
+    from_row: Option<usize>,
+    to: square::Square,
+    promotion_piece: Option<pieces::PieceType>,
+}
+
+impl ParsedSan {
+    fn matches(&self, m: &types::Move) -> bool {
+        !m.is_castle
+            && !m.is_drop
+            && m.piece.piece_kind() == self.piece_kind
+            && m.to_row_idx == self.to.row
+            && m.to_col_idx == self.to.col
+            && self.from_col.is_none_or(|col| m.from_col_idx == col)
+            && self.from_row.is_none_or(|row| m.from_row_idx == row)
+            && m.promotion_piece == self.promotion_piece
+    }
+}
+
+/// Parse SAN without castling and suffixes, eg. `Nbxd7`, `e8=Q`, `exd6`
+fn parse_notation(notation: &str, on_turn: player::Player) -> Option<ParsedSan> {
+    let mut chars: Vec<char> = notation.chars().collect();
+
+    // Promotion
+    let mut promotion_piece = None;
+    if let Some(&last) = chars.last() {
+        if let Some(kind) = self::piece_kind_from_letter(last) {
+            chars.pop();
+            if chars.last() == Some(&PROMOTION) {
+                chars.pop();
+            }
+            promotion_piece = Some(pieces::PieceType::from_kind(kind, on_turn));
+        }
+    }
+
+    // Piece
+    let piece_kind = match chars.first().and_then(|&c| self::piece_kind_from_letter(c)) {
+        Some(kind) => {
+            chars.remove(0);
+            kind
+        }
+        None => pieces::PieceKind::Pawn,
+    };
+
+    // Target square
+    if chars.len() < 2 {
+        return None;
+    }
+    let to_notation: String = chars.split_off(chars.len() - 2).into_iter().collect();
+    let to = square::Square::from_chess_notation(&to_notation)?;
+
+    // Capture
+    if chars.last() == Some(&CAPTURE) {
+        chars.pop();
+    }
+
+    // Disambiguation
+    let mut from_col = None;
+    let mut from_row = None;
+    for c in chars {
+        match c {
+            'a'..='h' if from_col.is_none() && from_row.is_none() => {
+                from_col = Some(c as usize - 'a' as usize)
+            }
+            '1'..='8' if from_row.is_none() => {
+                from_row = Some(types::MAX_SIZE_INDEX - (c as usize - '1' as usize))
+            }
+            _ => return None,
+        }
+    }
+
+    Some(ParsedSan {
+        piece_kind,
+        from_col,
+        from_row,
+        to,
+        promotion_piece,
+    })
+}
+
+/// Parse Crazyhouse drop without suffixes, eg. `N@f3`, `P@e4` or `@e4`
+fn parse_drop(notation: &str) -> Option<(pieces::PieceKind, square::Square)> {
+    let (piece, to) = notation.split_once(DROP)?;
+    let kind = match piece {
+        "" | "P" => pieces::PieceKind::Pawn,
+        _ if piece.len() == 1 => piece
+            .chars()
+            .next()
+            .and_then(self::piece_kind_from_letter)?,
+        _ => return None,
+    };
+
+    Some((kind, square::Square::from_chess_notation(to)?))
+}
+
+/// Get the shortest origin prefix that identifies the move among other legal moves
+/// of the same piece kind to the same square.
+fn disambiguation(
+    req_move: types::Move,
+    legal_moves: &[types::Move],
+    from_notation: &str,
+) -> String {
+    let rivals: Vec<&types::Move> = legal_moves
+        .iter()
+        .filter(|m| {
+            m.piece == req_move.piece
+                && m.to_row_idx == req_move.to_row_idx
+                && m.to_col_idx == req_move.to_col_idx
+                && (m.from_row_idx != req_move.from_row_idx
+                    || m.from_col_idx != req_move.from_col_idx)
+        })
+        .collect();
+
+    if rivals.is_empty() {
+        return String::new();
+    }
+
+    if rivals
+        .iter()
+        .all(|m| m.from_col_idx != req_move.from_col_idx)
+    {
+        return from_notation[..1].to_string();
+    }
+
+    if rivals
+        .iter()
+        .all(|m| m.from_row_idx != req_move.from_row_idx)
+    {
+        return from_notation[1..].to_string();
+    }
+
+    from_notation.to_string()
+}
+
+/// Remove check, checkmate, annotation and en passant suffixes
+fn strip_suffixes(san: &str) -> &str {
+    let trimmed = san.trim();
+    let trimmed = trimmed
+        .strip_suffix(EN_PASSANT_SUFFIX)
+        .map(|s| s.trim_end())
+        .unwrap_or(trimmed);
+
+    trimmed.trim_end_matches([CHECK, CHECKMATE, '!', '?'])
+}
+
+/// Get SAN letter of a piece kind (uppercase)
+fn piece_letter(kind: pieces::PieceKind) -> char {
+    pieces::PieceType::from_kind(kind, player::Player::White).to_char()
+}
+
+/// Get piece kind from SAN letter, pawns have no letter
+fn piece_kind_from_letter(c: char) -> Option<pieces::PieceKind> {
+    pieces::PieceType::from_char(c)
+        .filter(|piece| piece.is_white())
+        .map(|piece| piece.piece_kind())
+        .filter(|kind| *kind != pieces::PieceKind::Pawn)
+}
+
+fn square_notation(sq: square::Square, game: types::ParsedFen) -> types::ChessResult<String> {
+    sq.to_chess_notation()
+        .ok_or(err::ChessError::InvalidParsedFen(game))
+}
+
+fn get_san_error(san: &str) -> err::ChessError {
+    err::ChessError::InvalidSan(san.to_string())
+}
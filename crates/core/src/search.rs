@@ -0,0 +1,616 @@
+//! # Search Module
+//!
+//! This module chooses the best move of a position.
+//!
+//! ## Algorithm
+//! - Iterative deepening negamax with alpha-beta pruning
+//! - Quiescence search on captures and promotions to avoid the horizon effect
+//! - Transposition table with results of already searched positions, kept between searches
+//! - Move ordering: transposition table or principal variation move, captures by MVV-LVA,
+//!   killer moves, history heuristic
+//! - Repetitions inside the searched line and the fifty-move rule are scored as draws
+//! - Games decided by the variant rules (eg. third check) are scored like checkmates
+//!
+//! The search stops at the first reached limit (depth, nodes or milliseconds).
+//! Only fully searched iterations are reported, so the result is always consistent.
+//!
+use crate::bitboard;
+use crate::evaluation;
+use crate::pieces;
+use crate::player;
+use crate::result;
+use crate::tablebase;
+use crate::transposition;
+use crate::types;
+use serde::{Deserialize, Serialize};
+
+/// Score of a checkmate at the root, mates further away score lower
+pub const MATE_SCORE: i32 = 30_000;
+/// Depth used when no limit is given
+pub const DEFAULT_DEPTH: u32 = 4;
+/// Score of a tablebase win with zero distance to zeroing, higher distances score lower
+pub const TABLEBASE_WIN_SCORE: i32 = 20_000;
+/// Maximum depth of the search including quiescence
+pub const MAX_PLY: usize = 64;
+
+const INFINITY: i32 = 32_000;
+/// How often (in nodes) the clock is checked
+const CLOCK_CHECK_INTERVAL: u64 = 1024;
+
+/// Clock returning milliseconds since an arbitrary fixed point
+pub type Clock = fn() -> f64;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+/// Limits of the search, it stops at the first one reached.
+/// Without any limit the search runs to `DEFAULT_DEPTH`.
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub time_ms: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+/// Result of the last fully searched iteration
+pub struct SearchResult {
+    /// `None` if the player on turn has no legal move
+    pub best_move: Option<types::Move>,
+    /// Centipawns from the perspective of the player on turn
+    pub score: i32,
+    /// Moves to checkmate, positive if the player on turn mates, negative if gets mated
+    pub mate_in: Option<i32>,
+    /// Expected line of play starting with the best move
+    pub principal_variation: Vec<types::Move>,
+    /// Searched nodes of all iterations
+    pub nodes: u64,
+    /// Depth of the last fully searched iteration
+    pub depth: u32,
+}
+
+/// Search the best move of a position within the limits, reusing results stored in the table
+pub fn search(
+    game: types::ParsedFen,
+    limits: SearchLimits,
+    clock: Clock,
+    table: &mut transposition::TranspositionTable,
+) -> SearchResult {
+    table.new_search();
+
+    let mut searcher = Searcher::new(game, limits, clock, table);
+    searcher.iterative_deepening()
+}
+
+/// Search like `search`, positions covered by the tablebase are answered by it without searching.
+/// Tablebase results have no principal variation beyond the best move and report depth 0.
+pub fn search_with_tablebase(
+    game: types::ParsedFen,
+    limits: SearchLimits,
+    clock: Clock,
+    table: &mut transposition::TranspositionTable,
+    tablebase: &tablebase::Tablebase,
+) -> SearchResult {
+    let root = tablebase
+        .best_move(game)
+        .and_then(|best_move| Some((best_move, tablebase.probe(game)?)));
+
+    let Some((best_move, probe)) = root else {
+        return self::search(game, limits, clock, table);
+    };
+
+    let distance = probe.dtz.unwrap_or(0).abs();
+    let score = match probe.wdl {
+        tablebase::Wdl::Win => TABLEBASE_WIN_SCORE - distance,
+        // the fifty-move rule draws the game first, keep the side with better chances ahead
+        tablebase::Wdl::CursedWin => 1,
+        tablebase::Wdl::Draw => 0,
+        tablebase::Wdl::BlessedLoss => -1,
+        tablebase::Wdl::Loss => distance - TABLEBASE_WIN_SCORE,
+    };
+
+    SearchResult {
+        best_move: Some(best_move),
+        score,
+        mate_in: None,
+        principal_variation: vec![best_move],
+        nodes: 0,
+        depth: 0,
+    }
+}
+
+/// Score every legal move of a position by searching it to the depth, including the move itself.
+/// Scores are exact (not bounded by alpha-beta) and from the perspective of the player on turn,
+/// moves are sorted from the best.
+pub fn score_moves(
+    game: types::ParsedFen,
+    depth: u32,
+    table: &mut transposition::TranspositionTable,
+) -> Vec<(types::Move, i32)> {
+    let limits = SearchLimits {
+        depth: Some(depth),
+        ..SearchLimits::default()
+    };
+    table.new_search();
+
+    let mut searcher = Searcher::new(game, limits, self::system_clock, table);
+    let mut scored = searcher.score_root_moves(depth.clamp(1, MAX_PLY as u32 - 1));
+
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored
+}
+
+/// Clock based on `std::time::Instant`, not available on `wasm32-unknown-unknown`
+pub fn system_clock() -> f64 {
+    static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+
+    EPOCH
+        .get_or_init(std::time::Instant::now)
+        .elapsed()
+        .as_secs_f64()
+        * 1000.0
+}
+
+struct Searcher<'a> {
+    position: bitboard::Position,
+    table: &'a mut transposition::TranspositionTable,
+    limits: SearchLimits,
+    clock: Clock,
+    deadline: Option<f64>,
+    nodes: u64,
+    stopped: bool,
+    /// Hashes of positions on the searched line, used for repetition detection
+    hashes: Vec<u64>,
+    /// Two quiet moves per ply that caused a beta cutoff
+    killers: [[Option<types::Move>; 2]; MAX_PLY],
+    /// Scores of quiet moves by piece and target square that caused a beta cutoff
+    history: [[i32; 64]; 12],
+    /// Triangular table of principal variations per ply
+    pv_table: Vec<Vec<types::Move>>,
+    /// Principal variation of the previous iteration, searched first
+    previous_pv: Vec<types::Move>,
+}
+
+impl<'a> Searcher<'a> {
+    fn new(
+        game: types::ParsedFen,
+        limits: SearchLimits,
+        clock: Clock,
+        table: &'a mut transposition::TranspositionTable,
+    ) -> Self {
+        // clock is read only with a time limit, so it's never called otherwise
+        let deadline = limits.time_ms.map(|time_ms| clock() + time_ms as f64);
+
+        Self {
+            position: bitboard::Position::from(game),
+            table,
+            limits,
+            clock,
+            deadline,
+            nodes: 0,
+            stopped: false,
+            hashes: Vec::new(),
+            killers: [[None; 2]; MAX_PLY],
+            history: [[0; 64]; 12],
+            pv_table: vec![Vec::new(); MAX_PLY + 1],
+            previous_pv: Vec::new(),
+        }
+    }
+
+    fn iterative_deepening(&mut self) -> SearchResult {
+        let root_moves = self.legal_moves();
+        let max_depth = match self.limits {
+            SearchLimits {
+                depth: None,
+                nodes: None,
+                time_ms: None,
+            } => DEFAULT_DEPTH,
+            SearchLimits { depth, .. } => depth.unwrap_or(MAX_PLY as u32),
+        }
+        .clamp(1, MAX_PLY as u32 - 1);
+
+        let mut result = SearchResult {
+            best_move: root_moves.first().copied(),
+            score: 0,
+            mate_in: None,
+            principal_variation: root_moves.first().copied().into_iter().collect(),
+            nodes: 0,
+            depth: 0,
+        };
+
+        if root_moves.is_empty() {
+            let rules = self.position.state.variant.rules();
+            let is_check = rules.is_check(&self.position, self.position.state.on_turn);
+            result.score = match self.variant_score(0) {
+                Some(score) => score,
+                None if is_check => -MATE_SCORE,
+                None => 0,
+            };
+            result.mate_in = (result.score != 0).then_some(0);
+            return result;
+        }
+
+        for depth in 1..=max_depth {
+            let score = self.negamax(depth, 0, -INFINITY, INFINITY);
+
+            if self.stopped {
+                break;
+            }
+
+            result.score = score;
+            result.mate_in = self::mate_in(score);
+            result.principal_variation = self.pv_table[0].clone();
+            self.previous_pv = result.principal_variation.clone();
+            result.best_move = result.principal_variation.first().copied();
+            result.depth = depth;
+
+            // no need to search deeper once a forced mate is found
+            if result.mate_in.is_some() {
+                break;
+            }
+        }
+
+        result.nodes = self.nodes;
+        result
+    }
+
+    fn score_root_moves(&mut self, depth: u32) -> Vec<(types::Move, i32)> {
+        let mut moves = self.legal_moves();
+        self.order_moves(&mut moves, 0, None);
+
+        moves
+            .into_iter()
+            .map(|req_move| {
+                self.hashes.push(self.position.hash());
+                let undo = self.position.make_move(req_move);
+                let score = -self.negamax(depth - 1, 1, -INFINITY, INFINITY);
+                self.position.unmake_move(undo);
+                self.hashes.pop();
+
+                (req_move, score)
+            })
+            .collect()
+    }
+
+    fn negamax(&mut self, depth: u32, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.pv_table[ply].clear();
+
+        if ply > 0 && self.is_draw() {
+            return 0;
+        }
+
+        if let Some(score) = self.variant_score(ply) {
+            return score;
+        }
+
+        if depth == 0 || ply >= MAX_PLY - 1 {
+            return self.quiescence(ply, alpha, beta);
+        }
+
+        self.count_node();
+        if self.stopped {
+            return 0;
+        }
+
+        let hash = self.position.hash();
+        let table_entry = self.table.probe(hash).copied();
+
+        // root always searches, so the best move and principal variation are known
+        if let Some(entry) = table_entry.filter(|entry| ply > 0 && u32::from(entry.depth) >= depth)
+        {
+            let score = self::score_from_table(entry.score.into(), ply);
+
+            match entry.bound {
+                transposition::Bound::Exact => return score.clamp(alpha, beta),
+                transposition::Bound::Lower if score >= beta => return beta,
+                transposition::Bound::Upper if score <= alpha => return alpha,
+                _ => {}
+            }
+        }
+
+        let on_turn = self.position.state.on_turn;
+        let mut moves = self.legal_moves();
+
+        if moves.is_empty() {
+            let rules = self.position.state.variant.rules();
+            return match rules.is_check(&self.position, on_turn) {
+                true => -MATE_SCORE + ply as i32,
+                false => 0,
+            };
+        }
+
+        let table_move = table_entry
+            .and_then(|entry| entry.best_move)
+            .and_then(|packed| packed.unpack(&moves));
+        let pv_move = table_move.or(self.previous_pv.get(ply).copied());
+        self.order_moves(&mut moves, ply, pv_move);
+
+        let mut best_move = None;
+
+        for req_move in moves {
+            let is_quiet = self.is_quiet(req_move);
+
+            self.hashes.push(hash);
+            let undo = self.position.make_move(req_move);
+            let score = -self.negamax(depth - 1, ply + 1, -beta, -alpha);
+            self.position.unmake_move(undo);
+            self.hashes.pop();
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score >= beta {
+                if is_quiet {
+                    self.store_killer(req_move, ply);
+                    self.history[req_move.piece as usize][self::target_square(req_move)] +=
+                        (depth * depth) as i32;
+                }
+
+                let score = self::score_to_table(beta, ply);
+                self.table.store(
+                    hash,
+                    depth,
+                    score,
+                    transposition::Bound::Lower,
+                    Some(req_move),
+                );
+                return beta;
+            }
+
+            if score > alpha {
+                alpha = score;
+                best_move = Some(req_move);
+                self.update_pv(req_move, ply);
+            }
+        }
+
+        let bound = match best_move {
+            Some(_) => transposition::Bound::Exact,
+            None => transposition::Bound::Upper,
+        };
+        let score = self::score_to_table(alpha, ply);
+        self.table
+            .store(hash, depth, score, bound, best_move.or(table_move));
+
+        alpha
+    }
+
+    fn quiescence(&mut self, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.pv_table[ply].clear();
+        self.count_node();
+        if self.stopped {
+            return 0;
+        }
+
+        if let Some(score) = self.variant_score(ply) {
+            return score;
+        }
+
+        let rules = self.position.state.variant.rules();
+        let in_check = rules.is_check(&self.position, self.position.state.on_turn);
+
+        // in check the player can't stand pat, every evasion is searched
+        if !in_check {
+            let stand_pat = self::evaluate(&self.position);
+
+            if stand_pat >= beta {
+                return beta;
+            }
+            alpha = alpha.max(stand_pat);
+        }
+
+        if ply >= MAX_PLY - 1 {
+            return match in_check {
+                true => self::evaluate(&self.position).clamp(alpha, beta),
+                false => alpha,
+            };
+        }
+
+        let mut moves = self.legal_moves();
+
+        if in_check && moves.is_empty() {
+            return -MATE_SCORE + ply as i32;
+        }
+
+        if !in_check {
+            moves.retain(|m| !self.is_quiet(*m));
+        }
+        self.order_moves(&mut moves, ply, None);
+
+        for req_move in moves {
+            let undo = self.position.make_move(req_move);
+            let score = -self.quiescence(ply + 1, -beta, -alpha);
+            self.position.unmake_move(undo);
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score >= beta {
+                return beta;
+            }
+
+            if score > alpha {
+                alpha = score;
+                self.update_pv(req_move, ply);
+            }
+        }
+
+        alpha
+    }
+
+    /// Score of a game decided by the variant rules, from the perspective of the player on turn
+    fn variant_score(&self, ply: usize) -> Option<i32> {
+        let rules = self.position.state.variant.rules();
+        let result = rules.get_result(&self.position)?;
+
+        Some(match result.winner() {
+            Some(winner) if winner == self.position.state.on_turn => MATE_SCORE - ply as i32,
+            Some(_) => -MATE_SCORE + ply as i32,
+            None => 0,
+        })
+    }
+
+    fn legal_moves(&self) -> Vec<types::Move> {
+        self.position.all_legal_moves(self.position.state.on_turn)
+    }
+
+    /// Order moves by PV move, captures (MVV-LVA), killers and history
+    fn order_moves(&self, moves: &mut [types::Move], ply: usize, pv_move: Option<types::Move>) {
+        moves.sort_by_cached_key(|m| -self.move_order_score(*m, ply, pv_move));
+    }
+
+    fn move_order_score(
+        &self,
+        req_move: types::Move,
+        ply: usize,
+        pv_move: Option<types::Move>,
+    ) -> i32 {
+        if pv_move == Some(req_move) {
+            return 2_000_000;
+        }
+
+        if let Some(victim) = self.captured_piece(req_move) {
+            // most valuable victim first, then least valuable attacker
+            return 1_000_000 + 10 * self::piece_value(victim.piece_kind())
+                - self::piece_value(req_move.piece.piece_kind());
+        }
+
+        if let Some(promotion_piece) = req_move.promotion_piece {
+            return 900_000 + self::piece_value(promotion_piece.piece_kind());
+        }
+
+        if self.killers[ply][0] == Some(req_move) {
+            return 800_000;
+        }
+
+        if self.killers[ply][1] == Some(req_move) {
+            return 700_000;
+        }
+
+        self.history[req_move.piece as usize][self::target_square(req_move)]
+    }
+
+    fn captured_piece(&self, req_move: types::Move) -> Option<pieces::PieceType> {
+        if req_move.is_passant {
+            return Some(pieces::PieceType::from_kind(
+                pieces::PieceKind::Pawn,
+                req_move.piece.color().opponent(),
+            ));
+        }
+
+        self.position.piece_at(self::target_square(req_move))
+    }
+
+    fn is_quiet(&self, req_move: types::Move) -> bool {
+        req_move.promotion_piece.is_none() && self.captured_piece(req_move).is_none()
+    }
+
+    fn store_killer(&mut self, req_move: types::Move, ply: usize) {
+        if self.killers[ply][0] != Some(req_move) {
+            self.killers[ply][1] = self.killers[ply][0];
+            self.killers[ply][0] = Some(req_move);
+        }
+    }
+
+    fn update_pv(&mut self, req_move: types::Move, ply: usize) {
+        let (current, rest) = self.pv_table.split_at_mut(ply + 1);
+        let pv = &mut current[ply];
+
+        pv.clear();
+        pv.push(req_move);
+        pv.extend_from_slice(&rest[0]);
+    }
+
+    fn is_draw(&self) -> bool {
+        let state = &self.position.state;
+
+        if state.half_moves >= result::FIFTY_MOVE_RULE_HALF_MOVES {
+            return true;
+        }
+
+        // positions can repeat only since the last capture or pawn move,
+        // and only with the same player on turn
+        let hash = self.position.hash();
+        self.hashes
+            .iter()
+            .rev()
+            .take(state.half_moves as usize)
+            .skip(1)
+            .step_by(2)
+            .any(|previous| *previous == hash)
+    }
+
+    fn count_node(&mut self) {
+        self.nodes += 1;
+
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes) {
+            self.stopped = true;
+        }
+
+        if self.nodes.is_multiple_of(CLOCK_CHECK_INTERVAL) {
+            if let Some(deadline) = self.deadline {
+                if (self.clock)() >= deadline {
+                    self.stopped = true;
+                }
+            }
+        }
+    }
+}
+
+/// Static evaluation from the perspective of the player on turn
+fn evaluate(position: &bitboard::Position) -> i32 {
+    let score = evaluation::evaluate_position(position, &evaluation::DEFAULT_WEIGHTS);
+
+    match position.state.on_turn {
+        player::Player::White => score,
+        player::Player::Black => -score,
+    }
+}
+
+/// Piece value used for ordering captures
+fn piece_value(kind: pieces::PieceKind) -> i32 {
+    match kind {
+        pieces::PieceKind::Pawn => 100,
+        pieces::PieceKind::Knight => 320,
+        pieces::PieceKind::Bishop => 330,
+        pieces::PieceKind::Rook => 500,
+        pieces::PieceKind::Queen => 900,
+        pieces::PieceKind::King => 0,
+    }
+}
+
+/// Convert mate score into number of moves to mate
+fn mate_in(score: i32) -> Option<i32> {
+    let plies = MATE_SCORE - score.abs();
+
+    if plies > MAX_PLY as i32 {
+        return None;
+    }
+
+    let moves = (plies + 1) / 2;
+    match score > 0 {
+        true => Some(moves),
+        false => Some(-moves),
+    }
+}
+
+/// Mate scores are stored relative to the stored position instead of the root
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > MATE_SCORE - MAX_PLY as i32 => score + ply as i32,
+        score if score < -MATE_SCORE + MAX_PLY as i32 => score - ply as i32,
+        score => score,
+    }
+}
+
+/// Convert stored mate score back to be relative to the root
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > MATE_SCORE - MAX_PLY as i32 => score - ply as i32,
+        score if score < -MATE_SCORE + MAX_PLY as i32 => score + ply as i32,
+        score => score,
+    }
+}
+
+fn target_square(req_move: types::Move) -> usize {
+    bitboard::square_index(req_move.to_row_idx, req_move.to_col_idx)
+}
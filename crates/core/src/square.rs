@@ -50,7 +50,7 @@ impl Square {
     /// Parse rank character ('1'-'8') to 0-based index (0-7)
     /// Note: rank '1' maps to row 7 (bottom), rank '8' maps to row 0 (top)
     fn rank_char_to_index(rank: char) -> Option<usize> {
-        if rank >= '1' && rank <= '8' {
+        if ('1'..='8').contains(&rank) {
             Some(7 - ((rank as usize) - ('1' as usize)))
         } else {
             None
@@ -59,7 +59,7 @@ impl Square {
 
     /// Parse file character ('a'-'h') to 0-based index (0-7)
     fn file_char_to_index(file: char) -> Option<usize> {
-        if file >= 'a' && file <= 'h' {
+        if ('a'..='h').contains(&file) {
             let index = (file as usize) - ('a' as usize);
             Some(index)
         } else {
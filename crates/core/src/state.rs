@@ -1,3 +1,5 @@
+use crate::bitboard;
+use crate::err;
 use crate::pieces;
 use crate::player;
 use crate::square;
@@ -10,10 +12,14 @@ pub (super) fn get_next(
     let next_board = self::get_next_board(req_move, game)?;
     let next_state = self::get_next_state(req_move, game);
 
-    Ok(types::ParsedFen {
+    // variant state depends on the next board, eg. checks given in Three-check
+    let mut next = bitboard::Position::from(types::ParsedFen {
         board: next_board,
         state: next_state,
-    })
+    });
+    game.state.variant.rules().update_state(&mut next);
+
+    Ok(types::ParsedFen::from(next))
 }
 
 pub fn get_next_board(
@@ -22,48 +28,50 @@ pub fn get_next_board(
 ) -> types::ChessResult<types::Board> {
     let mut next = game;
 
+    //castle, both pieces leave first, in Chess960 the king may land on the rook square
+    let castle_rook_cols = req_move.is_castle.then(|| {
+        let is_short = req_move.is_short_castle();
+        let rook_target_col = match is_short {
+            true => types::KING_SHORT_CASTLE_COL - 1,
+            false => types::KING_LONG_CASTLE_COL + 1,
+        };
+        (game.state.castle_rooks.col(is_short), rook_target_col)
+    });
+    let rook_piece = pieces::PieceType::from_kind(pieces::PieceKind::Rook, req_move.piece.color());
+
     // target, source swap
     next.assign_square(req_move.from_row_idx, req_move.from_col_idx, None)?;
+    if let Some((rook_source_col, _)) = castle_rook_cols {
+        next.assign_square(req_move.to_row_idx, rook_source_col, None)?;
+    }
     next.assign_square(
         req_move.to_row_idx,
         req_move.to_col_idx,
         Some(req_move.piece),
     )?;
+    if let Some((_, rook_target_col)) = castle_rook_cols {
+        next.assign_square(req_move.to_row_idx, rook_target_col, Some(rook_piece))?;
+    }
 
     // passant
     if req_move.is_passant {
         next.assign_square(req_move.from_row_idx, req_move.to_col_idx, None)?;
     }
-    //castle
-    if req_move.is_castle {
-        let (rook_source_col, rook_target_col) = match req_move.to_col_idx {
-            6 => (7, 5),
-            _ => (0, 3),
-        };
-        let rook_piece = match req_move.piece.color() {
-            player::Player::White => pieces::PieceType::WhiteRook,
-            player::Player::Black => pieces::PieceType::BlackRook,
-        };
-
-        next.assign_square(req_move.to_row_idx, rook_source_col, None)?;
-        next.assign_square(req_move.to_row_idx, rook_target_col, Some(rook_piece))?;
-    }
 
-    // TODO replace promotion_piece with piece from player
-    let (promotion_row, allowed_upg_piece, promotion_piece) = match req_move.piece.color() {
-        player::Player::White => (
-            0,
-            pieces::PieceType::WhitePawn,
-            pieces::PieceType::WhiteQueen,
-        ),
-        player::Player::Black => (
-            7,
-            pieces::PieceType::BlackPawn,
-            pieces::PieceType::BlackQueen,
-        ),
+    // promotion
+    let (promotion_row, allowed_upg_piece) = match req_move.piece.color() {
+        player::Player::White => (0, pieces::PieceType::WhitePawn),
+        player::Player::Black => (7, pieces::PieceType::BlackPawn),
     };
 
     if req_move.to_row_idx == promotion_row && req_move.piece == allowed_upg_piece {
+        let promotion_piece = req_move.promotion_piece.ok_or_else(|| {
+            err::ChessError::InvalidMove(format!(
+                "Promotion piece is missing for {:?}",
+                req_move.piece
+            ))
+        })?;
+
         next.assign_square(
             req_move.to_row_idx,
             req_move.to_col_idx,
@@ -71,6 +79,23 @@ pub fn get_next_board(
         )?;
     };
 
+    // explosion of the capture in Atomic, including the capturing piece
+    let is_capture = req_move.is_passant
+        || game.is_enemy_square(
+            req_move.to_row_idx,
+            req_move.to_col_idx,
+            req_move.piece.color(),
+        );
+
+    if is_capture {
+        let position = bitboard::Position::from(next);
+        let target = bitboard::square_index(req_move.to_row_idx, req_move.to_col_idx);
+
+        for sq in bitboard::squares(game.state.variant.rules().explosion(&position, target)) {
+            next.assign_square(sq / 8, sq % 8, None)?;
+        }
+    }
+
     Ok(next.board)
 }
 
@@ -83,6 +108,11 @@ pub fn get_next_state(req_move: types::Move, game: types::ParsedFen) -> types::P
 
     let (can_castle_short, can_castle_long) = game.get_castle_ability(piece_color);
 
+    let (initial_row, opponent_initial_row) = match is_white {
+        true => (types::MAX_SIZE_INDEX, 0),
+        false => (0, types::MAX_SIZE_INDEX),
+    };
+
     // castle block
     if can_castle_long || can_castle_short {
         match req_move.piece {
@@ -90,16 +120,16 @@ pub fn get_next_state(req_move: types::Move, game: types::ParsedFen) -> types::P
                 next_state.castle_black_long = false;
                 next_state.castle_black_short = false;
             }
-            pieces::PieceType::WhiteRook | pieces::PieceType::BlackRook => {
-                if req_move.from_col_idx == 0 {
+            pieces::PieceType::WhiteRook | pieces::PieceType::BlackRook
+                if req_move.from_row_idx == initial_row && !req_move.is_drop =>
+            {
+                if req_move.from_col_idx == game.state.castle_rooks.long_col {
                     if is_white {
                         next_state.castle_white_long = false
                     } else {
                         next_state.castle_black_long = false
                     }
-                }
-
-                if req_move.from_col_idx == 7 {
+                } else if req_move.from_col_idx == game.state.castle_rooks.short_col {
                     if is_white {
                         next_state.castle_white_short = false
                     } else {
@@ -114,7 +144,51 @@ pub fn get_next_state(req_move: types::Move, game: types::ParsedFen) -> types::P
             _ => {}
         }
     }
-    // passant block
+    // capturing a rook on its initial square removes opponent castling rights
+    if req_move.to_row_idx == opponent_initial_row {
+        let captures_rook = game
+            .safe_access_square(req_move.to_row_idx, req_move.to_col_idx)
+            .is_ok_and(|piece| {
+                piece.is_some_and(|piece| {
+                    piece
+                        == pieces::PieceType::from_kind(
+                            pieces::PieceKind::Rook,
+                            piece_color.opponent(),
+                        )
+                })
+            });
+
+        if captures_rook {
+            let rooks = game.state.castle_rooks;
+
+            match (is_white, req_move.to_col_idx) {
+                (true, col) if col == rooks.long_col => next_state.castle_black_long = false,
+                (true, col) if col == rooks.short_col => next_state.castle_black_short = false,
+                (false, col) if col == rooks.long_col => next_state.castle_white_long = false,
+                (false, col) if col == rooks.short_col => next_state.castle_white_short = false,
+                _ => {}
+            }
+        }
+    }
+
+    // pockets, the pawn captured en passant stands next to the target square
+    if let Some(pockets) = next_state.pockets.as_mut() {
+        let captured_row = match req_move.is_passant {
+            true => req_move.from_row_idx,
+            false => req_move.to_row_idx,
+        };
+        let captured = game
+            .safe_access_square(captured_row, req_move.to_col_idx)
+            .ok()
+            .flatten()
+            .filter(|piece| piece.color() != piece_color)
+            .map(|piece| (captured_row, req_move.to_col_idx, piece));
+
+        pockets.apply_move(&req_move, captured);
+    }
+
+    // passant block, en passant square is only available right after a double pawn push
+    next_state.en_passant_square = None;
     if piece_kind == pieces::PieceKind::Pawn {
         let row_diff = req_move.to_row_idx.abs_diff(req_move.from_row_idx);
 
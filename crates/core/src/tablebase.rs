@@ -0,0 +1,893 @@
+//! # Tablebase Module
+//!
+//! This module probes endgame tablebases in a Syzygy-style layout. Every material (eg. `KQvK`)
+//! has a WDL table with win, draw or loss of every position and a DTZ table with the distance
+//! to zeroing: plies until a capture, a pawn move or checkmate with the best play.
+//! Tables are supplied as byte buffers, so they work in WASM without a filesystem.
+//!
+//! Tables use an own simple format, they are not compatible with Syzygy `.rtbw` / `.rtbz` files.
+//! `Tablebase::generate` builds both tables of a material by retrograde analysis,
+//! using already loaded tables of materials reached by captures and promotions.
+//!
+//! ## File format
+//! - magic `TCTB`, table kind `W` (WDL) or `Z` (DTZ) and format version, one byte each
+//! - length of the material key and the key itself (eg. `KQvK`)
+//! - number of positions (`u32`, little-endian)
+//! - WDL: 2 bits per position (0 = invalid, 1 = loss, 2 = draw, 3 = win of the player on turn)
+//! - DTZ: absolute distance to zeroing in plies, `u8` per position when every distance fits,
+//!   otherwise `u16` (little-endian)
+//!
+//! Positions are indexed by the player on turn and squares of pieces in material order
+//! (white pieces first, then king, queen, rook, bishop, knight, pawn). The board is mirrored
+//! so the white king stands in the a1-d1-d4 triangle, or on files a-d if there are pawns.
+//!
+//! Tables don't cover castling rights, en passant captures, Crazyhouse pockets and variants,
+//! such positions are not probed.
+//! Tables ignore the fifty-move rule, probes apply it with the half-move clock of the position:
+//! a win whose zeroing comes after the 100th half-move is a `CursedWin`, the loser can claim a draw.
+//!
+use crate::bitboard;
+use crate::err;
+use crate::pieces;
+use crate::player;
+use crate::result;
+use crate::types;
+use crate::variant;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Largest number of pieces (including kings) probed by default
+pub const DEFAULT_MAX_PIECES: usize = 5;
+
+const MAGIC: &[u8; 4] = b"TCTB";
+const VERSION: u8 = 1;
+const WDL_KIND: u8 = b'W';
+const DTZ_KIND: u8 = b'Z';
+
+const INVALID: u8 = 0;
+const LOSS: u8 = 1;
+const DRAW: u8 = 2;
+const WIN: u8 = 3;
+/// Position not resolved yet during generation, never stored in a table
+const UNKNOWN: u8 = 4;
+
+const SQUARES: usize = types::BOARD_SIZE * types::BOARD_SIZE;
+const PAWN_KING_SQUARES: usize = SQUARES / 2;
+
+/// Squares of the white king in pawnless tables, the a1-d1-d4 triangle
+const TRIANGLE: [usize; 10] = [56, 57, 58, 59, 49, 50, 51, 42, 43, 35];
+
+/// Order of piece kinds in a material key
+const KIND_ORDER: [pieces::PieceKind; 6] = [
+    pieces::PieceKind::King,
+    pieces::PieceKind::Queen,
+    pieces::PieceKind::Rook,
+    pieces::PieceKind::Bishop,
+    pieces::PieceKind::Knight,
+    pieces::PieceKind::Pawn,
+];
+
+/// Result of a position for the player on turn
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Wdl {
+    Loss,
+    /// Lost, but the fifty-move rule draws the game before the opponent converts the win
+    BlessedLoss,
+    Draw,
+    /// Won, but the fifty-move rule draws the game before the win is converted
+    CursedWin,
+    Win,
+}
+
+/// Tablebase answer for a position
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct TablebaseProbe {
+    pub wdl: Wdl,
+    /// Plies to zeroing, positive when winning, negative when losing, 0 for draws
+    /// and checkmated positions. `None` if the DTZ table is not loaded.
+    pub dtz: Option<i32>,
+}
+
+/// Generated WDL and DTZ table files of a material
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TableFiles {
+    pub wdl: Vec<u8>,
+    pub dtz: Vec<u8>,
+}
+
+/// Set of loaded tables
+#[derive(Debug, Clone)]
+pub struct Tablebase {
+    tables: HashMap<String, Table>,
+    max_pieces: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Table {
+    wdl: Option<Vec<u8>>,
+    dtz: Option<Vec<u16>>,
+}
+
+/// Pieces of a table, white pieces first, each color ordered by `KIND_ORDER`
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Material {
+    pieces: Vec<pieces::PieceType>,
+}
+
+/// Position prepared for a table lookup
+struct Lookup {
+    material: Material,
+    squares: Vec<usize>,
+    on_turn: player::Player,
+}
+
+/// Move of a generated position staying in the same table
+struct Edge {
+    parent: u32,
+    child: u32,
+    is_zeroing: bool,
+}
+
+/// Board mirroring which moves the white king into the indexed area
+struct Symmetry {
+    flip_file: bool,
+    flip_rank: bool,
+    transpose: bool,
+}
+
+impl Wdl {
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            LOSS => Some(Wdl::Loss),
+            DRAW => Some(Wdl::Draw),
+            WIN => Some(Wdl::Win),
+            _ => None,
+        }
+    }
+}
+
+impl Tablebase {
+    /// Create an empty tablebase probing positions with up to `max_pieces` pieces
+    pub fn new(max_pieces: usize) -> Self {
+        Self {
+            tables: HashMap::new(),
+            max_pieces,
+        }
+    }
+
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    pub fn set_max_pieces(&mut self, max_pieces: usize) {
+        self.max_pieces = max_pieces;
+    }
+
+    /// Load a WDL or DTZ table file, replacing the loaded table of the same kind and material
+    pub fn add_table(&mut self, bytes: &[u8]) -> types::ChessResult<()> {
+        let invalid = |msg: &str| err::ChessError::InvalidTablebase(msg.to_string());
+
+        if bytes.len() < 8 || &bytes[0..4] != MAGIC {
+            return Err(invalid("Missing TCTB header"));
+        }
+
+        let kind = bytes[4];
+        if bytes[5] != VERSION {
+            return Err(invalid(&format!("Unsupported version {}", bytes[5])));
+        }
+
+        let key_end = 7 + usize::from(bytes[6]);
+        let data_start = key_end + 4;
+        if bytes.len() < data_start {
+            return Err(invalid("Truncated header"));
+        }
+
+        let key = std::str::from_utf8(&bytes[7..key_end]).map_err(|_| invalid("Invalid key"))?;
+        let material = Material::parse(key)?;
+        let count = u32::from_le_bytes(bytes[key_end..data_start].try_into().unwrap()) as usize;
+        let data = &bytes[data_start..];
+
+        if count != material.table_size() {
+            return Err(invalid(&format!("{} has {} positions", key, count)));
+        }
+
+        match kind {
+            WDL_KIND if data.len() == count.div_ceil(4) => {
+                self.tables.entry(material.key()).or_default().wdl = Some(data.to_vec());
+            }
+            DTZ_KIND if data.len() == count => {
+                let dtz = data.iter().map(|distance| u16::from(*distance)).collect();
+                self.tables.entry(material.key()).or_default().dtz = Some(dtz);
+            }
+            DTZ_KIND if data.len() == count * 2 => {
+                let dtz = data
+                    .chunks_exact(2)
+                    .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+                    .collect();
+                self.tables.entry(material.key()).or_default().dtz = Some(dtz);
+            }
+            WDL_KIND | DTZ_KIND => return Err(invalid(&format!("{} has invalid length", key))),
+            _ => return Err(invalid(&format!("Unknown table kind {}", kind))),
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the WDL table of the material (eg. `KQvK`) is loaded
+    pub fn has_table(&self, material: &str) -> bool {
+        Material::parse(material)
+            .ok()
+            .and_then(|material| self.tables.get(&material.key()))
+            .is_some_and(|table| table.wdl.is_some())
+    }
+
+    /// Get win, draw or loss of the player on turn, wins and losses turn cursed or blessed
+    /// when the fifty-move rule comes first (needs the DTZ table).
+    /// `None` if the position is not covered by loaded tables.
+    pub fn probe_wdl(&self, game: types::ParsedFen) -> Option<Wdl> {
+        let wdl = self.probe_table_wdl(game)?;
+        let Some(distance) = self.probe_table_dtz(game, wdl) else {
+            return Some(wdl);
+        };
+
+        // checkmate ends the game even on the 100th half-move
+        let is_too_late = distance != 0
+            && distance.unsigned_abs() + game.state.half_moves > result::FIFTY_MOVE_RULE_HALF_MOVES;
+
+        Some(match wdl {
+            Wdl::Win if is_too_late => Wdl::CursedWin,
+            Wdl::Loss if is_too_late => Wdl::BlessedLoss,
+            wdl => wdl,
+        })
+    }
+
+    /// Get plies to zeroing, positive when the player on turn wins, negative when loses.
+    /// `None` if the position is not covered by loaded tables.
+    pub fn probe_dtz(&self, game: types::ParsedFen) -> Option<i32> {
+        let wdl = self.probe_table_wdl(game)?;
+        self.probe_table_dtz(game, wdl)
+    }
+
+    /// Get win, draw or loss stored in the WDL table, ignoring the fifty-move rule
+    fn probe_table_wdl(&self, game: types::ParsedFen) -> Option<Wdl> {
+        let lookup = self.lookup(game)?;
+
+        // bare kings are always a draw, no table needed
+        if lookup.material.pieces.len() == 2 {
+            return Some(Wdl::Draw);
+        }
+
+        let wdl = self.tables.get(&lookup.material.key())?.wdl.as_ref()?;
+        let idx = lookup.material.index(&lookup.squares, lookup.on_turn);
+
+        Wdl::from_code((wdl[idx / 4] >> (idx % 4 * 2)) & 0b11)
+    }
+
+    /// Get plies to zeroing stored in the DTZ table, signed by the stored `wdl`
+    fn probe_table_dtz(&self, game: types::ParsedFen, wdl: Wdl) -> Option<i32> {
+        if wdl == Wdl::Draw {
+            return Some(0);
+        }
+
+        let lookup = self.lookup(game)?;
+        let dtz = self.tables.get(&lookup.material.key())?.dtz.as_ref()?;
+        let distance = i32::from(dtz[lookup.material.index(&lookup.squares, lookup.on_turn)]);
+
+        match wdl {
+            Wdl::Win => Some(distance),
+            _ => Some(-distance),
+        }
+    }
+
+    /// Probe both tables of the position
+    pub fn probe(&self, game: types::ParsedFen) -> Option<TablebaseProbe> {
+        Some(TablebaseProbe {
+            wdl: self.probe_wdl(game)?,
+            dtz: self.probe_dtz(game),
+        })
+    }
+
+    /// Get the move keeping the best result: the fastest zeroing move or checkmate when winning,
+    /// the slowest when losing. `None` if the position or any reply is not covered by
+    /// loaded WDL and DTZ tables.
+    pub fn best_move(&self, game: types::ParsedFen) -> Option<types::Move> {
+        self.probe_wdl(game)?;
+
+        let position = bitboard::Position::from(game);
+        let mut best: Option<(types::Move, (u8, i32))> = None;
+
+        for req_move in position.legal_moves(game.state.on_turn, bitboard::FULL) {
+            let target = bitboard::square_index(req_move.to_row_idx, req_move.to_col_idx);
+            let is_zeroing = position.piece_at(target).is_some()
+                || req_move.is_passant
+                || req_move.piece.piece_kind() == pieces::PieceKind::Pawn;
+
+            let child = types::ParsedFen::from(position.play(req_move));
+            let reply = self.probe(child)?;
+            let distance = match is_zeroing {
+                true => 1,
+                false => 1 + reply.dtz?.abs(),
+            };
+
+            // ordered by result of the moving player, then by preferred distance
+            let rank = match reply.wdl {
+                Wdl::Loss if reply.dtz == Some(0) => (4, i32::MAX),
+                Wdl::Loss => (4, -distance),
+                Wdl::BlessedLoss => (3, -distance),
+                Wdl::Draw => (2, 0),
+                Wdl::CursedWin => (1, distance),
+                Wdl::Win => (0, distance),
+            };
+
+            if best.is_none_or(|(_, best_rank)| rank > best_rank) {
+                best = Some((req_move, rank));
+            }
+        }
+
+        best.map(|(req_move, _)| req_move)
+    }
+
+    /// Generate WDL and DTZ tables of the material (eg. `KRvK`) by retrograde analysis.
+    /// Tables of materials reached by captures and promotions must be loaded already.
+    pub fn generate(&self, material: &str) -> types::ChessResult<TableFiles> {
+        let material = Material::parse(material)?;
+        let piece_count = material.pieces.len();
+
+        if piece_count < 3 || piece_count > self.max_pieces {
+            return Err(err::ChessError::InvalidTablebase(format!(
+                "Can't generate {} with max {} pieces",
+                material.key(),
+                self.max_pieces
+            )));
+        }
+
+        let size = material.table_size();
+        let mut wdl = vec![INVALID; size];
+        let mut edges = Vec::new();
+        // moves staying in the table, which don't lead to a known win of the opponent yet
+        let mut remaining = vec![0u32; size];
+        let mut can_lose = vec![true; size];
+        let mut has_zeroing_win = vec![false; size];
+        let mut has_zeroing_move = vec![false; size];
+        let mut resolved = Vec::new();
+
+        for idx in 0..size {
+            let (on_turn, squares) = material.decode(idx);
+            if !material.is_valid(&squares) {
+                continue;
+            }
+
+            let position = bitboard::Position::from(material.to_game(&squares, on_turn));
+            if position.is_king_attacked(on_turn.opponent()) {
+                continue;
+            }
+
+            let moves = position.legal_moves(on_turn, bitboard::FULL);
+            if moves.is_empty() {
+                wdl[idx] = match position.is_king_attacked(on_turn) {
+                    true => LOSS,
+                    false => DRAW,
+                };
+                resolved.push(idx);
+                continue;
+            }
+
+            for req_move in moves {
+                let from = bitboard::square_index(req_move.from_row_idx, req_move.from_col_idx);
+                let to = bitboard::square_index(req_move.to_row_idx, req_move.to_col_idx);
+                let is_capture = position.piece_at(to).is_some() || req_move.is_passant;
+
+                if is_capture || req_move.promotion_piece.is_some() {
+                    let child = types::ParsedFen::from(position.play(req_move));
+                    let child_wdl = self.probe_table_wdl(child).ok_or_else(|| {
+                        err::ChessError::InvalidTablebase(format!(
+                            "Missing table {}",
+                            Material::from_game(child).key()
+                        ))
+                    })?;
+
+                    has_zeroing_move[idx] = true;
+                    match child_wdl {
+                        Wdl::Loss | Wdl::BlessedLoss => has_zeroing_win[idx] = true,
+                        Wdl::Draw => can_lose[idx] = false,
+                        Wdl::Win | Wdl::CursedWin => {}
+                    }
+                    continue;
+                }
+
+                let mut child_squares = squares.clone();
+                let moved = child_squares.iter().position(|sq| *sq == from).unwrap();
+                child_squares[moved] = to;
+
+                edges.push(Edge {
+                    parent: idx as u32,
+                    child: material.index(&child_squares, on_turn.opponent()) as u32,
+                    is_zeroing: req_move.piece.piece_kind() == pieces::PieceKind::Pawn,
+                });
+                remaining[idx] += 1;
+            }
+
+            wdl[idx] = match (has_zeroing_win[idx], remaining[idx] == 0 && can_lose[idx]) {
+                (true, _) => WIN,
+                (false, true) => LOSS,
+                (false, false) => UNKNOWN,
+            };
+            if wdl[idx] != UNKNOWN {
+                resolved.push(idx);
+            }
+        }
+
+        let (pred_start, preds) = self::predecessors(&edges, size);
+        let parents = |child: usize| &preds[pred_start[child]..pred_start[child + 1]];
+
+        // a position is won if any move leads to a lost position of the opponent,
+        // lost if all moves lead to won positions of the opponent
+        while let Some(child) = resolved.pop() {
+            for &(parent, _) in parents(child) {
+                let parent = parent as usize;
+                if wdl[parent] != UNKNOWN {
+                    continue;
+                }
+
+                match wdl[child] {
+                    LOSS => {
+                        wdl[parent] = WIN;
+                        resolved.push(parent);
+                    }
+                    WIN => {
+                        remaining[parent] -= 1;
+                        if remaining[parent] == 0 && can_lose[parent] {
+                            wdl[parent] = LOSS;
+                            resolved.push(parent);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for value in wdl.iter_mut().filter(|value| **value == UNKNOWN) {
+            *value = DRAW;
+        }
+
+        let dtz = self::distances_to_zeroing(
+            &wdl,
+            &edges,
+            &has_zeroing_win,
+            &has_zeroing_move,
+            |child| parents(child),
+        );
+
+        let key = material.key();
+        let mut wdl_file = self::header(WDL_KIND, &key, size);
+        wdl_file.extend(wdl.chunks(4).map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (shift, code)| byte | code << (shift * 2))
+        }));
+
+        let mut dtz_file = self::header(DTZ_KIND, &key, size);
+        match dtz.iter().all(|distance| *distance <= u16::from(u8::MAX)) {
+            true => dtz_file.extend(dtz.iter().map(|distance| *distance as u8)),
+            false => dtz_file.extend(dtz.iter().flat_map(|distance| distance.to_le_bytes())),
+        }
+
+        Ok(TableFiles {
+            wdl: wdl_file,
+            dtz: dtz_file,
+        })
+    }
+
+    /// Prepare the position for a lookup, flipping colors if only the table
+    /// of the mirrored material is loaded
+    fn lookup(&self, game: types::ParsedFen) -> Option<Lookup> {
+        let state = game.state;
+        if state.castle_white_short
+            || state.castle_white_long
+            || state.castle_black_short
+            || state.castle_black_long
+            || state.pockets.is_some()
+            || state.variant != variant::VariantKind::Standard
+        {
+            return None;
+        }
+
+        let mut pieces = self::board_pieces(game);
+        let kings = pieces
+            .iter()
+            .filter(|(piece, _)| piece.piece_kind() == pieces::PieceKind::King)
+            .count();
+        if pieces.len() > self.max_pieces || kings != 2 {
+            return None;
+        }
+
+        let position = bitboard::Position::from(game);
+        if position.is_king_attacked(state.on_turn.opponent()) {
+            return None;
+        }
+
+        if state.en_passant_square.is_some()
+            && position
+                .legal_moves(state.on_turn, bitboard::FULL)
+                .iter()
+                .any(|req_move| req_move.is_passant)
+        {
+            return None;
+        }
+
+        let mut on_turn = state.on_turn;
+        let mut material = Material::from_pieces(&pieces);
+
+        if material.pieces.len() > 2 && !self.has_table(&material.key()) {
+            pieces = pieces
+                .iter()
+                .map(|(piece, sq)| {
+                    let flipped_piece =
+                        pieces::PieceType::from_kind(piece.piece_kind(), piece.color().opponent());
+                    let flipped_sq = (types::MAX_SIZE_INDEX - sq / types::BOARD_SIZE)
+                        * types::BOARD_SIZE
+                        + sq % types::BOARD_SIZE;
+                    (flipped_piece, flipped_sq)
+                })
+                .collect();
+            pieces.sort_by_key(|(piece, sq)| self::piece_order(*piece, *sq));
+            material = Material::from_pieces(&pieces);
+            on_turn = on_turn.opponent();
+        }
+
+        Some(Lookup {
+            material,
+            squares: pieces.iter().map(|(_, sq)| *sq).collect(),
+            on_turn,
+        })
+    }
+}
+
+impl Default for Tablebase {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_PIECES)
+    }
+}
+
+impl Material {
+    /// Parse a material key like `KRPvKR`
+    fn parse(key: &str) -> types::ChessResult<Self> {
+        let invalid = || err::ChessError::InvalidTablebase(format!("Invalid material {}", key));
+        let (white, black) = key.split_once('v').ok_or_else(invalid)?;
+
+        let mut pieces = Vec::new();
+        for (side, color) in [
+            (white, player::Player::White),
+            (black, player::Player::Black),
+        ] {
+            let mut side_pieces = side
+                .chars()
+                .map(|c| pieces::PieceType::from_char(c.to_ascii_uppercase()))
+                .map(|piece| {
+                    piece.map(|piece| pieces::PieceType::from_kind(piece.piece_kind(), color))
+                })
+                .collect::<Option<Vec<pieces::PieceType>>>()
+                .ok_or_else(invalid)?;
+
+            let kings = side_pieces
+                .iter()
+                .filter(|piece| piece.piece_kind() == pieces::PieceKind::King)
+                .count();
+            if kings != 1 {
+                return Err(invalid());
+            }
+
+            side_pieces.sort_by_key(|piece| self::kind_order(piece.piece_kind()));
+            pieces.extend(side_pieces);
+        }
+
+        Ok(Self { pieces })
+    }
+
+    fn from_pieces(pieces: &[(pieces::PieceType, usize)]) -> Self {
+        Self {
+            pieces: pieces.iter().map(|(piece, _)| *piece).collect(),
+        }
+    }
+
+    fn from_game(game: types::ParsedFen) -> Self {
+        Self::from_pieces(&self::board_pieces(game))
+    }
+
+    /// Key like `KQvK`
+    fn key(&self) -> String {
+        let mut key = String::new();
+
+        for (idx, piece) in self.pieces.iter().enumerate() {
+            if idx > 0 && !piece.is_white() && self.pieces[idx - 1].is_white() {
+                key.push('v');
+            }
+            key.push(piece.to_char().to_ascii_uppercase());
+        }
+
+        key
+    }
+
+    fn has_pawns(&self) -> bool {
+        self.pieces
+            .iter()
+            .any(|piece| piece.piece_kind() == pieces::PieceKind::Pawn)
+    }
+
+    fn king_squares(&self) -> usize {
+        match self.has_pawns() {
+            true => PAWN_KING_SQUARES,
+            false => TRIANGLE.len(),
+        }
+    }
+
+    fn table_size(&self) -> usize {
+        2 * self.king_squares() * SQUARES.pow(self.pieces.len() as u32 - 1)
+    }
+
+    fn index(&self, squares: &[usize], on_turn: player::Player) -> usize {
+        let has_pawns = self.has_pawns();
+        let symmetry = Symmetry::canonical(squares[0], has_pawns);
+        let king = symmetry.apply(squares[0]);
+        let king_idx = match has_pawns {
+            true => {
+                (types::MAX_SIZE_INDEX - king / types::BOARD_SIZE) * 4 + king % types::BOARD_SIZE
+            }
+            false => TRIANGLE.iter().position(|sq| *sq == king).unwrap(),
+        };
+
+        squares[1..].iter().fold(
+            bitboard::color_index(on_turn) * self.king_squares() + king_idx,
+            |idx, sq| idx * SQUARES + symmetry.apply(*sq),
+        )
+    }
+
+    fn decode(&self, mut idx: usize) -> (player::Player, Vec<usize>) {
+        let mut squares = vec![0; self.pieces.len()];
+
+        for sq in squares[1..].iter_mut().rev() {
+            *sq = idx % SQUARES;
+            idx /= SQUARES;
+        }
+
+        let king_idx = idx % self.king_squares();
+        squares[0] = match self.has_pawns() {
+            true => (types::MAX_SIZE_INDEX - king_idx / 4) * types::BOARD_SIZE + king_idx % 4,
+            false => TRIANGLE[king_idx],
+        };
+
+        let on_turn = match idx / self.king_squares() {
+            0 => player::Player::White,
+            _ => player::Player::Black,
+        };
+
+        (on_turn, squares)
+    }
+
+    /// Pieces on distinct squares and no pawns on the first or last rank
+    fn is_valid(&self, squares: &[usize]) -> bool {
+        let mut occupied = bitboard::EMPTY;
+
+        for (piece, sq) in self.pieces.iter().zip(squares) {
+            let row = sq / types::BOARD_SIZE;
+            let is_pawn = piece.piece_kind() == pieces::PieceKind::Pawn;
+
+            if occupied & bitboard::square_bit(*sq) != bitboard::EMPTY
+                || (is_pawn && (row == 0 || row == types::MAX_SIZE_INDEX))
+            {
+                return false;
+            }
+            occupied |= bitboard::square_bit(*sq);
+        }
+
+        true
+    }
+
+    fn to_game(&self, squares: &[usize], on_turn: player::Player) -> types::ParsedFen {
+        let mut board: types::Board = [[None; types::BOARD_SIZE]; types::BOARD_SIZE];
+
+        for (piece, sq) in self.pieces.iter().zip(squares) {
+            board[sq / types::BOARD_SIZE][sq % types::BOARD_SIZE] = Some(*piece);
+        }
+
+        types::ParsedFen {
+            board,
+            state: types::ParsedFenState {
+                en_passant_square: None,
+                on_turn,
+                castle_white_short: false,
+                castle_white_long: false,
+                castle_black_short: false,
+                castle_black_long: false,
+                half_moves: 0,
+                full_moves: 1,
+                castle_rooks: types::CastleRooks::default(),
+                chess960: false,
+                pockets: None,
+                variant: variant::VariantKind::Standard,
+                checks: types::Checks::default(),
+            },
+        }
+    }
+}
+
+impl Symmetry {
+    fn canonical(king: usize, has_pawns: bool) -> Self {
+        let file = king % types::BOARD_SIZE;
+        let rank = types::MAX_SIZE_INDEX - king / types::BOARD_SIZE;
+
+        let flip_file = file > 3;
+        let flip_rank = !has_pawns && rank > 3;
+        let file = if flip_file {
+            types::MAX_SIZE_INDEX - file
+        } else {
+            file
+        };
+        let rank = if flip_rank {
+            types::MAX_SIZE_INDEX - rank
+        } else {
+            rank
+        };
+
+        Self {
+            flip_file,
+            flip_rank,
+            transpose: !has_pawns && rank > file,
+        }
+    }
+
+    fn apply(&self, sq: usize) -> usize {
+        let mut file = sq % types::BOARD_SIZE;
+        let mut rank = types::MAX_SIZE_INDEX - sq / types::BOARD_SIZE;
+
+        if self.flip_file {
+            file = types::MAX_SIZE_INDEX - file;
+        }
+        if self.flip_rank {
+            rank = types::MAX_SIZE_INDEX - rank;
+        }
+        if self.transpose {
+            std::mem::swap(&mut file, &mut rank);
+        }
+
+        (types::MAX_SIZE_INDEX - rank) * types::BOARD_SIZE + file
+    }
+}
+
+/// Distance to zeroing of every position, processed in increasing distance.
+/// A won position takes the shortest winning move, a lost position the longest move.
+fn distances_to_zeroing<'a>(
+    wdl: &[u8],
+    edges: &[Edge],
+    has_zeroing_win: &[bool],
+    has_zeroing_move: &[bool],
+    parents: impl Fn(usize) -> &'a [(u32, bool)],
+) -> Vec<u16> {
+    let size = wdl.len();
+    let mut dtz = vec![0u16; size];
+    let mut done = vec![false; size];
+    let mut loss_remaining = vec![0u32; size];
+    let mut loss_distance = vec![0usize; size];
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); 2];
+
+    for idx in 0..size {
+        if wdl[idx] == WIN && has_zeroing_win[idx] {
+            buckets[1].push(idx);
+        }
+        if wdl[idx] == LOSS && has_zeroing_move[idx] {
+            loss_distance[idx] = 1;
+        }
+    }
+
+    for edge in edges {
+        let (parent, child) = (edge.parent as usize, edge.child as usize);
+
+        match (wdl[parent], edge.is_zeroing) {
+            (WIN, true) if wdl[child] == LOSS => buckets[1].push(parent),
+            (LOSS, true) => loss_distance[parent] = loss_distance[parent].max(1),
+            (LOSS, false) => loss_remaining[parent] += 1,
+            _ => {}
+        }
+    }
+
+    for idx in 0..size {
+        if wdl[idx] == LOSS && loss_remaining[idx] == 0 {
+            buckets[loss_distance[idx]].push(idx);
+        }
+    }
+
+    let mut distance = 0;
+    while distance < buckets.len() {
+        for idx in std::mem::take(&mut buckets[distance]) {
+            if done[idx] {
+                continue;
+            }
+            done[idx] = true;
+            dtz[idx] = distance as u16;
+
+            for &(parent, is_zeroing) in parents(idx) {
+                let parent = parent as usize;
+                if done[parent] || is_zeroing {
+                    continue;
+                }
+
+                let next = match (wdl[idx], wdl[parent]) {
+                    (LOSS, WIN) => distance + 1,
+                    (WIN, LOSS) => {
+                        loss_distance[parent] = loss_distance[parent].max(distance + 1);
+                        loss_remaining[parent] -= 1;
+                        match loss_remaining[parent] {
+                            0 => loss_distance[parent],
+                            _ => continue,
+                        }
+                    }
+                    _ => continue,
+                };
+
+                if buckets.len() <= next {
+                    buckets.resize(next + 1, Vec::new());
+                }
+                buckets[next].push(parent);
+            }
+        }
+        distance += 1;
+    }
+
+    dtz
+}
+
+/// Group table moves by the reached position, returns start offsets and parents
+fn predecessors(edges: &[Edge], size: usize) -> (Vec<usize>, Vec<(u32, bool)>) {
+    let mut start = vec![0usize; size + 1];
+    for edge in edges {
+        start[edge.child as usize + 1] += 1;
+    }
+    for idx in 0..size {
+        start[idx + 1] += start[idx];
+    }
+
+    let mut offsets = start.clone();
+    let mut parents = vec![(0, false); edges.len()];
+    for edge in edges {
+        let offset = &mut offsets[edge.child as usize];
+        parents[*offset] = (edge.parent, edge.is_zeroing);
+        *offset += 1;
+    }
+
+    (start, parents)
+}
+
+fn header(kind: u8, key: &str, size: usize) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend([kind, VERSION, key.len() as u8]);
+    bytes.extend(key.as_bytes());
+    bytes.extend((size as u32).to_le_bytes());
+    bytes
+}
+
+/// Pieces on the board with their squares in material order
+fn board_pieces(game: types::ParsedFen) -> Vec<(pieces::PieceType, usize)> {
+    let mut pieces: Vec<(pieces::PieceType, usize)> = game
+        .board
+        .iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(sq, piece)| piece.map(|piece| (piece, sq)))
+        .collect();
+
+    pieces.sort_by_key(|(piece, sq)| self::piece_order(*piece, *sq));
+    pieces
+}
+
+fn piece_order(piece: pieces::PieceType, sq: usize) -> (usize, usize, usize) {
+    (
+        bitboard::color_index(piece.color()),
+        self::kind_order(piece.piece_kind()),
+        sq,
+    )
+}
+
+fn kind_order(kind: pieces::PieceKind) -> usize {
+    KIND_ORDER.iter().position(|k| *k == kind).unwrap()
+}
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod test_antichess {
+    use crate::tests::{assert_perft, assert_play_matches_state, play, variant_game};
+    use crate::{validation, Chess, ChessError, GameResult, PieceType, Player, VariantKind};
+
+    const ANTICHESS_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1";
+    // white rook and black pawn, no kings on the board
+    const NO_KINGS: &str = "8/8/8/8/8/8/4p3/3R4 w - - 0 1";
+    // kings stand next to each other
+    const KINGS_ONLY: &str = "8/8/8/8/8/8/4k3/4K3 w - - 0 1";
+
+    #[test]
+    pub fn test_capture_is_compulsory() {
+        let standard = play(Chess::parse_fen(ANTICHESS_FEN).unwrap(), &["e2e4", "d7d5"]);
+        let game = play(
+            variant_game(ANTICHESS_FEN, VariantKind::Antichess),
+            &["e2e4", "d7d5"],
+        );
+        let capture = Chess::uci_to_move("e4d5", game).unwrap();
+
+        assert_eq!(Chess::get_all_moves(game), vec![capture]);
+        assert!(Chess::get_moves(crate::Square::new(6, 0), game).is_empty());
+
+        let quiet = Chess::uci_to_move("a2a3", standard).unwrap();
+        assert!(matches!(
+            Chess::move_piece(quiet, game),
+            Err(ChessError::InvalidMove(_))
+        ));
+        assert!(Chess::move_piece(capture, game).is_ok());
+    }
+
+    #[test]
+    pub fn test_kings_are_ordinary_pieces() {
+        let game = variant_game(KINGS_ONLY, VariantKind::Antichess);
+
+        assert!(Chess::parse_fen(KINGS_ONLY).is_err());
+        assert!(!validation::is_king_in_check(game, Player::White));
+
+        // the white king must take the black king, black has no pieces left
+        let capture = Chess::uci_to_move("e1e2", game).unwrap();
+        assert_eq!(Chess::get_all_moves(game), vec![capture]);
+
+        let won = play(game, &["e1e2"]);
+        assert_eq!(
+            Chess::get_game_result(won).unwrap(),
+            Some(GameResult::BlackAntichess)
+        );
+        assert_eq!(GameResult::BlackAntichess.to_pgn_result(), "0-1");
+        assert!(Chess::get_all_moves(won).is_empty());
+    }
+
+    #[test]
+    pub fn test_positions_without_kings() {
+        let game = variant_game(NO_KINGS, VariantKind::Antichess);
+
+        assert!(Chess::parse_fen(NO_KINGS).is_err());
+        assert_eq!(Chess::get_game_result(game).unwrap(), None);
+
+        // pawn promotes to a king, which the rook has to take
+        let promoted = play(game, &["d1a1", "e2e1k"]);
+        assert_eq!(
+            Chess::stringify(&promoted).unwrap(),
+            "8/8/8/8/8/8/8/R3k3 w - - 0 2"
+        );
+        assert_eq!(
+            Chess::move_to_san(Chess::uci_to_move("a1e1", promoted).unwrap(), promoted).unwrap(),
+            "Rxe1"
+        );
+
+        let won = play(promoted, &["a1e1"]);
+        assert_eq!(
+            Chess::get_game_result(won).unwrap(),
+            Some(GameResult::BlackAntichess)
+        );
+    }
+
+    #[test]
+    pub fn test_king_promotion() {
+        let fen = "8/P7/8/8/8/8/8/7k w - - 0 1";
+        let promotions: Vec<Option<PieceType>> =
+            Chess::get_all_moves(variant_game(fen, VariantKind::Antichess))
+                .iter()
+                .map(|m| m.promotion_piece)
+                .collect();
+
+        assert_eq!(promotions.len(), 5);
+        assert!(promotions.contains(&Some(PieceType::WhiteKing)));
+
+        // standard pawns can't promote to a king
+        let standard = Chess::parse_fen("7k/P7/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        assert!(Chess::uci_to_move("a7a8k", standard).is_err());
+    }
+
+    #[test]
+    pub fn test_stalemate_wins() {
+        let blocked = variant_game("8/8/8/8/8/p7/P7/8 w - - 0 1", VariantKind::Antichess);
+
+        assert!(Chess::get_all_moves(blocked).is_empty());
+        assert_eq!(
+            Chess::get_game_result(blocked).unwrap(),
+            Some(GameResult::WhiteAntichess)
+        );
+    }
+
+    #[test]
+    pub fn test_no_castling() {
+        let game = variant_game("r3k3/8/8/8/8/8/8/R3K2R w KQq - 0 1", VariantKind::Antichess);
+
+        assert!(Chess::get_all_moves(game).iter().all(|m| !m.is_castle));
+    }
+
+    #[test]
+    pub fn test_insufficient_material() {
+        // bishops on squares of different colors never meet
+        let opposite = variant_game("8/8/8/8/8/8/8/Bb6 w - - 0 1", VariantKind::Antichess);
+        assert_eq!(
+            Chess::get_game_result(opposite).unwrap(),
+            Some(GameResult::InsufficientMaterial)
+        );
+
+        let same = variant_game("8/8/8/8/8/8/1b6/B7 w - - 0 1", VariantKind::Antichess);
+        assert_eq!(Chess::get_game_result(same).unwrap(), None);
+
+        // bare kings can still take each other
+        let kings = variant_game("8/8/8/8/8/8/8/k1K5 w - - 0 1", VariantKind::Antichess);
+        assert_eq!(Chess::get_game_result(kings).unwrap(), None);
+    }
+
+    #[test]
+    pub fn test_play_matches_state() {
+        for game in [
+            variant_game(ANTICHESS_FEN, VariantKind::Antichess),
+            play(
+                variant_game(ANTICHESS_FEN, VariantKind::Antichess),
+                &["e2e4", "d7d5"],
+            ),
+            variant_game(NO_KINGS, VariantKind::Antichess),
+            variant_game(KINGS_ONLY, VariantKind::Antichess),
+            variant_game("8/P7/8/8/8/8/8/7k w - - 0 1", VariantKind::Antichess),
+        ] {
+            assert_play_matches_state(game);
+        }
+    }
+
+    #[test]
+    pub fn test_perft() {
+        for (fen, nodes) in [
+            (ANTICHESS_FEN, [20, 400, 8067, 153299]),
+            ("8/1p6/8/8/8/8/P7/8 w - - 0 1", [2, 4, 4, 3]),
+            ("8/2p5/8/8/8/8/P7/8 w - - 0 1", [2, 4, 4, 4]),
+        ] {
+            assert_perft(variant_game(fen, VariantKind::Antichess), &nodes);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod test_atomic {
+    use crate::tests::{assert_perft, assert_play_matches_state, play, variant_game};
+    use crate::{
+        validation, Chess, ChessError, GameResult, Player, SearchLimits, VariantKind, INITIAL_FEN,
+    };
+
+    // knight on g5 takes f7 next to the black king
+    const KNIGHT_ATTACK: &str = "rnbqkbnr/1ppp1ppp/p3p3/6N1/8/8/PPPPPPPP/RNBQKB1R w KQkq - 0 3";
+    // black bishop takes g2 next to the castling rook
+    const ROOK_EXPLOSION: &str = "4k3/1b6/8/8/8/8/6P1/R3K2R b KQ - 0 1";
+    // kings stand next to each other, black rook on e1
+    const ADJACENT_KINGS: &str = "8/8/8/8/3k4/4K3/8/4r3 w - - 0 1";
+    const CASTLE_ADJACENT_KINGS: &str = "r3k1rR/5K2/8/8/8/8/8/8 b kq - 0 1";
+
+    #[test]
+    pub fn test_capture_explodes() {
+        let queen = variant_game("4k3/8/2n1p3/3q4/4P3/8/8/4K3 w - - 0 1", VariantKind::Atomic);
+        let exploded = play(queen, &["e4d5"]);
+
+        // pawns next to the explosion survive
+        assert_eq!(
+            Chess::stringify(&exploded).unwrap(),
+            "4k3/8/4p3/8/8/8/8/4K3 b - - 0 1"
+        );
+
+        let standard = play(
+            Chess::parse_fen("4k3/8/2n1p3/3q4/4P3/8/8/4K3 w - - 0 1").unwrap(),
+            &["e4d5"],
+        );
+        assert_eq!(
+            Chess::stringify(&standard).unwrap(),
+            "4k3/8/2n1p3/3P4/8/8/8/4K3 b - - 0 1"
+        );
+
+        // en passant explodes on the target square
+        let passant = variant_game("4k3/4n3/8/3pP3/8/8/8/4K3 w - d6 0 2", VariantKind::Atomic);
+        let exploded = play(passant, &["e5d6"]);
+        assert_eq!(
+            Chess::stringify(&exploded).unwrap(),
+            "4k3/8/8/8/8/8/8/4K3 b - - 0 2"
+        );
+    }
+
+    #[test]
+    pub fn test_king_explosion_wins() {
+        let attack = variant_game(KNIGHT_ATTACK, VariantKind::Atomic);
+        let won = play(attack, &["g5f7"]);
+
+        assert_eq!(
+            Chess::stringify(&won).unwrap(),
+            "rnbq3r/1ppp2pp/p3p3/8/8/8/PPPPPPPP/RNBQKB1R b KQ - 0 3"
+        );
+        assert_eq!(
+            Chess::get_game_result(won).unwrap(),
+            Some(GameResult::WhiteExplosion)
+        );
+        assert_eq!(GameResult::WhiteExplosion.to_pgn_result(), "1-0");
+        assert!(Chess::get_all_moves(won).is_empty());
+
+        let result = Chess::best_move(
+            attack,
+            SearchLimits {
+                depth: Some(2),
+                ..SearchLimits::default()
+            },
+        );
+        assert_eq!(
+            result.best_move,
+            Some(Chess::uci_to_move("g5f7", attack).unwrap())
+        );
+        assert_eq!(result.mate_in, Some(1));
+
+        // game without the white king is already decided
+        let no_king = variant_game("4k3/8/8/8/8/8/8/8 w - - 0 1", VariantKind::Atomic);
+        assert_eq!(
+            Chess::get_game_result(no_king).unwrap(),
+            Some(GameResult::BlackExplosion)
+        );
+        assert!(Chess::parse_fen("4k3/8/8/8/8/8/8/8 w - - 0 1").is_err());
+    }
+
+    #[test]
+    pub fn test_king_cannot_capture() {
+        let fen = "4k3/8/8/8/8/8/3p4/4K3 w - - 0 1";
+        let capture = Chess::uci_to_move("e1d2", Chess::parse_fen(fen).unwrap()).unwrap();
+
+        assert!(Chess::get_all_moves(variant_game(fen, VariantKind::Atomic))
+            .iter()
+            .all(|m| m.to_row_idx != 6 || m.to_col_idx != 3));
+        assert!(matches!(
+            Chess::move_piece(capture, variant_game(fen, VariantKind::Atomic)),
+            Err(ChessError::InvalidMove(_))
+        ));
+        assert!(Chess::move_piece(capture, Chess::parse_fen(fen).unwrap()).is_ok());
+    }
+
+    #[test]
+    pub fn test_self_explosion_is_illegal() {
+        // queen takes next to the own king
+        let fen = "4k3/8/8/8/8/8/3p4/3QK3 w - - 0 1";
+        let game = variant_game(fen, VariantKind::Atomic);
+        let capture = Chess::uci_to_move("d1d2", Chess::parse_fen(fen).unwrap()).unwrap();
+
+        assert!(!Chess::get_all_moves(game).contains(&capture));
+        assert!(matches!(
+            Chess::move_piece(capture, game),
+            Err(ChessError::InvalidMove(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_adjacent_kings() {
+        let game = variant_game(ADJACENT_KINGS, VariantKind::Atomic);
+
+        assert!(Chess::parse_fen(ADJACENT_KINGS).is_err());
+        assert!(!validation::is_king_in_check(game, Player::White));
+        assert_eq!(Chess::get_game_result(game).unwrap(), None);
+
+        // leaving the enemy king steps into the rook check
+        assert!(Chess::uci_to_move("e3e2", game).is_err());
+        assert!(Chess::move_piece(Chess::uci_to_move("e3f3", game).unwrap(), game).is_ok());
+        assert!(Chess::move_piece(Chess::uci_to_move("e3d3", game).unwrap(), game).is_ok());
+    }
+
+    #[test]
+    pub fn test_exploded_rook_loses_castling() {
+        let game = variant_game(ROOK_EXPLOSION, VariantKind::Atomic);
+        let exploded = play(game, &["b7g2"]);
+
+        assert_eq!(
+            Chess::stringify(&exploded).unwrap(),
+            "4k3/8/8/8/8/8/8/R3K3 w Q - 0 2"
+        );
+    }
+
+    #[test]
+    pub fn test_play_matches_state() {
+        for fen in [
+            INITIAL_FEN,
+            KNIGHT_ATTACK,
+            ROOK_EXPLOSION,
+            ADJACENT_KINGS,
+            CASTLE_ADJACENT_KINGS,
+            "4k3/4n3/8/3pP3/8/8/8/4K3 w - d6 0 2",
+        ] {
+            assert_play_matches_state(variant_game(fen, VariantKind::Atomic));
+        }
+    }
+
+    #[test]
+    pub fn test_perft() {
+        for (fen, nodes) in [
+            (INITIAL_FEN, [20, 400, 8902, 197326]),
+            ("8/8/8/8/8/8/2k5/rR4KR w KQ - 0 1", [18, 180, 4364, 61401]),
+            (CASTLE_ADJACENT_KINGS, [25, 282, 6753, 98729]),
+            (
+                "Rr2k1rR/3K4/3p4/8/8/8/7P/8 w kq - 0 1",
+                [21, 465, 10631, 241478],
+            ),
+        ] {
+            assert_perft(variant_game(fen, VariantKind::Atomic), &nodes);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod test_attacks {
+    use crate::{Chess, Player, Square};
+
+    #[test]
+    pub fn test_pawn_attacks() {
+        let game = Chess::parse_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+
+        // e4 pawn attacks d5 and f5, but not e5 in front of it
+        assert!(Chess::is_square_attacked(
+            Square::new(3, 3),
+            game,
+            Player::White
+        ));
+        assert!(Chess::is_square_attacked(
+            Square::new(3, 5),
+            game,
+            Player::White
+        ));
+        assert!(!Chess::is_square_attacked(
+            Square::new(3, 4),
+            game,
+            Player::White
+        ));
+
+        // d5 pawn attacks e4 and c4
+        assert!(Chess::is_square_attacked(
+            Square::new(4, 4),
+            game,
+            Player::Black
+        ));
+        assert!(Chess::is_square_attacked(
+            Square::new(4, 2),
+            game,
+            Player::Black
+        ));
+        assert!(!Chess::is_square_attacked(
+            Square::new(4, 3),
+            game,
+            Player::Black
+        ));
+    }
+
+    #[test]
+    pub fn test_sliding_attacks_are_blocked() {
+        let game = Chess::parse_fen("4k3/8/8/8/R2P3q/8/8/4K3 w - - 0 1").unwrap();
+
+        assert!(Chess::is_square_attacked(
+            Square::new(4, 2),
+            game,
+            Player::White
+        ));
+        // d4 pawn blocks the rook
+        assert!(!Chess::is_square_attacked(
+            Square::new(4, 4),
+            game,
+            Player::White
+        ));
+        // the queen defends squares up to the blocking piece and attacks the pawn
+        assert_eq!(
+            Chess::attackers_of(Square::new(4, 3), game, Player::Black),
+            vec![Square::new(4, 7)]
+        );
+        // e1 king is attacked by the queen on the diagonal
+        assert!(Chess::is_square_attacked(
+            Square::new(7, 4),
+            game,
+            Player::Black
+        ));
+    }
+
+    #[test]
+    pub fn test_attackers_of() {
+        let game =
+            Chess::parse_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+                .unwrap();
+
+        // e5 pawn is attacked by the f3 knight and defended by the c6 knight
+        assert_eq!(
+            Chess::attackers_of(Square::new(3, 4), game, Player::White),
+            vec![Square::new(5, 5)]
+        );
+        assert_eq!(
+            Chess::attackers_of(Square::new(3, 4), game, Player::Black),
+            vec![Square::new(2, 2)]
+        );
+        assert!(Chess::attackers_of(Square::new(4, 0), game, Player::Black).is_empty());
+    }
+
+    #[test]
+    pub fn test_attacked_squares() {
+        let game = Chess::parse_fen("8/8/8/8/8/8/8/K6k w - - 0 1").unwrap();
+
+        let attacked = Chess::get_attacked_squares(game, Player::White);
+
+        assert_eq!(attacked.len(), 3);
+        assert!(attacked.contains(&Square::new(6, 0)));
+        assert!(attacked.contains(&Square::new(6, 1)));
+        assert!(attacked.contains(&Square::new(7, 1)));
+    }
+}
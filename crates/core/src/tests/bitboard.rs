@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod test_bitboard {
+    use crate::tests::{assert_play_matches_state, game};
+    use crate::{bitboard, moves, pieces, validation, Chess, ParsedFen, Player, INITIAL_FEN};
+
+    const POSITIONS: [&str; 6] = [
+        INITIAL_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    ];
+
+    #[test]
+    pub fn test_parsed_fen_round_trip() {
+        for fen in POSITIONS {
+            let game = Chess::parse_fen(fen).unwrap();
+            let position = bitboard::Position::from(game);
+
+            assert_eq!(ParsedFen::from(position), game);
+        }
+    }
+
+    #[test]
+    pub fn test_piece_sets() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let position = bitboard::Position::from(game);
+
+        assert_eq!(position.occupancy(Player::Black), 0xFFFF);
+        assert_eq!(position.occupancy(Player::White), 0xFFFF << 48);
+        assert_eq!(
+            position.pieces(pieces::PieceType::WhiteKing),
+            bitboard::square_bit(bitboard::square_index(7, 4))
+        );
+        assert_eq!(position.king_square(Player::Black), Some(4));
+        assert_eq!(
+            position.piece_at(bitboard::square_index(0, 3)),
+            Some(pieces::PieceType::BlackQueen)
+        );
+        assert_eq!(position.piece_at(bitboard::square_index(4, 4)), None);
+    }
+
+    #[test]
+    pub fn test_sliding_attacks() {
+        let d4 = bitboard::square_index(4, 3);
+        let blockers = bitboard::square_bit(bitboard::square_index(4, 5))
+            | bitboard::square_bit(bitboard::square_index(1, 3));
+
+        let rook = bitboard::rook_attacks(d4, blockers);
+
+        // blocker squares are attacked, squares behind them are not
+        assert!(rook & bitboard::square_bit(bitboard::square_index(4, 5)) != 0);
+        assert!(rook & bitboard::square_bit(bitboard::square_index(4, 6)) == 0);
+        assert!(rook & bitboard::square_bit(bitboard::square_index(1, 3)) != 0);
+        assert!(rook & bitboard::square_bit(bitboard::square_index(0, 3)) == 0);
+        assert_eq!(rook.count_ones(), 3 + 2 + 3 + 3);
+
+        assert_eq!(bitboard::bishop_attacks(d4, 0).count_ones(), 13);
+    }
+
+    #[test]
+    pub fn test_legal_moves_match_validation() {
+        for fen in POSITIONS {
+            let game = Chess::parse_fen(fen).unwrap();
+
+            // walk one ply deeper to cover positions after captures, castling and promotions
+            let mut games = vec![game];
+            for req_move in Chess::get_all_moves(game) {
+                games.push(validation::validate_move(req_move, game).unwrap());
+            }
+
+            for game in games {
+                let mut expected: Vec<_> = moves::get_player_squares(game, game.state.on_turn)
+                    .into_iter()
+                    .flat_map(|sq| moves::get_pseudo_moves(sq, game))
+                    .filter(|m| validation::validate_move(*m, game).is_ok())
+                    .map(|m| m.to_uci().unwrap())
+                    .collect();
+                let mut actual: Vec<_> = Chess::get_all_moves(game)
+                    .iter()
+                    .map(|m| m.to_uci().unwrap())
+                    .collect();
+
+                expected.sort();
+                actual.sort();
+
+                assert_eq!(actual, expected, "{}", Chess::stringify(&game).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_play_matches_state() {
+        for fen in POSITIONS {
+            assert_play_matches_state(game(fen));
+        }
+    }
+
+    #[test]
+    pub fn test_make_unmake_round_trip() {
+        let tricky_positions = [
+            // promotions capturing rooks in the corner, both sides can castle
+            "r3k2r/1P4P1/8/8/8/8/1p4p1/R3K2R w KQkq - 0 1",
+            // en passant capture exposing the king on the rank
+            "8/8/8/K2pP2r/8/8/8/7k w - d6 0 2",
+            // clocks must be restored exactly
+            "4k3/8/8/8/8/8/8/R3K2R b KQ - 99 120",
+        ];
+
+        for fen in POSITIONS.iter().chain(tricky_positions.iter()) {
+            let game = Chess::parse_fen(fen).unwrap();
+            let mut position = bitboard::Position::from(game);
+            let original = position;
+
+            // pseudo moves include illegal ones, eg. en passant exposing the king
+            for req_move in original.pseudo_moves(game.state.on_turn, bitboard::FULL) {
+                let undo = position.make_move(req_move);
+
+                // walk one ply deeper to cover unmaking in nested positions
+                let next = position;
+                for reply in Chess::get_all_moves(ParsedFen::from(next)) {
+                    let reply_undo = position.make_move(reply);
+                    position.unmake_move(reply_undo);
+
+                    assert_eq!(position, next, "{} {:?} {:?}", fen, req_move, reply);
+                }
+
+                position.unmake_move(undo);
+
+                assert_eq!(position, original, "{} {:?}", fen, req_move);
+                assert_eq!(ParsedFen::from(position), game);
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+#[cfg(test)]
+mod test_book {
+    use crate::tests::game;
+    use crate::zobrist::{DEFAULT_KEYS, POLYGLOT_RANDOM64_LENGTH};
+    use crate::{Chess, ChessError, PolyglotBook, ZobristKeys, INITIAL_FEN};
+    use std::collections::HashSet;
+
+    const CASTLING_FEN: &str = "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1";
+    const PROMOTION_FEN: &str = "8/P6k/8/8/8/8/8/K7 w - - 0 1";
+
+    /// Encode a UCI move (eg. "e2e4") with a promotion piece in the Polyglot format
+    fn raw_move(uci: &str, promotion: u16) -> u16 {
+        let bytes = uci.as_bytes();
+        let file = |idx: usize| u16::from(bytes[idx] - b'a');
+        let rank = |idx: usize| u16::from(bytes[idx] - b'1');
+
+        file(2) | rank(3) << 3 | file(0) << 6 | rank(1) << 9 | promotion << 12
+    }
+
+    fn entry(key: u64, uci: &str, promotion: u16, weight: u16) -> Vec<u8> {
+        let mut bytes = key.to_be_bytes().to_vec();
+        bytes.extend(raw_move(uci, promotion).to_be_bytes());
+        bytes.extend(weight.to_be_bytes());
+        bytes.extend(7u32.to_be_bytes());
+        bytes
+    }
+
+    fn uci_moves(book: &PolyglotBook, fen: &str) -> Vec<(String, u16)> {
+        book.moves(game(fen))
+            .into_iter()
+            .map(|book_move| (book_move.req_move.to_uci().unwrap(), book_move.weight))
+            .collect()
+    }
+
+    fn initial_book() -> PolyglotBook {
+        let key = game(INITIAL_FEN).hash();
+        let bytes = [
+            entry(key.wrapping_add(1), "a2a3", 0, 10),
+            entry(key, "d2d4", 0, 1),
+            entry(key, "e2e4", 0, 3),
+            entry(key, "e2e5", 0, 5),
+            entry(key, "g1f3", 0, 0),
+        ]
+        .concat();
+
+        PolyglotBook::from_bytes(&bytes, DEFAULT_KEYS.clone()).unwrap()
+    }
+
+    #[test]
+    pub fn test_invalid_length() {
+        assert!(matches!(
+            PolyglotBook::from_bytes(&[0; 17], DEFAULT_KEYS.clone()),
+            Err(ChessError::InvalidBook(_))
+        ));
+
+        let empty = PolyglotBook::from_bytes(&[], DEFAULT_KEYS.clone()).unwrap();
+        assert!(empty.is_empty());
+        assert!(empty.moves(game(INITIAL_FEN)).is_empty());
+    }
+
+    #[test]
+    pub fn test_moves() {
+        let book = initial_book();
+
+        assert_eq!(book.len(), 5);
+        assert_eq!(
+            uci_moves(&book, INITIAL_FEN),
+            // illegal e2e5 and other positions are skipped
+            vec![
+                ("e2e4".to_string(), 3),
+                ("d2d4".to_string(), 1),
+                ("g1f3".to_string(), 0)
+            ]
+        );
+        assert_eq!(book.moves(game(INITIAL_FEN))[0].learn, 7);
+        assert!(uci_moves(&book, CASTLING_FEN).is_empty());
+    }
+
+    #[test]
+    pub fn test_castling_and_promotion() {
+        let castling = game(CASTLING_FEN).hash();
+        let promotion = game(PROMOTION_FEN).hash();
+        let bytes = [
+            entry(castling, "e1h1", 0, 2),
+            entry(castling, "e1a1", 0, 1),
+            entry(promotion, "a7a8", 4, 2),
+            entry(promotion, "a7a8", 1, 1),
+        ]
+        .concat();
+        let book = PolyglotBook::from_bytes(&bytes, DEFAULT_KEYS.clone()).unwrap();
+
+        let moves = book.moves(game(CASTLING_FEN));
+        assert!(moves.iter().all(|book_move| book_move.req_move.is_castle));
+        assert_eq!(
+            uci_moves(&book, CASTLING_FEN),
+            vec![("e1g1".to_string(), 2), ("e1c1".to_string(), 1)]
+        );
+
+        assert_eq!(
+            uci_moves(&book, PROMOTION_FEN),
+            vec![("a7a8q".to_string(), 2), ("a7a8n".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    pub fn test_chess960_castling() {
+        // long castling and the plain king move both end on c1
+        let mut chess960 = game("4k3/8/8/8/8/8/8/RK6 w A - 0 1");
+        chess960.state.chess960 = true;
+        let key = chess960.hash();
+        let bytes = [entry(key, "b1a1", 0, 2), entry(key, "b1c1", 0, 1)].concat();
+        let book = PolyglotBook::from_bytes(&bytes, DEFAULT_KEYS.clone()).unwrap();
+
+        let moves: Vec<_> = book
+            .moves(chess960)
+            .into_iter()
+            .map(|book_move| {
+                let uci = Chess::move_to_uci(book_move.req_move, chess960).unwrap();
+                (uci, book_move.req_move.is_castle)
+            })
+            .collect();
+        assert_eq!(
+            moves,
+            vec![("b1a1".to_string(), true), ("b1c1".to_string(), false)]
+        );
+    }
+
+    #[test]
+    pub fn test_pick_move() {
+        let book = initial_book();
+        let initial = game(INITIAL_FEN);
+
+        assert_eq!(book.pick_move(initial, 5), book.pick_move(initial, 5));
+
+        let picked: HashSet<String> = (0..50)
+            .map(|seed| book.pick_move(initial, seed).unwrap().to_uci().unwrap())
+            .collect();
+
+        // moves without weight are never picked
+        assert_eq!(
+            picked,
+            HashSet::from(["e2e4".to_string(), "d2d4".to_string()])
+        );
+        assert_eq!(book.pick_move(game(CASTLING_FEN), 0), None);
+    }
+
+    #[test]
+    pub fn test_custom_keys() {
+        let mut random64 = [0u64; POLYGLOT_RANDOM64_LENGTH];
+        for (idx, key) in random64.iter_mut().enumerate() {
+            *key = (idx as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        }
+        let keys = ZobristKeys::from_polyglot_random64(&random64);
+
+        let initial = game(INITIAL_FEN);
+        let bytes = entry(initial.hash_with_keys(&keys), "e2e4", 0, 1);
+
+        let book = PolyglotBook::from_bytes(&bytes, keys).unwrap();
+        assert_eq!(uci_moves(&book, INITIAL_FEN), vec![("e2e4".to_string(), 1)]);
+
+        let default_book = PolyglotBook::from_bytes(&bytes, DEFAULT_KEYS.clone()).unwrap();
+        assert!(default_book.moves(initial).is_empty());
+    }
+}
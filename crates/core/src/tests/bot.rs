@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod test_bot {
+    use crate::{Chess, ChessError, INITIAL_FEN};
+    use std::collections::HashSet;
+
+    const ENDGAME_FEN: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+
+    #[test]
+    pub fn test_every_level_plays_legal_move() {
+        let game = Chess::parse_fen(ENDGAME_FEN).unwrap();
+        let legal_moves = Chess::get_all_moves(game);
+
+        for level in 1..=10 {
+            let req_move = Chess::bot_move(game, level, 7).unwrap();
+            assert!(legal_moves.contains(&req_move), "level {level}");
+        }
+    }
+
+    #[test]
+    pub fn test_same_seed_same_move() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+
+        for level in 1..=4 {
+            for seed in [0, 1, 42] {
+                assert_eq!(
+                    Chess::bot_move(game, level, seed),
+                    Chess::bot_move(game, level, seed)
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_weak_level_varies_with_seed() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let moves: HashSet<String> = (0..20)
+            .map(|seed| Chess::bot_move(game, 1, seed).unwrap())
+            .map(|req_move| req_move.to_uci().unwrap())
+            .collect();
+
+        assert!(moves.len() > 1);
+    }
+
+    #[test]
+    pub fn test_strong_levels() {
+        let hanging_queen = Chess::parse_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let mate_in_one = Chess::parse_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+
+        for level in 9..=10 {
+            for seed in [0, 1, 2] {
+                let req_move = Chess::bot_move(hanging_queen, level, seed).unwrap();
+                assert_eq!(req_move.to_uci().unwrap(), "d2d5");
+
+                let req_move = Chess::bot_move(mate_in_one, level, seed).unwrap();
+                assert_eq!(req_move.to_uci().unwrap(), "a1a8");
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_invalid_level() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+
+        assert_eq!(
+            Chess::bot_move(game, 0, 0),
+            Err(ChessError::InvalidBotLevel(0))
+        );
+        assert_eq!(
+            Chess::bot_move(game, 11, 0),
+            Err(ChessError::InvalidBotLevel(11))
+        );
+    }
+
+    #[test]
+    pub fn test_no_legal_moves() {
+        let mated = Chess::parse_fen("7k/8/8/8/8/8/5PPP/3r2K1 w - - 0 1").unwrap();
+
+        for level in [1, 10] {
+            assert!(matches!(
+                Chess::bot_move(mated, level, 0),
+                Err(ChessError::InvalidMove(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,200 @@
+#[cfg(test)]
+mod test_chess960 {
+    use crate::tests::{assert_perft, game};
+    use crate::{CastleRooks, Chess, ChessError, PieceKind, INITIAL_FEN};
+    use std::collections::HashSet;
+
+    // Chess960 perft positions, see https://www.chessprogramming.org/Chess960_Perft_Results
+    const POSITION_1: &str = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+    const POSITION_2: &str = "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9";
+
+    fn first_rank(index: u16) -> String {
+        let fen = Chess::stringify(&Chess::chess960_position(index).unwrap()).unwrap();
+        fen.split('/').next_back().unwrap()[..8].to_string()
+    }
+
+    #[test]
+    pub fn test_start_positions() {
+        assert_eq!(
+            Chess::stringify(&Chess::chess960_position(518).unwrap()).unwrap(),
+            INITIAL_FEN.replace("KQkq", "HAha")
+        );
+        assert_eq!(first_rank(0), "BBQNNRKR");
+        assert_eq!(first_rank(959), "RKRNNQBB");
+
+        let ranks: HashSet<String> = (0..960).map(first_rank).collect();
+        assert_eq!(ranks.len(), 960);
+
+        for rank in ranks {
+            let col = |piece: char| rank.find(piece).unwrap();
+            let rooks: Vec<usize> = rank.match_indices('R').map(|(col, _)| col).collect();
+            let bishops: Vec<usize> = rank.match_indices('B').map(|(col, _)| col).collect();
+
+            assert!(rooks[0] < col('K') && col('K') < rooks[1], "{}", rank);
+            assert_ne!(bishops[0] % 2, bishops[1] % 2, "{}", rank);
+        }
+
+        assert_eq!(
+            Chess::chess960_position(960),
+            Err(ChessError::InvalidChess960Index(960))
+        );
+    }
+
+    #[test]
+    pub fn test_castling_notation() {
+        let game = Chess::parse_fen(POSITION_1).unwrap();
+
+        assert!(game.state.chess960);
+        assert_eq!(
+            game.state.castle_rooks,
+            CastleRooks {
+                short_col: 7,
+                long_col: 5
+            }
+        );
+        // X-FEN writes outermost rooks as K and Q, Shredder-FEN always uses files
+        assert_eq!(
+            Chess::stringify(&game).unwrap(),
+            POSITION_1.replace("HFhf", "KQkq")
+        );
+        assert_eq!(Chess::stringify_shredder(&game).unwrap(), POSITION_1);
+        assert_eq!(
+            Chess::parse_fen(&POSITION_1.replace("HFhf", "KQkq")).unwrap(),
+            game
+        );
+
+        // inner rook needs its file letter in X-FEN, black rooks are both outermost
+        let inner = "1r2k1r1/8/8/8/8/8/8/RR2K1R1 w BGbg - 0 1";
+        let game = Chess::parse_fen(inner).unwrap();
+        assert_eq!(
+            Chess::stringify(&game).unwrap(),
+            "1r2k1r1/8/8/8/8/8/8/RR2K1R1 w KBkq - 0 1"
+        );
+
+        // standard positions keep the standard notation
+        let standard = Chess::parse_fen(INITIAL_FEN).unwrap();
+        assert!(!standard.state.chess960);
+        assert_eq!(Chess::stringify_shredder(&standard).unwrap(), INITIAL_FEN);
+
+        // different rook files of the players and rights without king are not supported
+        assert!(Chess::parse_fen("r3k2r/8/8/8/8/8/8/R3K1R1 w GAha - 0 1").is_err());
+        assert!(Chess::parse_fen("4k3/8/8/8/8/8/8/R5KR w - - 0 1").is_ok());
+        assert!(Chess::parse_fen("4k3/8/8/8/8/4K3/8/R6R w H - 0 1").is_err());
+    }
+
+    #[test]
+    pub fn test_castling_moves() {
+        // king on f1 castles short with the g1 rook, both pieces swap squares
+        let game = Chess::parse_fen("4k3/8/8/8/8/8/8/1R3KR1 w GB - 0 1").unwrap();
+        let short = Chess::uci_to_move("f1g1", game).unwrap();
+
+        assert!(short.is_castle);
+        assert_eq!(Chess::move_to_san(short, game).unwrap(), "O-O");
+        assert_eq!(Chess::move_to_uci(short, game).unwrap(), "f1g1");
+
+        let next = Chess::move_piece(short, game).unwrap();
+        assert_eq!(
+            Chess::stringify(&next).unwrap(),
+            "4k3/8/8/8/8/8/8/1R3RK1 b - - 1 1"
+        );
+
+        // long castling moves the king to c1 and the rook over it to d1
+        let long = Chess::san_to_move("O-O-O", game).unwrap();
+        assert_eq!(Chess::move_to_uci(long, game).unwrap(), "f1b1");
+        assert_eq!(
+            Chess::stringify(&Chess::move_piece(long, game).unwrap()).unwrap(),
+            "4k3/8/8/8/8/8/8/2KR2R1 b - - 1 1"
+        );
+
+        // king on g1 castles short without moving, f1 must be free
+        let game = Chess::parse_fen("4k3/8/8/8/8/8/8/R5KR w HA - 0 1").unwrap();
+        let short = Chess::uci_to_move("g1h1", game).unwrap();
+        assert_eq!((short.from_col_idx, short.to_col_idx), (6, 6));
+        assert_eq!(
+            Chess::stringify(&Chess::move_piece(short, game).unwrap()).unwrap(),
+            "4k3/8/8/8/8/8/8/R4RK1 b - - 1 1"
+        );
+
+        // passed squares must not be attacked
+        let attacked = Chess::parse_fen("3rk3/8/8/8/8/8/8/R5KR w HA - 0 1").unwrap();
+        assert!(Chess::uci_to_move("g1a1", attacked).is_err());
+    }
+
+    #[test]
+    pub fn test_castling_rights() {
+        let game = Chess::parse_fen("1r2k1r1/8/8/8/8/8/8/1R2K1R1 w GBgb - 0 1").unwrap();
+
+        // moving the castling rook loses the right, the other side keeps it
+        let next = Chess::move_piece(Chess::uci_to_move("g1g8", game).unwrap(), game).unwrap();
+        assert_eq!(
+            Chess::stringify_shredder(&next).unwrap(),
+            "1r2k1R1/8/8/8/8/8/8/1R2K3 b Bb - 0 1"
+        );
+
+        // standard king move to g1 is ambiguous in Chess960, only the rook square means castling
+        let game = Chess::parse_fen("4k3/8/8/8/8/8/8/R3K2R w HA - 0 1").unwrap();
+        let game = crate::ParsedFen {
+            state: crate::ParsedFenState {
+                chess960: true,
+                ..game.state
+            },
+            ..game
+        };
+        assert!(Chess::uci_to_move("e1g1", game).is_err());
+        assert!(Chess::uci_to_move("e1h1", game).unwrap().is_castle);
+
+        // outside Chess960 both forms work
+        let standard = Chess::parse_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        assert_eq!(
+            Chess::uci_to_move("e1h1", standard).unwrap(),
+            Chess::uci_to_move("e1g1", standard).unwrap()
+        );
+        assert_eq!(
+            Chess::move_to_uci(Chess::uci_to_move("e1h1", standard).unwrap(), standard).unwrap(),
+            "e1g1"
+        );
+    }
+
+    #[test]
+    pub fn test_standard_setup_round_trip() {
+        let game = Chess::chess960_position(518).unwrap();
+        let parsed = Chess::parse_fen(&Chess::stringify(&game).unwrap()).unwrap();
+
+        assert_eq!(parsed, game);
+        assert!(parsed.state.chess960);
+
+        // castling keeps the Chess960 notation after the round trip
+        let game = ["e2e4", "e7e5", "g1f3", "b8c6", "f1c4", "g8f6"]
+            .iter()
+            .fold(parsed, |game, uci| {
+                let next = Chess::move_piece(Chess::uci_to_move(uci, game).unwrap(), game).unwrap();
+                Chess::parse_fen(&Chess::stringify(&next).unwrap()).unwrap()
+            });
+        let castle = Chess::uci_to_move("e1h1", game).unwrap();
+
+        assert!(game.state.chess960);
+        assert!(castle.is_castle);
+        assert_eq!(Chess::move_to_uci(castle, game).unwrap(), "e1h1");
+        assert!(Chess::uci_to_move("e1g1", game).is_err());
+    }
+
+    #[test]
+    pub fn test_start_position_state() {
+        let game = Chess::chess960_position(0).unwrap();
+        let king = game.board[7][6].unwrap();
+
+        assert_eq!(king.piece_kind(), PieceKind::King);
+        assert_eq!(game.state.castle_rooks.col(true), 7);
+        assert_eq!(game.state.castle_rooks.col(false), 5);
+    }
+
+    #[test]
+    pub fn test_perft() {
+        for (fen, expected) in [
+            (POSITION_1, [21, 528, 12189, 326672]),
+            (POSITION_2, [21, 807, 18002, 667366]),
+        ] {
+            assert_perft(game(fen), &expected);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod test_crazyhouse {
+    use crate::tests::{assert_perft, assert_play_matches_state, game, play};
+    use crate::{Chess, ChessError, GameResult, Pocket};
+
+    const INITIAL_CRAZYHOUSE_FEN: &str =
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1";
+    const POSITIONS: [&str; 3] = [
+        "r1bqk2r/pppp1ppp/2n1p3/4P3/1b1Pn3/2NB1N2/PPP2PPP/R1BQK2R[] b KQkq - 0 1",
+        "2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - - 0 1",
+        // promotions and promoted queen, both sides can drop a knight
+        "r2q~k3/1P6/8/8/8/8/6p1/R3K2R[Nn] w KQq - 0 1",
+    ];
+
+    #[test]
+    pub fn test_fen_pockets() {
+        let initial = game(INITIAL_CRAZYHOUSE_FEN);
+        let pockets = initial.state.pockets.unwrap();
+
+        assert!(pockets.white.is_empty() && pockets.black.is_empty());
+        assert_eq!(Chess::stringify(&initial).unwrap(), INITIAL_CRAZYHOUSE_FEN);
+        assert_eq!(game(crate::INITIAL_FEN).state.pockets, None);
+
+        let fen = "r2q~k3/1P6/8/8/8/8/6p1/R3K2R[QPPbn] w KQq - 0 1";
+        let pockets = game(fen).state.pockets.unwrap();
+
+        assert_eq!(
+            pockets.white,
+            Pocket {
+                queens: 1,
+                pawns: 2,
+                ..Pocket::default()
+            }
+        );
+        assert_eq!(pockets.black.count(crate::PieceKind::Knight), 1);
+        assert!(pockets.promoted[0][3]);
+        assert_eq!(Chess::stringify(&game(fen)).unwrap(), fen);
+
+        // kings can't be in a pocket, promoted marks need a piece and pockets
+        for invalid in [
+            "4k3/8/8/8/8/8/8/4K3[K] w - - 0 1",
+            "4k3/8/8/8/8/8/8/4K3[ w - - 0 1",
+            "4k3/8/8/8/8/8/8/~3K3[] w - - 0 1",
+            "4k3/8/8/8/8/8/8/4K~3[] w - - 0 1",
+            "4k3/8/8/8/8/8/8/Q~3K3 w - - 0 1",
+        ] {
+            assert!(Chess::parse_fen(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    pub fn test_captures_fill_pockets() {
+        let exchanged = play(
+            game(INITIAL_CRAZYHOUSE_FEN),
+            &["e2e4", "d7d5", "e4d5", "d8d5"],
+        );
+
+        assert_eq!(
+            Chess::stringify(&exchanged).unwrap(),
+            "rnb1kbnr/ppp1pppp/8/3q4/8/8/PPPP1PPP/RNBQKBNR[Pp] w KQkq - 0 3"
+        );
+
+        // promoted pieces keep the mark when moving and return to the pocket as pawns
+        let moved = play(
+            game("8/P3k3/8/8/8/8/4K3/8[] w - - 0 1"),
+            &["a7a8q", "e7d6", "a8h8"],
+        );
+        assert_eq!(
+            Chess::stringify(&moved).unwrap(),
+            "7Q~/8/3k4/8/8/8/4K3/8[] b - - 2 2"
+        );
+
+        let captured = play(
+            game("8/P3k3/8/8/8/8/4K3/r7[] w - - 0 1"),
+            &["a7a8q", "a1a8"],
+        );
+        assert_eq!(
+            Chess::stringify(&captured).unwrap(),
+            "r7/4k3/8/8/8/8/4K3/8[p] w - - 0 2"
+        );
+    }
+
+    #[test]
+    pub fn test_drops() {
+        // pawns can't be dropped on the first and last rank
+        let drops = Chess::get_drop_moves(game("4k3/8/8/8/8/8/8/4K3[Pn] w - - 0 1"));
+
+        assert_eq!(drops.len(), 48);
+        assert!(drops
+            .iter()
+            .all(|m| m.is_drop && m.to_row_idx != 0 && m.to_row_idx != 7));
+
+        // in check only drops blocking the check are legal
+        let check = game("4k3/8/8/8/8/8/8/r3K3[N] w - - 0 1");
+        assert_eq!(Chess::get_drop_moves(check).len(), 3);
+        assert_eq!(Chess::get_all_moves(check).len(), 6);
+
+        let dropped = play(game("4k3/8/8/8/8/8/8/r3K3[N] w - - 0 1"), &["N@d1"]);
+        assert_eq!(
+            Chess::stringify(&dropped).unwrap(),
+            "4k3/8/8/8/8/8/8/r2NK3[] b - - 1 1"
+        );
+
+        // missing piece, occupied square and pawn to the last rank
+        let game = game("4k3/8/8/8/8/8/8/4K3[P] w - - 0 1");
+        let pawn_drop = Chess::uci_to_move("P@e4", game).unwrap();
+
+        for invalid in [
+            crate::Move {
+                piece: crate::PieceType::WhiteQueen,
+                ..pawn_drop
+            },
+            crate::Move {
+                from_row_idx: 7,
+                to_row_idx: 7,
+                ..pawn_drop
+            },
+            crate::Move {
+                from_row_idx: 0,
+                from_col_idx: 0,
+                to_row_idx: 0,
+                to_col_idx: 0,
+                ..pawn_drop
+            },
+        ] {
+            assert!(matches!(
+                Chess::move_piece(invalid, game),
+                Err(ChessError::InvalidMove(_))
+            ));
+        }
+    }
+
+    #[test]
+    pub fn test_drop_notation() {
+        let game = game("4k3/8/8/8/8/8/8/4K3[PN] w - - 0 1");
+        let knight_drop = Chess::san_to_move("N@d6", game).unwrap();
+
+        assert!(knight_drop.is_drop);
+        assert_eq!(Chess::move_to_san(knight_drop, game).unwrap(), "N@d6+");
+        assert_eq!(Chess::move_to_uci(knight_drop, game).unwrap(), "N@d6");
+        assert_eq!(Chess::uci_to_move("N@d6", game).unwrap(), knight_drop);
+
+        // pawn drops may omit the piece letter
+        let pawn_drop = Chess::san_to_move("@e4", game).unwrap();
+        assert_eq!(Chess::san_to_move("P@e4", game).unwrap(), pawn_drop);
+        assert_eq!(Chess::move_to_san(pawn_drop, game).unwrap(), "P@e4");
+
+        // drops aren't mistaken for moves of pieces on the board
+        assert!(Chess::san_to_move("Nd6", game).is_err());
+        assert!(Chess::san_to_move("Q@e4", game).is_err());
+        assert!(Chess::uci_to_move("P@e8", game).is_err());
+    }
+
+    #[test]
+    pub fn test_game_result() {
+        // drop blocking the check saves from checkmate
+        let check = "4k3/8/8/8/8/8/1r6/r3K3[N] w - - 0 1";
+        assert_eq!(Chess::get_game_result(game(check)).unwrap(), None);
+        assert_eq!(
+            Chess::get_game_result(game(&check.replace("[N]", "[]"))).unwrap(),
+            Some(GameResult::BlackCheckmate)
+        );
+
+        // pieces in pockets can still mate
+        assert_eq!(
+            Chess::get_game_result(game("4k3/8/8/8/8/8/8/4K3[b] w - - 0 1")).unwrap(),
+            None
+        );
+        assert_eq!(
+            Chess::get_game_result(game("4k3/8/8/8/8/8/8/4K3[] w - - 0 1")).unwrap(),
+            Some(GameResult::InsufficientMaterial)
+        );
+    }
+
+    #[test]
+    pub fn test_play_matches_state() {
+        for fen in POSITIONS {
+            assert_play_matches_state(game(fen));
+        }
+    }
+
+    #[test]
+    pub fn test_perft() {
+        assert_perft(game(INITIAL_CRAZYHOUSE_FEN), &[20, 400, 8902, 197281]);
+
+        // every pocket piece of both players
+        assert_perft(game(POSITIONS[1]), &[301, 75353]);
+    }
+}
@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod test_evaluation {
+    use crate::{
+        Chess, EvaluationWeights, MobilityWeights, ParsedFen, PhaseScore, PhaseTable,
+        PieceSquareTables, PieceType, PieceValues, INITIAL_FEN,
+    };
+
+    const POSITIONS: [&str; 5] = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    ];
+
+    fn empty_weights() -> EvaluationWeights {
+        let score = PhaseScore::default();
+        let table = PhaseTable {
+            middlegame: [[0; 8]; 8],
+            endgame: [[0; 8]; 8],
+        };
+
+        EvaluationWeights {
+            material: PieceValues {
+                pawn: score,
+                knight: score,
+                bishop: score,
+                rook: score,
+                queen: score,
+            },
+            piece_square_tables: PieceSquareTables {
+                pawn: table,
+                knight: table,
+                bishop: table,
+                rook: table,
+                queen: table,
+                king: table,
+            },
+            doubled_pawn: score,
+            isolated_pawn: score,
+            passed_pawn: [score; 8],
+            pawn_shield: score,
+            king_open_file: score,
+            king_zone_attack: score,
+            mobility: MobilityWeights {
+                knight: score,
+                bishop: score,
+                rook: score,
+                queen: score,
+            },
+        }
+    }
+
+    /// Swap colors and flip the board vertically
+    fn mirror(game: ParsedFen) -> ParsedFen {
+        let mut mirrored = game;
+
+        for (row_idx, row) in game.board.iter().enumerate() {
+            for (col_idx, piece) in row.iter().enumerate() {
+                mirrored.board[7 - row_idx][col_idx] = piece.map(|piece| {
+                    PieceType::from_kind(piece.piece_kind(), piece.color().opponent())
+                });
+            }
+        }
+
+        let state = &mut mirrored.state;
+        state.on_turn = game.state.on_turn.opponent();
+        state.castle_white_short = game.state.castle_black_short;
+        state.castle_white_long = game.state.castle_black_long;
+        state.castle_black_short = game.state.castle_white_short;
+        state.castle_black_long = game.state.castle_white_long;
+        state.en_passant_square = game.state.en_passant_square.map(|mut square| {
+            square.row = 7 - square.row;
+            square
+        });
+
+        mirrored
+    }
+
+    fn evaluate(fen: &str, weights: &EvaluationWeights) -> i32 {
+        Chess::evaluate_with_weights(Chess::parse_fen(fen).unwrap(), weights)
+    }
+
+    #[test]
+    pub fn test_initial_position_is_equal() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        assert_eq!(Chess::evaluate(game), 0);
+    }
+
+    #[test]
+    pub fn test_mirrored_position_negates_score() {
+        for fen in POSITIONS {
+            let game = Chess::parse_fen(fen).unwrap();
+            assert_eq!(
+                Chess::evaluate(game),
+                -Chess::evaluate(mirror(game)),
+                "{fen}"
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_default_weights() {
+        for fen in POSITIONS {
+            let game = Chess::parse_fen(fen).unwrap();
+            assert_eq!(
+                Chess::evaluate(game),
+                Chess::evaluate_with_weights(game, &EvaluationWeights::default())
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_material_advantage() {
+        let game =
+            Chess::parse_fen("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        assert!(Chess::evaluate(game) > 800);
+        assert!(Chess::evaluate(mirror(game)) < -800);
+    }
+
+    #[test]
+    pub fn test_tapered_phase() {
+        let mut weights = empty_weights();
+        weights.material.queen = PhaseScore::new(1000, 400);
+
+        // only the queen is left (phase 4 of 24)
+        assert_eq!(evaluate("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", &weights), 500);
+
+        // both queens cancel out
+        assert_eq!(evaluate("3qk3/8/8/8/8/8/8/3QK3 w - - 0 1", &weights), 0);
+    }
+
+    #[test]
+    pub fn test_pawn_structure() {
+        let doubled = "4k3/8/8/8/8/4P3/4P3/4K3 w - - 0 1";
+
+        let mut weights = empty_weights();
+        weights.doubled_pawn = PhaseScore::new(-10, -10);
+        assert_eq!(evaluate(doubled, &weights), -10);
+
+        let mut weights = empty_weights();
+        weights.isolated_pawn = PhaseScore::new(-7, -7);
+        assert_eq!(evaluate(doubled, &weights), -14);
+        assert_eq!(evaluate("4k3/8/8/8/8/8/3PP3/4K3 w - - 0 1", &weights), 0);
+
+        let mut weights = empty_weights();
+        weights.passed_pawn[4] = PhaseScore::new(50, 50);
+        assert_eq!(evaluate("4k3/8/8/3P4/8/8/8/4K3 w - - 0 1", &weights), 50);
+        assert_eq!(evaluate("4k3/4p3/8/3P4/8/8/8/4K3 w - - 0 1", &weights), 0);
+        assert_eq!(evaluate("4k3/8/8/8/3p4/8/8/4K3 w - - 0 1", &weights), -50);
+    }
+
+    #[test]
+    pub fn test_king_safety() {
+        let shelter = "6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1";
+
+        let mut weights = empty_weights();
+        weights.pawn_shield = PhaseScore::new(1, 1);
+        assert_eq!(evaluate(shelter, &weights), 3);
+
+        let mut weights = empty_weights();
+        weights.king_open_file = PhaseScore::new(-1, -1);
+        assert_eq!(evaluate(shelter, &weights), 3);
+
+        let mut weights = empty_weights();
+        weights.king_zone_attack = PhaseScore::new(-1, -1);
+        assert_eq!(evaluate("k7/8/8/8/8/8/8/1R4K1 w - - 0 1", &weights), 2);
+    }
+
+    #[test]
+    pub fn test_mobility() {
+        let mut weights = empty_weights();
+        weights.mobility.knight = PhaseScore::new(1, 1);
+        assert_eq!(evaluate("4k3/8/8/8/8/8/8/N3K3 w - - 0 1", &weights), 2);
+        assert_eq!(evaluate("4k3/8/8/8/8/8/8/4K2N w - - 0 1", &weights), 2);
+
+        // squares attacked by opponent pawns don't count
+        assert_eq!(evaluate("4k3/8/8/8/2p5/8/8/N3K3 w - - 0 1", &weights), 1);
+    }
+}
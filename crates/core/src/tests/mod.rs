@@ -1,9 +1,41 @@
 // src/tests/mod.rs
 // This makes tests/ a module within your crate
 
+// original tests compare booleans with `assert_eq!`
+#![allow(clippy::bool_assert_comparison)]
+
+use crate::{Chess, ParsedFen, SearchLimits, VariantKind};
+
+#[cfg(test)]
+mod antichess;
+
+#[cfg(test)]
+mod atomic;
+
+#[cfg(test)]
+mod attacks;
+
+#[cfg(test)]
+mod bitboard;
+
 #[cfg(test)]
 mod bishop;
 
+#[cfg(test)]
+mod book;
+
+#[cfg(test)]
+mod bot;
+
+#[cfg(test)]
+mod chess960;
+
+#[cfg(test)]
+mod crazyhouse;
+
+#[cfg(test)]
+mod evaluation;
+
 #[cfg(test)]
 mod fen;
 
@@ -13,23 +45,128 @@ mod king;
 #[cfg(test)]
 mod knight;
 
+#[cfg(test)]
+mod moves;
+
 #[cfg(test)]
 mod pawn;
 
+#[cfg(test)]
+mod perft;
+
+#[cfg(test)]
+mod pgn;
+
 #[cfg(test)]
 mod queen;
 
 #[cfg(test)]
 mod rook;
 
+#[cfg(test)]
+mod san;
+
+#[cfg(test)]
+mod search;
+
 #[cfg(test)]
 mod square;
 
 #[cfg(test)]
 mod state;
 
+#[cfg(test)]
+mod tablebase;
+
+#[cfg(test)]
+mod transposition;
+
 #[cfg(test)]
 mod validation;
 
 #[cfg(test)]
-mod result;
\ No newline at end of file
+mod variant;
+
+#[cfg(test)]
+mod result;
+
+#[cfg(test)]
+mod uci;
+
+#[cfg(test)]
+mod zobrist;
+
+// Helpers shared by the test modules
+
+/// Parses a FEN, the variant is detected like in `Chess::parse_fen` (eg. Crazyhouse pockets)
+fn game(fen: &str) -> ParsedFen {
+    Chess::parse_fen(fen).unwrap()
+}
+
+/// Parses a FEN of the variant
+fn variant_game(fen: &str, variant: VariantKind) -> ParsedFen {
+    Chess::parse_fen_with_variant(fen, variant).unwrap()
+}
+
+/// Plays moves in UCI notation (eg. "e2e4", "N@d1") from the position
+fn play(game: ParsedFen, moves: &[&str]) -> ParsedFen {
+    moves.iter().fold(game, |game, uci| {
+        Chess::move_piece(Chess::uci_to_move(uci, game).unwrap(), game).unwrap()
+    })
+}
+
+/// Plays moves in SAN notation from the position, returning every position including the first one
+fn play_san(game: ParsedFen, san_moves: &[&str]) -> Vec<ParsedFen> {
+    let mut history = vec![game];
+
+    for san in san_moves {
+        let game = *history.last().unwrap();
+        let req_move = Chess::san_to_move(san, game).unwrap();
+        history.push(Chess::move_piece(req_move, game).unwrap());
+    }
+
+    history
+}
+
+/// Search limited only by depth
+fn depth(depth: u32) -> SearchLimits {
+    SearchLimits {
+        depth: Some(depth),
+        ..SearchLimits::default()
+    }
+}
+
+/// Plays every legal move on the bitboard, compares the position and its hash with the one
+/// created by validation and checks that unmaking the move restores the original position
+fn assert_play_matches_state(game: ParsedFen) {
+    let mut position = crate::bitboard::Position::from(game);
+
+    for req_move in Chess::get_all_moves(game) {
+        let expected = crate::validation::validate_move(req_move, game).unwrap();
+        let played = ParsedFen::from(position.play(req_move));
+        assert_eq!(played, expected, "{:?}", req_move);
+
+        let undo = position.make_move(req_move);
+
+        assert_eq!(ParsedFen::from(position), expected, "{:?}", req_move);
+        assert_eq!(position.hash(), expected.hash(), "{:?}", req_move);
+
+        position.unmake_move(undo);
+        assert_eq!(ParsedFen::from(position), game);
+    }
+}
+
+/// Compares perft node counts, starting at depth 1
+fn assert_perft(game: ParsedFen, expected: &[u64]) {
+    let fen = Chess::stringify(&game).unwrap();
+
+    for (depth, nodes) in expected.iter().enumerate() {
+        assert_eq!(
+            Chess::perft(game, depth as u32 + 1),
+            *nodes,
+            "{} at depth {}",
+            fen,
+            depth + 1
+        );
+    }
+}
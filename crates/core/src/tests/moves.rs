@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod test_all_moves {
+    use crate::{square, Chess, INITIAL_FEN};
+
+    #[test]
+    pub fn test_initial_position() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let moves = Chess::get_all_moves(game);
+
+        assert_eq!(moves.len(), 20);
+        assert!(moves.iter().all(|m| m.piece.is_white()));
+    }
+
+    #[test]
+    pub fn test_only_player_on_turn() {
+        let game = Chess::parse_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")
+            .unwrap();
+        let moves = Chess::get_all_moves(game);
+
+        assert_eq!(moves.len(), 20);
+        assert!(moves.iter().all(|m| !m.piece.is_white()));
+    }
+
+    #[test]
+    pub fn test_in_check() {
+        let game =
+            Chess::parse_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")
+                .unwrap();
+        let moves = Chess::get_all_moves(game);
+
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    pub fn test_grouped_by_square() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let grouped = Chess::get_all_moves_by_square(game);
+
+        // 8 pawns and 2 knights can move
+        assert_eq!(grouped.len(), 10);
+        assert_eq!(
+            grouped.iter().map(|g| g.moves.len()).sum::<usize>(),
+            Chess::get_all_moves(game).len()
+        );
+
+        let knight = grouped
+            .iter()
+            .find(|g| g.square == square::Square::new(7, 1))
+            .unwrap();
+
+        assert_eq!(knight.moves.len(), 2);
+        assert!(knight
+            .moves
+            .iter()
+            .all(|m| m.from_row_idx == 7 && m.from_col_idx == 1));
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod test_pawn_moves {
-    use crate::{moves, pieces, player, square, types, Chess};
+    use crate::{moves, pieces, player, square, types, variant, Chess};
 
     #[test]
     fn test_white_en_passant() {
@@ -89,6 +89,11 @@ mod test_pawn_moves {
                 castle_black_long: false,
                 half_moves: 0,
                 full_moves: 1,
+                castle_rooks: types::CastleRooks::default(),
+                chess960: false,
+                pockets: None,
+                variant: variant::VariantKind::Standard,
+                checks: types::Checks::default(),
             },
         };
 
@@ -120,4 +125,34 @@ mod test_pawn_moves {
 
         assert_eq!(moves.len(), 0);
     }
+
+    #[test]
+    fn test_promotion_choices() {
+        let fen = "8/1P4k1/8/8/8/8/6K1/2n5 w - - 0 1";
+        let result = Chess::parse_fen(fen).unwrap();
+        let moves = moves::get_pseudo_moves(square::Square::new(1, 1), result);
+
+        assert_eq!(moves.len(), 4);
+        assert!(moves.iter().all(|m| m.to_row_idx == 0 && m.to_col_idx == 1));
+        assert_eq!(
+            moves.iter().map(|m| m.promotion_piece).collect::<Vec<_>>(),
+            vec![
+                Some(pieces::PieceType::WhiteQueen),
+                Some(pieces::PieceType::WhiteRook),
+                Some(pieces::PieceType::WhiteBishop),
+                Some(pieces::PieceType::WhiteKnight),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_promotion_captures() {
+        let fen = "2n5/1P4k1/8/8/8/8/6K1/8 w - - 0 1";
+        let result = Chess::parse_fen(fen).unwrap();
+        let moves = moves::get_pseudo_moves(square::Square::new(1, 1), result);
+
+        assert_eq!(moves.len(), 8);
+        assert_eq!(moves.iter().filter(|m| m.to_col_idx == 2).count(), 4);
+        assert!(moves.iter().all(|m| m.promotion_piece.is_some()));
+    }
 }
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod test_perft {
+    use crate::tests::{assert_perft, game};
+    use crate::{bitboard, perft, types, Chess, INITIAL_FEN};
+
+    // Standard positions and their node counts, see https://www.chessprogramming.org/Perft_Results
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+    const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+    const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+    const POSITION_6: &str =
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+    /// Walk both move generators node by node and require the same moves and positions.
+    fn assert_generators_agree(game: types::ParsedFen, depth: u32) {
+        if depth == 0 {
+            return;
+        }
+
+        let position = bitboard::Position::from(game);
+        let mut fast: Vec<(String, String)> = Chess::get_all_moves(game)
+            .into_iter()
+            .map(|m| {
+                let next_game = types::ParsedFen::from(position.play(m));
+                (m.to_uci().unwrap(), Chess::stringify(&next_game).unwrap())
+            })
+            .collect();
+        let mut validated: Vec<(String, String)> = perft::validated_moves(game)
+            .into_iter()
+            .map(|(m, next_game)| (m.to_uci().unwrap(), Chess::stringify(&next_game).unwrap()))
+            .collect();
+
+        fast.sort();
+        validated.sort();
+        assert_eq!(fast, validated, "{}", Chess::stringify(&game).unwrap());
+
+        for (_, next_game) in perft::validated_moves(game) {
+            assert_generators_agree(next_game, depth - 1);
+        }
+    }
+
+    #[test]
+    pub fn test_perft_initial() {
+        assert_perft(game(INITIAL_FEN), &[20, 400, 8902, 197281]);
+    }
+
+    #[test]
+    pub fn test_perft_kiwipete() {
+        assert_perft(game(KIWIPETE), &[48, 2039, 97862, 4085603]);
+    }
+
+    #[test]
+    pub fn test_perft_position_3() {
+        assert_perft(game(POSITION_3), &[14, 191, 2812, 43238, 674624]);
+    }
+
+    #[test]
+    pub fn test_perft_position_4() {
+        assert_perft(game(POSITION_4), &[6, 264, 9467, 422333]);
+    }
+
+    #[test]
+    pub fn test_perft_position_5() {
+        assert_perft(game(POSITION_5), &[44, 1486, 62379, 2103487]);
+    }
+
+    #[test]
+    pub fn test_perft_position_6() {
+        assert_perft(game(POSITION_6), &[46, 2079, 89890, 3894594]);
+    }
+
+    #[test]
+    pub fn test_perft_divide() {
+        let game = Chess::parse_fen(POSITION_5).unwrap();
+        let divided = Chess::perft_divide(game, 2);
+
+        assert_eq!(divided.len(), 44);
+        assert_eq!(divided.iter().map(|(_, nodes)| nodes).sum::<u64>(), 1486);
+        assert!(divided
+            .iter()
+            .any(|(m, nodes)| m.to_uci().as_deref() == Some("e1g1") && *nodes == 34));
+        assert!(Chess::perft_divide(game, 0).is_empty());
+    }
+
+    #[test]
+    pub fn test_perft_validated() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        assert_eq!(perft::perft_validated(game, 3), 8902);
+
+        let game = Chess::parse_fen(POSITION_3).unwrap();
+        assert_eq!(perft::perft_validated(game, 3), 2812);
+
+        let game = Chess::parse_fen(KIWIPETE).unwrap();
+        assert_eq!(perft::perft_validated(game, 2), 2039);
+    }
+
+    #[test]
+    pub fn test_generators_agree() {
+        for fen in [
+            INITIAL_FEN,
+            KIWIPETE,
+            POSITION_3,
+            POSITION_4,
+            POSITION_5,
+            POSITION_6,
+        ] {
+            assert_generators_agree(Chess::parse_fen(fen).unwrap(), 2);
+        }
+    }
+}
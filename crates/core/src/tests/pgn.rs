@@ -0,0 +1,300 @@
+#[cfg(test)]
+mod test_pgn {
+    use crate::{pieces, Chess, ChessError, INITIAL_FEN};
+
+    pub(super) const IMMORTAL_GAME: &str = r#"[Event "London"]
+[Site "London ENG"]
+[Date "1851.06.21"]
+[Round "?"]
+[White "Adolf Anderssen"]
+[Black "Lionel Kieseritzky"]
+[Result "1-0"]
+
+1.e4 e5 2.f4 exf4 3.Bc4 Qh4+ 4.Kf1 b5 5.Bxb5 Nf6 6.Nf3 Qh6 7.d3 Nh5 8.Nh4 Qg5
+9.Nf5 c6 10.g4 Nf6 11.Rg1 cxb5 12.h4 Qg6 13.h5 Qg5 14.Qf3 Ng8 15.Bxf4 Qf6
+16.Nc3 Bc5 17.Nd5 Qxb2 18.Bd6 Bxg1 19.e5 Qxa1+ 20.Ke2 Na6 21.Nxg7+ Kd8
+22.Qf6+ Nxf6 23.Be7# 1-0
+"#;
+
+    #[test]
+    pub fn test_tags_and_moves() {
+        let game = Chess::parse_pgn(IMMORTAL_GAME).unwrap();
+
+        assert_eq!(game.tags.len(), 7);
+        assert_eq!(game.tag("White"), Some("Adolf Anderssen"));
+        assert_eq!(game.tag("Result"), Some("1-0"));
+        assert_eq!(game.tag("ECO"), None);
+        assert_eq!(game.result, Some("1-0".to_string()));
+        assert_eq!(game.moves.len(), 45);
+        assert_eq!(game.positions.len(), 46);
+        assert_eq!(game.positions[0], Chess::parse_fen(INITIAL_FEN).unwrap());
+        assert_eq!(
+            Chess::stringify(game.positions.last().unwrap()).unwrap(),
+            "r1bk3r/p2pBpNp/n4n2/1p1NP2P/6P1/3P4/P1P1K3/q5b1 b - - 1 23"
+        );
+        assert_eq!(
+            Chess::get_game_result(*game.positions.last().unwrap()).unwrap(),
+            Some(crate::GameResult::WhiteCheckmate)
+        );
+    }
+
+    #[test]
+    pub fn test_comments_nags_and_variations() {
+        let pgn = r#"[Event "Annotated"]
+
+1. e4 {Best by test} e5 $1 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) 2... Nc6 ; Two knights
+3. Bc4!? Nf6 {Two knights defense} *"#;
+        let game = Chess::parse_pgn(pgn).unwrap();
+
+        assert_eq!(game.moves.len(), 6);
+        assert_eq!(game.result, Some("*".to_string()));
+        assert_eq!(game.moves[2].piece, pieces::PieceType::WhiteKnight);
+        assert_eq!(game.moves[4].piece, pieces::PieceType::WhiteBishop);
+    }
+
+    #[test]
+    pub fn test_escaped_lines() {
+        let pgn = "% exported by a tool\n[Event \"Escaped\"]\n\n1. e4 e5\n%2. d4 d5\r\n%\n2. Nf3 *";
+        let game = Chess::parse_pgn(pgn).unwrap();
+
+        assert_eq!(game.tag("Event"), Some("Escaped"));
+        assert_eq!(game.moves.len(), 3);
+        assert_eq!(game.moves[2].piece, pieces::PieceType::WhiteKnight);
+
+        // outside of the first column the percent sign is not an escape
+        assert!(Chess::parse_pgn("1. e4 %e5 2. Nf3 *").is_err());
+    }
+
+    #[test]
+    pub fn test_custom_start_position() {
+        let pgn = r#"[Event "Promotion"]
+[SetUp "1"]
+[FEN "1rbn4/1pkpP3/1pp5/8/8/8/8/7K w - - 0 1"]
+
+1. e8=N# 1-0"#;
+        let game = Chess::parse_pgn(pgn).unwrap();
+
+        assert_eq!(game.moves.len(), 1);
+        assert_eq!(
+            game.moves[0].promotion_piece,
+            Some(pieces::PieceType::WhiteKnight)
+        );
+        assert_eq!(
+            game.positions[1].board[0][4],
+            Some(pieces::PieceType::WhiteKnight)
+        );
+    }
+
+    #[test]
+    pub fn test_escaped_tag_value() {
+        let pgn = r#"[Event "The \"Immortal\" game \\ London"]
+
+1. e4 *"#;
+        let game = Chess::parse_pgn(pgn).unwrap();
+
+        assert_eq!(game.tag("Event"), Some(r#"The "Immortal" game \ London"#));
+    }
+
+    #[test]
+    pub fn test_multiple_games() {
+        let pgn = format!(
+            "{}\n{}",
+            IMMORTAL_GAME, "[Event \"Second\"]\n\n1. d4 d5 1/2-1/2\n"
+        );
+        let games = Chess::parse_pgn_games(&pgn).unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[1].tag("Event"), Some("Second"));
+        assert_eq!(games[1].moves.len(), 2);
+        assert_eq!(games[1].result, Some("1/2-1/2".to_string()));
+    }
+
+    #[test]
+    pub fn test_illegal_move_reports_ply() {
+        let pgn = "1. e4 e5 2. Nf3 Nc6 3. Bb5 Nf3 *";
+
+        assert_eq!(
+            Chess::parse_pgn(pgn),
+            Err(ChessError::InvalidPgnMove(6, "Nf3".to_string()))
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_syntax() {
+        assert!(matches!(
+            Chess::parse_pgn("[Event \"Unterminated]\n1. e4"),
+            Err(ChessError::InvalidPgn(_))
+        ));
+        assert!(matches!(
+            Chess::parse_pgn("1. e4 {never closed"),
+            Err(ChessError::InvalidPgn(_))
+        ));
+        assert!(matches!(
+            Chess::parse_pgn("1. e4 (1. d4"),
+            Err(ChessError::InvalidPgn(_))
+        ));
+        assert!(matches!(
+            Chess::parse_pgn(""),
+            Err(ChessError::InvalidPgn(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_pgn_export {
+    use crate::tests::play_san;
+    use crate::{Chess, ParsedFen, PgnGame, PgnTag, VariantKind, INITIAL_FEN};
+
+    #[test]
+    pub fn test_round_trip() {
+        let game = Chess::parse_pgn(super::test_pgn::IMMORTAL_GAME).unwrap();
+        let pgn = Chess::to_pgn(&game).unwrap();
+
+        assert!(pgn.starts_with(
+            "[Event \"London\"]\n[Site \"London ENG\"]\n[Date \"1851.06.21\"]\n[Round \"?\"]\n"
+        ));
+        assert!(pgn.contains("\n\n1. e4 e5 2. f4 exf4 3. Bc4 Qh4+ 4. Kf1 b5"));
+        assert!(pgn.ends_with("23. Be7# 1-0\n"));
+        assert!(!pgn.contains("[FEN"));
+        assert!(pgn.lines().all(|line| line.len() <= 80));
+
+        let reparsed = Chess::parse_pgn(&pgn).unwrap();
+        assert_eq!(reparsed.moves, game.moves);
+        assert_eq!(reparsed.tags, game.tags);
+    }
+
+    #[test]
+    pub fn test_default_tags() {
+        let game = PgnGame {
+            tags: vec![PgnTag {
+                name: "Annotator".to_string(),
+                value: "Quote \" and \\".to_string(),
+            }],
+            moves: Vec::new(),
+            positions: vec![Chess::parse_fen(INITIAL_FEN).unwrap()],
+            result: None,
+        };
+
+        assert_eq!(
+            Chess::to_pgn(&game).unwrap(),
+            "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n[White \"?\"]\n\
+             [Black \"?\"]\n[Result \"*\"]\n[Annotator \"Quote \\\" and \\\\\"]\n\n*\n"
+        );
+    }
+
+    #[test]
+    pub fn test_custom_start_position() {
+        let start = Chess::parse_fen("4k3/8/8/8/8/8/p7/4K3 b - - 0 40").unwrap();
+        let first = Chess::san_to_move("a1=Q+", start).unwrap();
+        let after_first = Chess::move_piece(first, start).unwrap();
+        let second = Chess::san_to_move("Ke2", after_first).unwrap();
+        let after_second = Chess::move_piece(second, after_first).unwrap();
+
+        let game = PgnGame {
+            tags: Vec::new(),
+            moves: vec![first, second],
+            positions: vec![start, after_first, after_second],
+            result: None,
+        };
+        let pgn = Chess::to_pgn(&game).unwrap();
+
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/p7/4K3 b - - 0 40\"]\n"));
+        assert!(pgn.ends_with("\n\n40... a1=Q+ 41. Ke2 *\n"));
+
+        let reparsed = Chess::parse_pgn(&pgn).unwrap();
+        assert_eq!(reparsed.positions, game.positions);
+    }
+
+    #[test]
+    pub fn test_mate_by_pinned_piece() {
+        let start = Chess::parse_fen("8/3b4/8/5R2/6nP/6PK/7P/5k2 b - - 0 1").unwrap();
+        let mate = Chess::san_to_move("Nf2", start).unwrap();
+        let end = Chess::move_piece(mate, start).unwrap();
+        let result = Chess::get_game_result(end).unwrap().unwrap();
+
+        let game = PgnGame {
+            tags: Vec::new(),
+            moves: vec![mate],
+            positions: vec![start, end],
+            result: Some(result.to_pgn_result().to_string()),
+        };
+        let pgn = Chess::to_pgn(&game).unwrap();
+
+        assert!(pgn.contains("[Result \"0-1\"]"));
+        assert!(pgn.ends_with("\n\n1... Nf2# 0-1\n"));
+    }
+
+    /// Game of the SAN moves played from the start position
+    fn pgn_game(start: ParsedFen, san_moves: &[&str]) -> PgnGame {
+        let positions = play_san(start, san_moves);
+        let moves = san_moves
+            .iter()
+            .zip(&positions)
+            .map(|(san, position)| Chess::san_to_move(san, *position).unwrap())
+            .collect();
+
+        PgnGame {
+            tags: Vec::new(),
+            moves,
+            positions,
+            result: None,
+        }
+    }
+
+    #[test]
+    pub fn test_chess960_round_trip() {
+        let start = Chess::chess960_position(518).unwrap();
+        let game = pgn_game(start, &["e4", "e5", "Nf3", "Nc6", "Bc4", "Bc5", "O-O"]);
+        let pgn = Chess::to_pgn(&game).unwrap();
+
+        assert!(pgn.contains("[Variant \"Chess960\"]\n[SetUp \"1\"]\n"));
+        assert!(pgn.ends_with("4. O-O *\n"));
+
+        let reparsed = Chess::parse_pgn(&pgn).unwrap();
+        assert_eq!(reparsed.positions, game.positions);
+        assert!(reparsed.positions[0].state.chess960);
+        assert_eq!(
+            Chess::move_to_uci(reparsed.moves[6], reparsed.positions[6]).as_deref(),
+            Some("e1h1")
+        );
+    }
+
+    #[test]
+    pub fn test_variant_round_trip() {
+        // the capture explodes the queen on d5, which standard chess would keep
+        let start = Chess::parse_fen_with_variant(INITIAL_FEN, VariantKind::Atomic).unwrap();
+        let game = pgn_game(start, &["e4", "d5", "exd5", "Qxd5"]);
+        let pgn = Chess::to_pgn(&game).unwrap();
+
+        assert!(pgn.contains("[Variant \"Atomic\"]\n"));
+        assert!(!pgn.contains("[FEN"));
+
+        let reparsed = Chess::parse_pgn(&pgn).unwrap();
+        assert_eq!(reparsed.positions, game.positions);
+        assert_eq!(reparsed.positions[0].state.variant, VariantKind::Atomic);
+
+        let crazyhouse =
+            Chess::parse_pgn("[Variant \"Crazyhouse\"]\n\n1. e4 d5 2. exd5 *").unwrap();
+        assert_eq!(
+            Chess::stringify(crazyhouse.positions.last().unwrap()).unwrap(),
+            "rnbqkbnr/ppp1pppp/8/3P4/8/8/PPPP1PPP/RNBQKBNR[P] b KQkq - 0 2"
+        );
+
+        assert!(matches!(
+            Chess::parse_pgn("[Variant \"Shogi\"]\n\n*"),
+            Err(crate::ChessError::InvalidPgn(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_mismatched_positions() {
+        let game = PgnGame {
+            tags: Vec::new(),
+            moves: Vec::new(),
+            positions: Vec::new(),
+            result: None,
+        };
+
+        assert!(Chess::to_pgn(&game).is_err());
+    }
+}
@@ -27,6 +27,14 @@ mod test_result {
         assert_eq!(result.unwrap(), Some(result::GameResult::BlackCheckmate));
     }
 
+    #[test]
+    pub fn test_checkmate_by_pinned_piece() {
+        // knight on f2 is pinned to its king, but it still gives check
+        let game = Chess::parse_fen("8/3b4/8/5R2/7P/6PK/5n1P/5k2 w - - 0 1").unwrap();
+        let result = result::get_game_result(game);
+        assert_eq!(result, Ok(Some(result::GameResult::BlackCheckmate)));
+    }
+
     #[test]
     pub fn test_stalemate() {
         let game = Chess::parse_fen("6B1/8/7p/7P/8/8/8/k1K5 b - - 0 1").unwrap();
@@ -84,6 +92,185 @@ mod test_result {
         let game = Chess::parse_fen("8/5k2/3p4/1p1Pp2p/pP2Pp1P/P4P1K/8/8 w - - 100 50").unwrap();
         let result = result::get_game_result(game);
         assert_eq!(result.is_ok(), true);
-        assert_eq!(result.unwrap(), Some(result::GameResult::FiftyMoveRule));
+        // Fifty move rule is only claimable
+        assert_eq!(result.unwrap(), None);
+        assert_eq!(
+            result::get_claimable_draw(&[game]),
+            Ok(Some(result::GameResult::FiftyMoveRule))
+        );
+    }
+
+    #[test]
+    pub fn test_seventy_five_move_rule() {
+        let game = Chess::parse_fen("8/5k2/3p4/1p1Pp2p/pP2Pp1P/P4P1K/8/8 w - - 150 75").unwrap();
+        let result = result::get_game_result(game);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            Some(result::GameResult::SeventyFiveMoveRule)
+        );
+        assert_eq!(result::get_claimable_draw(&[game]), Ok(None));
+    }
+
+    #[test]
+    pub fn test_checkmate_on_seventy_fifth_move() {
+        let game = Chess::parse_fen("6B1/8/7p/7P/8/7K/Q7/k7 b - - 150 80").unwrap();
+        let result = result::get_game_result(game);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(result::GameResult::WhiteCheckmate));
+    }
+
+    #[test]
+    pub fn test_claimable_draws() {
+        assert!(result::GameResult::FiftyMoveRule.is_claimable_draw());
+        assert!(result::GameResult::ThreefoldRepetition.is_claimable_draw());
+        assert!(!result::GameResult::SeventyFiveMoveRule.is_claimable_draw());
+        assert!(!result::GameResult::FivefoldRepetition.is_claimable_draw());
+        assert!(!result::GameResult::Stalemate.is_claimable_draw());
+    }
+
+    #[test]
+    pub fn test_player_results() {
+        assert_eq!(
+            result::GameResult::resignation(crate::Player::White),
+            result::GameResult::WhiteResignation
+        );
+        assert_eq!(result::GameResult::WhiteResignation.to_pgn_result(), "0-1");
+        assert_eq!(result::GameResult::BlackResignation.to_pgn_result(), "1-0");
+        assert_eq!(
+            result::GameResult::DrawByAgreement.to_pgn_result(),
+            "1/2-1/2"
+        );
+        assert_eq!(result::GameResult::Aborted.to_pgn_result(), "*");
+    }
+}
+
+#[cfg(test)]
+mod test_repetition {
+    use crate::tests::{game, play_san};
+    use crate::{result, Chess, ChessError, INITIAL_FEN};
+
+    const KNIGHT_SHUFFLE: [&str; 4] = ["Nf3", "Nf6", "Ng1", "Ng8"];
+
+    #[test]
+    pub fn test_no_repetition() {
+        let history = play_san(game(INITIAL_FEN), &KNIGHT_SHUFFLE[..2]);
+
+        assert_eq!(result::get_repetition_count(&history), 1);
+        assert_eq!(result::get_claimable_draw(&history), Ok(None));
+        assert_eq!(result::get_game_result_from_history(&history), Ok(None));
+    }
+
+    #[test]
+    pub fn test_threefold_repetition() {
+        let history = play_san(game(INITIAL_FEN), &KNIGHT_SHUFFLE.repeat(2));
+
+        assert_eq!(result::get_repetition_count(&history), 3);
+        assert_eq!(
+            result::get_claimable_draw(&history),
+            Ok(Some(result::GameResult::ThreefoldRepetition))
+        );
+        // Threefold repetition is only claimable, the game goes on
+        assert_eq!(result::get_game_result_from_history(&history), Ok(None));
+    }
+
+    #[test]
+    pub fn test_fivefold_repetition() {
+        let history = play_san(game(INITIAL_FEN), &KNIGHT_SHUFFLE.repeat(4));
+
+        assert_eq!(result::get_repetition_count(&history), 5);
+        assert_eq!(
+            result::get_game_result_from_history(&history),
+            Ok(Some(result::GameResult::FivefoldRepetition))
+        );
+    }
+
+    #[test]
+    pub fn test_repetition_keys() {
+        let history = play_san(game(INITIAL_FEN), &KNIGHT_SHUFFLE.repeat(4));
+        let repetitions: Vec<_> = history
+            .iter()
+            .map(|game| Chess::get_repetition_key(*game))
+            .collect();
+        let game = *history.last().unwrap();
+
+        assert_eq!(result::count_repetitions(&repetitions), 5);
+        assert_eq!(result::count_repetitions(&[]), 0);
+        assert_eq!(
+            Chess::get_game_result_with_repetitions(game, &repetitions),
+            Ok(Some(result::GameResult::FivefoldRepetition))
+        );
+        assert_eq!(
+            Chess::get_claimable_draw_with_repetitions(history[8], &repetitions[..9]),
+            Ok(Some(result::GameResult::ThreefoldRepetition))
+        );
+        // keys ignore the move counters
+        assert_eq!(repetitions[0], repetitions[4]);
+        assert_ne!(history[0], history[4]);
+    }
+
+    #[test]
+    pub fn test_castling_rights_break_repetition() {
+        // Kings return to the same squares but castling rights are lost
+        let history = play_san(
+            game("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),
+            &["Ke2", "Ke7", "Ke1", "Ke8", "Ke2", "Ke7", "Ke1", "Ke8"],
+        );
+
+        assert_eq!(result::get_repetition_count(&history), 2);
+        assert_eq!(result::get_claimable_draw(&history), Ok(None));
+    }
+
+    #[test]
+    pub fn test_castling_rooks_break_repetition() {
+        // Same board and castling sides, but the players castle with different rooks
+        let a_rooks = Chess::parse_fen("rr2k3/8/8/8/8/8/8/RR2K3 w Aa - 0 1").unwrap();
+        let b_rooks = Chess::parse_fen("rr2k3/8/8/8/8/8/8/RR2K3 w Bb - 0 1").unwrap();
+
+        assert_eq!(result::get_repetition_count(&[a_rooks, b_rooks]), 1);
+        assert_eq!(result::get_repetition_count(&[b_rooks, b_rooks]), 2);
+    }
+
+    #[test]
+    pub fn test_ineffective_en_passant_square() {
+        // No black pawn can capture on e3, so the en passant square doesn't matter
+        let with_square =
+            Chess::parse_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")
+                .unwrap();
+        let without_square =
+            Chess::parse_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+
+        assert_eq!(
+            result::get_repetition_count(&[with_square, without_square]),
+            2
+        );
+    }
+
+    #[test]
+    pub fn test_effective_en_passant_square() {
+        // Black pawn on d4 can capture on e3
+        let with_square =
+            Chess::parse_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")
+                .unwrap();
+        let without_square =
+            Chess::parse_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+                .unwrap();
+
+        assert_eq!(
+            result::get_repetition_count(&[with_square, without_square]),
+            1
+        );
+    }
+
+    #[test]
+    pub fn test_empty_history() {
+        assert!(matches!(
+            result::get_game_result_from_history(&[]),
+            Err(ChessError::InvalidHistory(_))
+        ));
+        assert!(matches!(
+            result::get_claimable_draw(&[]),
+            Err(ChessError::InvalidHistory(_))
+        ));
     }
 }
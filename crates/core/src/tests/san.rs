@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod test_move_to_san {
+    use crate::{pieces, square, Chess, INITIAL_FEN};
+
+    fn find_san(fen: &str, from: &str, to: &str) -> String {
+        let game = Chess::parse_fen(fen).unwrap();
+        let from = square::Square::from_chess_notation(from).unwrap();
+        let to = square::Square::from_chess_notation(to).unwrap();
+        let req_move = Chess::get_moves(from, game)
+            .into_iter()
+            .find(|m| m.to_row_idx == to.row && m.to_col_idx == to.col)
+            .unwrap();
+
+        Chess::move_to_san(req_move, game).unwrap()
+    }
+
+    #[test]
+    pub fn test_pawn_and_piece_moves() {
+        assert_eq!(find_san(INITIAL_FEN, "e2", "e4"), "e4");
+        assert_eq!(find_san(INITIAL_FEN, "g1", "f3"), "Nf3");
+    }
+
+    #[test]
+    pub fn test_capture_with_check() {
+        assert_eq!(
+            find_san("5k2/3r4/1N3N2/8/8/8/8/4K3 w - - 0 1", "b6", "d7"),
+            "Nbxd7+"
+        );
+    }
+
+    #[test]
+    pub fn test_rank_disambiguation() {
+        assert_eq!(
+            find_san("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1", "a1", "a3"),
+            "R1a3"
+        );
+    }
+
+    #[test]
+    pub fn test_file_and_rank_disambiguation() {
+        assert_eq!(
+            find_san("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1", "a1", "b2"),
+            "Qa1b2"
+        );
+        assert_eq!(
+            find_san("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1", "c1", "b2"),
+            "Qcb2"
+        );
+    }
+
+    #[test]
+    pub fn test_long_castle_with_check() {
+        assert_eq!(
+            find_san("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", "e1", "c1"),
+            "O-O-O+"
+        );
+    }
+
+    #[test]
+    pub fn test_en_passant() {
+        assert_eq!(
+            find_san("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5", "d6"),
+            "exd6"
+        );
+    }
+
+    #[test]
+    pub fn test_knight_promotion_mate() {
+        let game = Chess::parse_fen("1rbn4/1pkpP3/1pp5/8/8/8/8/7K w - - 0 1").unwrap();
+        let req_move = Chess::get_all_moves(game)
+            .into_iter()
+            .find(|m| m.promotion_piece == Some(pieces::PieceType::WhiteKnight))
+            .unwrap();
+
+        assert_eq!(Chess::move_to_san(req_move, game).unwrap(), "e8=N#");
+    }
+
+    #[test]
+    pub fn test_mate_by_pinned_piece() {
+        // the knight is pinned to its king by the rook on f5, the mate still counts
+        assert_eq!(
+            find_san("8/3b4/8/5R2/6nP/6PK/7P/5k2 b - - 0 1", "g4", "f2"),
+            "Nf2#"
+        );
+    }
+
+    #[test]
+    pub fn test_illegal_move() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let mut req_move = Chess::get_all_moves(game)[0];
+        req_move.to_row_idx = 3;
+
+        assert!(Chess::move_to_san(req_move, game).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_san_to_move {
+    use crate::{pieces, Chess, ChessError, INITIAL_FEN};
+
+    #[test]
+    pub fn test_pawn_and_piece_moves() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+
+        let pawn_move = Chess::san_to_move("e4", game).unwrap();
+        assert_eq!((pawn_move.from_row_idx, pawn_move.to_row_idx), (6, 4));
+
+        let knight_move = Chess::san_to_move("Nf3", game).unwrap();
+        assert_eq!(knight_move.piece, pieces::PieceType::WhiteKnight);
+        assert_eq!((knight_move.to_row_idx, knight_move.to_col_idx), (5, 5));
+    }
+
+    #[test]
+    pub fn test_disambiguation() {
+        let game = Chess::parse_fen("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1").unwrap();
+
+        let queen_move = Chess::san_to_move("Qa1b2", game).unwrap();
+        assert_eq!((queen_move.from_row_idx, queen_move.from_col_idx), (7, 0));
+
+        let queen_move = Chess::san_to_move("Qcb2", game).unwrap();
+        assert_eq!((queen_move.from_row_idx, queen_move.from_col_idx), (7, 2));
+
+        let queen_move = Chess::san_to_move("Q3b2", game).unwrap();
+        assert_eq!((queen_move.from_row_idx, queen_move.from_col_idx), (5, 0));
+
+        assert!(matches!(
+            Chess::san_to_move("Qb2", game),
+            Err(ChessError::InvalidMove(_))
+        ));
+        assert!(matches!(
+            Chess::san_to_move("Qab2", game),
+            Err(ChessError::InvalidMove(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_castling() {
+        let game = Chess::parse_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+
+        let short_castle = Chess::san_to_move("O-O", game).unwrap();
+        assert!(short_castle.is_castle);
+        assert_eq!(short_castle.to_col_idx, 6);
+
+        let long_castle = Chess::san_to_move("0-0-0", game).unwrap();
+        assert!(long_castle.is_castle);
+        assert_eq!(long_castle.to_col_idx, 2);
+    }
+
+    #[test]
+    pub fn test_en_passant() {
+        let game = Chess::parse_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+        let passant_move = Chess::san_to_move("exd6 e.p.", game).unwrap();
+        assert!(passant_move.is_passant);
+        assert_eq!(Chess::san_to_move("exd6", game).unwrap(), passant_move);
+    }
+
+    #[test]
+    pub fn test_promotion() {
+        let game = Chess::parse_fen("1rbn4/1pkpP3/1pp5/8/8/8/8/7K w - - 0 1").unwrap();
+
+        let promotion_move = Chess::san_to_move("e8=N#", game).unwrap();
+        assert_eq!(
+            promotion_move.promotion_piece,
+            Some(pieces::PieceType::WhiteKnight)
+        );
+
+        let promotion_move = Chess::san_to_move("e8Q", game).unwrap();
+        assert_eq!(
+            promotion_move.promotion_piece,
+            Some(pieces::PieceType::WhiteQueen)
+        );
+
+        assert!(Chess::san_to_move("e8", game).is_err());
+    }
+
+    #[test]
+    pub fn test_invalid_san() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+
+        assert!(matches!(
+            Chess::san_to_move("Zz9", game),
+            Err(ChessError::InvalidSan(_))
+        ));
+        assert!(matches!(
+            Chess::san_to_move("", game),
+            Err(ChessError::InvalidSan(_))
+        ));
+        assert!(matches!(
+            Chess::san_to_move("e5", game),
+            Err(ChessError::InvalidMove(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_round_trip() {
+        let game = Chess::parse_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        )
+        .unwrap();
+
+        for req_move in Chess::get_all_moves(game) {
+            let san = Chess::move_to_san(req_move, game).unwrap();
+            assert_eq!(Chess::san_to_move(&san, game).unwrap(), req_move, "{}", san);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod test_search {
+    use crate::tests::depth;
+    use crate::{validation, Chess, SearchLimits, INITIAL_FEN};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    pub fn test_mate_in_one() {
+        let game = Chess::parse_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let result = Chess::best_move(game, depth(3));
+
+        assert_eq!(result.best_move.unwrap().to_uci().unwrap(), "a1a8");
+        assert_eq!(result.mate_in, Some(1));
+    }
+
+    #[test]
+    pub fn test_quiescence_searches_evasions() {
+        // the mate is found past the horizon, the checked king can't stand pat
+        let game = Chess::parse_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let result = Chess::best_move(game, depth(1));
+
+        assert_eq!(result.best_move.unwrap().to_uci().unwrap(), "a1a8");
+        assert_eq!(result.mate_in, Some(1));
+    }
+
+    #[test]
+    pub fn test_captures_hanging_queen() {
+        let game = Chess::parse_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let result = Chess::best_move(game, depth(3));
+
+        assert_eq!(result.best_move.unwrap().to_uci().unwrap(), "d2d5");
+        assert!(result.score > 300);
+    }
+
+    #[test]
+    pub fn test_game_over() {
+        let mated = Chess::parse_fen("7k/8/8/8/8/8/5PPP/3r2K1 w - - 0 1").unwrap();
+        let result = Chess::best_move(mated, SearchLimits::default());
+
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.mate_in, Some(0));
+        assert!(result.principal_variation.is_empty());
+
+        let stalemate = Chess::parse_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        let result = Chess::best_move(stalemate, SearchLimits::default());
+
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, 0);
+        assert_eq!(result.mate_in, None);
+    }
+
+    #[test]
+    pub fn test_principal_variation_is_legal() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let result = Chess::best_move(game, depth(4));
+
+        assert_eq!(result.depth, 4);
+        assert_eq!(
+            result.best_move,
+            result.principal_variation.first().copied()
+        );
+
+        let mut current = game;
+        for req_move in result.principal_variation {
+            assert!(Chess::get_all_moves(current).contains(&req_move));
+            current = validation::validate_move(req_move, current).unwrap();
+        }
+    }
+
+    #[test]
+    pub fn test_node_limit() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let result = Chess::best_move(
+            game,
+            SearchLimits {
+                nodes: Some(500),
+                ..SearchLimits::default()
+            },
+        );
+
+        assert!(result.nodes <= 500);
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    pub fn test_time_limit() {
+        static NOW: AtomicU64 = AtomicU64::new(0);
+
+        // every clock reading advances the time by 10 ms
+        fn fake_clock() -> f64 {
+            NOW.fetch_add(10, Ordering::SeqCst) as f64
+        }
+
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let result = Chess::best_move_with_clock(
+            game,
+            SearchLimits {
+                time_ms: Some(50),
+                ..SearchLimits::default()
+            },
+            fake_clock,
+        );
+
+        assert!(result.best_move.is_some());
+        assert!(result.depth < 10);
+        assert!(NOW.load(Ordering::SeqCst) <= 70);
+    }
+}
@@ -78,11 +78,51 @@ mod test_state {
 
         assert_eq!(excepted_move.is_some(), true);
 
+        assert_eq!(
+            excepted_move.unwrap().promotion_piece,
+            Some(pieces::PieceType::BlackQueen)
+        );
+
         let new_board = state::get_next_board(*excepted_move.unwrap(), game).unwrap();
 
-        // TODO: Need to be reworked after state.rs change promotion
         assert_eq!(new_board[7][2], Some(pieces::PieceType::BlackQueen));
     }
+
+    #[test]
+    pub fn test_underpromotion() {
+        let game =
+            Chess::parse_fen("rnbqkb1r/1p3ppp/p3pn2/P2p4/3P1B2/3QP3/1PpK1PPP/3R1BNR b kq - 1 10")
+                .unwrap();
+        let moves = moves::get_pseudo_moves(Square::new(6, 2), game);
+
+        let excepted_move = moves.iter().find(|m| {
+            m.to_col_idx == 2
+                && m.to_row_idx == 7
+                && m.promotion_piece == Some(pieces::PieceType::BlackKnight)
+        });
+
+        assert!(excepted_move.is_some());
+
+        let new_board = state::get_next_board(*excepted_move.unwrap(), game).unwrap();
+
+        assert_eq!(new_board[6][2], None);
+        assert_eq!(new_board[7][2], Some(pieces::PieceType::BlackKnight));
+    }
+
+    #[test]
+    pub fn test_promotion_without_piece() {
+        let game =
+            Chess::parse_fen("rnbqkb1r/1p3ppp/p3pn2/P2p4/3P1B2/3QP3/1PpK1PPP/3R1BNR b kq - 1 10")
+                .unwrap();
+        let moves = moves::get_pseudo_moves(Square::new(6, 2), game);
+        let mut req_move = *moves
+            .iter()
+            .find(|m| m.to_col_idx == 2 && m.to_row_idx == 7)
+            .unwrap();
+        req_move.promotion_piece = None;
+
+        assert!(state::get_next_board(req_move, game).is_err());
+    }
 }
 
 
@@ -162,4 +202,36 @@ mod test_get_next_state {
         assert_eq!(next_state.full_moves, 8);
         assert_eq!(next_state.on_turn, player::Player::White);
     }
+
+    #[test]
+    pub fn test_en_passant_square_reset() {
+        let game = Chess::parse_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")
+            .unwrap();
+        let moves = moves::get_pseudo_moves(Square::new(0, 6), game);
+        let next_state = state::get_next_state(moves[0], game);
+
+        assert_eq!(next_state.en_passant_square, None);
+    }
+
+    #[test]
+    pub fn test_rook_capture_break_castle() {
+        let game = Chess::parse_fen("r3k2r/8/8/8/8/8/6B1/R3K2R w KQkq - 0 1").unwrap();
+        let capture = Chess::uci_to_move("g2a8", game).unwrap();
+        let next_state = state::get_next_state(capture, game);
+
+        assert!(!next_state.castle_black_long);
+        assert!(next_state.castle_black_short);
+        assert!(next_state.castle_white_long);
+        assert!(next_state.castle_white_short);
+    }
+
+    #[test]
+    pub fn test_rook_outside_initial_rank_keeps_castle() {
+        let game = Chess::parse_fen("4k3/8/8/8/R7/8/8/R3K2R w KQ - 0 1").unwrap();
+        let rook_move = Chess::uci_to_move("a4a5", game).unwrap();
+        let next_state = state::get_next_state(rook_move, game);
+
+        assert!(next_state.castle_white_long);
+        assert!(next_state.castle_white_short);
+    }
 }
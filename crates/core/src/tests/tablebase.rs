@@ -0,0 +1,256 @@
+#[cfg(test)]
+mod test_tablebase {
+    use crate::tests::game;
+    use crate::{
+        Chess, ChessError, GameResult, SearchLimits, Tablebase, TablebaseProbe, TranspositionTable,
+        Wdl,
+    };
+
+    const KQVK_WDL: &[u8] = include_bytes!("fixtures/tablebase/KQvK.wdl");
+    const KQVK_DTZ: &[u8] = include_bytes!("fixtures/tablebase/KQvK.dtz");
+    const KRVK_WDL: &[u8] = include_bytes!("fixtures/tablebase/KRvK.wdl");
+    const KRVK_DTZ: &[u8] = include_bytes!("fixtures/tablebase/KRvK.dtz");
+
+    fn tablebase() -> Tablebase {
+        let mut tablebase = Tablebase::default();
+
+        for file in [KQVK_WDL, KQVK_DTZ, KRVK_WDL, KRVK_DTZ] {
+            tablebase.add_table(file).unwrap();
+        }
+
+        tablebase
+    }
+
+    /// Largest distance to zeroing of White on turn, stored in the first half of the DTZ file
+    fn max_white_dtz(dtz_file: &[u8]) -> u8 {
+        let data_start = 7 + usize::from(dtz_file[6]) + 4;
+        let data = &dtz_file[data_start..];
+
+        *data[..data.len() / 2].iter().max().unwrap()
+    }
+
+    #[test]
+    pub fn test_probe_results() {
+        let tablebase = tablebase();
+        let probe = |fen: &str| Chess::probe_tablebase(game(fen), &tablebase);
+
+        // queen wins with White on turn
+        let win = probe("8/8/8/4k3/8/8/8/KQ6 w - - 0 1").unwrap();
+        assert_eq!(win.wdl, Wdl::Win);
+        assert!(win.dtz.unwrap() > 0);
+
+        // stalemate and hanging queen are draws
+        assert_eq!(
+            probe("k7/2Q5/1K6/8/8/8/8/8 b - - 0 1"),
+            Some(TablebaseProbe {
+                wdl: Wdl::Draw,
+                dtz: Some(0)
+            })
+        );
+        assert_eq!(
+            probe("8/8/8/8/8/8/1Qk5/7K b - - 0 1").unwrap().wdl,
+            Wdl::Draw
+        );
+
+        // checkmated and mate in one
+        assert_eq!(
+            probe("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1"),
+            Some(TablebaseProbe {
+                wdl: Wdl::Loss,
+                dtz: Some(0)
+            })
+        );
+        assert_eq!(
+            probe("k7/8/1K6/8/8/8/8/2Q5 w - - 0 1").unwrap().dtz,
+            Some(1)
+        );
+    }
+
+    #[test]
+    pub fn test_probe_flipped_colors() {
+        let tablebase = tablebase();
+
+        let white = Chess::probe_tablebase(game("8/8/8/4k3/8/8/8/KQ6 w - - 0 1"), &tablebase);
+        let black = Chess::probe_tablebase(game("kq6/8/8/8/4K3/8/8/8 b - - 0 1"), &tablebase);
+
+        assert_eq!(white, black);
+        assert_eq!(
+            Chess::probe_tablebase(game("8/8/8/4k3/8/8/8/K7 w - - 0 1"), &tablebase),
+            Some(TablebaseProbe {
+                wdl: Wdl::Draw,
+                dtz: Some(0)
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_longest_wins() {
+        // longest mates take 10 moves with a queen and 16 moves with a rook
+        assert_eq!(max_white_dtz(KQVK_DTZ), 19);
+        assert_eq!(max_white_dtz(KRVK_DTZ), 31);
+    }
+
+    #[test]
+    pub fn test_generate_matches_fixtures() {
+        let files = Tablebase::default().generate("KQvK").unwrap();
+
+        assert_eq!(files.wdl, KQVK_WDL);
+        assert_eq!(files.dtz, KQVK_DTZ);
+    }
+
+    #[test]
+    pub fn test_fifty_move_rule() {
+        let tablebase = tablebase();
+        let fen = |half_moves: u32| format!("8/8/8/3k4/8/8/8/R3K3 w - - {} 60", half_moves);
+        let dtz = tablebase.probe_dtz(game(&fen(0))).unwrap() as u32;
+
+        assert_eq!(tablebase.probe_wdl(game(&fen(100 - dtz))), Some(Wdl::Win));
+        assert_eq!(
+            Chess::probe_tablebase(game(&fen(101 - dtz)), &tablebase),
+            Some(TablebaseProbe {
+                wdl: Wdl::CursedWin,
+                dtz: Some(dtz as i32)
+            })
+        );
+
+        // the losing side keeps the draw with the slowest defence
+        let cursed = game(&fen(101 - dtz));
+        let reply = Chess::move_piece(tablebase.best_move(cursed).unwrap(), cursed).unwrap();
+        assert_eq!(tablebase.probe_wdl(reply), Some(Wdl::BlessedLoss));
+
+        // checkmate on the 100th half-move still wins
+        let mated = game("k7/1Q6/1K6/8/8/8/8/8 b - - 100 80");
+        assert_eq!(tablebase.probe_wdl(mated), Some(Wdl::Loss));
+    }
+
+    #[test]
+    pub fn test_tablebase_result() {
+        let tablebase = tablebase();
+        let result = |fen: &str| Chess::get_game_result_with_tablebase(game(fen), &tablebase);
+
+        assert_eq!(
+            result("8/8/8/4k3/8/8/8/KQ6 w - - 0 1").unwrap(),
+            Some(GameResult::WhiteTablebaseWin)
+        );
+        assert_eq!(
+            result("8/8/8/4k3/8/8/8/KQ6 b - - 0 1").unwrap(),
+            Some(GameResult::WhiteTablebaseWin)
+        );
+        assert_eq!(
+            result("kr6/8/8/8/4K3/8/8/8 w - - 0 1").unwrap(),
+            Some(GameResult::BlackTablebaseWin)
+        );
+        assert_eq!(
+            result("8/8/8/8/8/8/1Qk5/7K b - - 0 1").unwrap(),
+            Some(GameResult::TablebaseDraw)
+        );
+        assert_eq!(
+            result("8/8/8/3k4/8/8/8/R3K3 w - - 99 60").unwrap(),
+            Some(GameResult::TablebaseDraw)
+        );
+        assert_eq!(GameResult::WhiteTablebaseWin.to_pgn_result(), "1-0");
+
+        // finished games keep their result, uncovered positions are not adjudicated
+        assert_eq!(
+            result("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1").unwrap(),
+            Chess::get_game_result(game("k7/1Q6/1K6/8/8/8/8/8 b - - 0 1")).unwrap()
+        );
+        assert_eq!(result("8/8/8/4k3/8/8/8/BN2K3 w - - 0 1").unwrap(), None);
+    }
+
+    #[test]
+    pub fn test_best_moves_mate_in_dtz() {
+        let tablebase = tablebase();
+        let mut game = game("8/8/8/3k4/8/8/8/R3K3 w - - 0 1");
+        let dtz = tablebase.probe_dtz(game).unwrap();
+
+        for _ in 0..dtz {
+            let req_move = tablebase.best_move(game).unwrap();
+            game = Chess::move_piece(req_move, game).unwrap();
+        }
+
+        assert_eq!(
+            Chess::get_game_result(game).unwrap(),
+            Some(crate::GameResult::WhiteCheckmate)
+        );
+    }
+
+    #[test]
+    pub fn test_generate_pawn_table() {
+        let mut tablebase = tablebase();
+
+        // promotions to minor pieces need their (drawn) tables too
+        for material in ["KBvK", "KNvK", "KPvK"] {
+            let files = tablebase.generate(material).unwrap();
+            tablebase.add_table(&files.wdl).unwrap();
+            tablebase.add_table(&files.dtz).unwrap();
+        }
+
+        let probe = |fen: &str| tablebase.probe_wdl(game(fen));
+
+        // king in front of the pawn on the sixth rank wins, a rook pawn with the king in the corner draws
+        assert_eq!(probe("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1"), Some(Wdl::Loss));
+        assert_eq!(probe("k7/8/8/8/8/8/P7/K7 w - - 0 1"), Some(Wdl::Draw));
+        assert_eq!(probe("8/8/8/8/4p3/4k3/8/4K3 w - - 0 1"), Some(Wdl::Loss));
+    }
+
+    #[test]
+    pub fn test_unprobed_positions() {
+        let mut tablebase = tablebase();
+
+        // castling rights are not covered
+        let castling = game("8/8/8/4k3/8/8/8/R3K3 w Q - 0 1");
+        assert_eq!(Chess::probe_tablebase(castling, &tablebase), None);
+
+        // missing table
+        let missing = game("8/8/8/4k3/8/8/8/BN2K3 w - - 0 1");
+        assert_eq!(Chess::probe_tablebase(missing, &tablebase), None);
+
+        tablebase.set_max_pieces(2);
+        let queen = game("8/8/8/4k3/8/8/8/KQ6 w - - 0 1");
+        assert_eq!(Chess::probe_tablebase(queen, &tablebase), None);
+    }
+
+    #[test]
+    pub fn test_invalid_tables() {
+        let mut tablebase = Tablebase::default();
+
+        assert!(matches!(
+            tablebase.add_table(b"not a table"),
+            Err(ChessError::InvalidTablebase(_))
+        ));
+
+        let mut truncated = KQVK_WDL.to_vec();
+        truncated.pop();
+        assert!(matches!(
+            tablebase.add_table(&truncated),
+            Err(ChessError::InvalidTablebase(_))
+        ));
+        assert!(!tablebase.has_table("KQvK"));
+
+        // promotions lead to KQvK, which isn't loaded
+        assert!(matches!(
+            tablebase.generate("KPvK"),
+            Err(ChessError::InvalidTablebase(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_search_uses_tablebase() {
+        let tablebase = tablebase();
+        let game = game("8/8/8/3k4/8/8/8/R3K3 w - - 0 1");
+        let dtz = tablebase.probe_dtz(game).unwrap();
+
+        let result = Chess::best_move_with_tablebase(
+            game,
+            SearchLimits::default(),
+            crate::search::system_clock,
+            &mut TranspositionTable::new(0),
+            &tablebase,
+        );
+
+        assert_eq!(result.best_move, tablebase.best_move(game));
+        assert_eq!(result.score, crate::search::TABLEBASE_WIN_SCORE - dtz);
+        assert_eq!(result.nodes, 0);
+    }
+}
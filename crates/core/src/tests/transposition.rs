@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod test_transposition {
+    use crate::tests::depth;
+    use crate::{Bound, Chess, PackedMove, TranspositionEntry, TranspositionTable, INITIAL_FEN};
+
+    fn clock() -> f64 {
+        0.0
+    }
+
+    #[test]
+    pub fn test_capacity() {
+        let small = TranspositionTable::new(1);
+        let large = TranspositionTable::new(4);
+
+        assert!(small.capacity() > 0);
+        assert_eq!(large.capacity(), small.capacity() * 4);
+
+        let mut empty = TranspositionTable::new(0);
+        empty.store(1, 1, 10, Bound::Exact, None);
+
+        assert_eq!(empty.capacity(), 0);
+        assert_eq!(empty.probe(1), None);
+    }
+
+    #[test]
+    pub fn test_store_and_probe() {
+        let mut table = TranspositionTable::new(1);
+        let colliding = 7 + table.capacity() as u64;
+
+        table.store(7, 3, 42, Bound::Lower, None);
+
+        let entry = table.probe(7).unwrap();
+        assert_eq!(entry.depth, 3);
+        assert_eq!(entry.score, 42);
+        assert_eq!(entry.bound, Bound::Lower);
+
+        // same slot, different position
+        assert_eq!(table.probe(colliding), None);
+
+        table.clear();
+        assert_eq!(table.probe(7), None);
+    }
+
+    #[test]
+    pub fn test_replacement() {
+        let mut table = TranspositionTable::new(1);
+        let colliding = 7 + table.capacity() as u64;
+
+        table.store(7, 5, 1, Bound::Exact, None);
+
+        // shallower result of another position keeps the deeper entry
+        table.store(colliding, 2, 2, Bound::Exact, None);
+        assert_eq!(table.probe(7).unwrap().score, 1);
+        assert_eq!(table.probe(colliding), None);
+
+        // same position is always replaced
+        table.store(7, 1, 3, Bound::Upper, None);
+        assert_eq!(table.probe(7).unwrap().score, 3);
+
+        // entries of older searches are replaced
+        table.store(7, 5, 4, Bound::Exact, None);
+        table.new_search();
+        table.store(colliding, 1, 5, Bound::Exact, None);
+        assert_eq!(table.probe(7), None);
+        assert_eq!(table.probe(colliding).unwrap().score, 5);
+    }
+
+    #[test]
+    pub fn test_entry_size() {
+        assert_eq!(std::mem::size_of::<Option<TranspositionEntry>>(), 16);
+        assert_eq!(std::mem::size_of::<Option<PackedMove>>(), 2);
+    }
+
+    #[test]
+    pub fn test_packed_moves() {
+        for fen in [
+            INITIAL_FEN,
+            // castling both sides, en passant and promotions with captures
+            "r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1",
+            // Chess960 king castling onto its own square
+            "1r4kr/8/8/8/8/8/8/1R4KR w HBhb - 0 1",
+            // Crazyhouse drops
+            "4k3/8/8/8/8/8/8/4K3[NPq] w - - 0 1",
+        ] {
+            let game = Chess::parse_fen(fen).unwrap();
+            let mut moves = Chess::get_all_moves(game);
+            moves.extend(Chess::get_drop_moves(game));
+
+            for req_move in &moves {
+                assert_eq!(
+                    PackedMove::new(*req_move).unpack(&moves),
+                    Some(*req_move),
+                    "{} {:?}",
+                    fen,
+                    req_move
+                );
+            }
+        }
+
+        let mut table = TranspositionTable::new(1);
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let moves = Chess::get_all_moves(game);
+
+        table.store(7, 1, 0, Bound::Exact, Some(moves[3]));
+        let packed = table.probe(7).unwrap().best_move.unwrap();
+        assert_eq!(packed.unpack(&moves), Some(moves[3]));
+    }
+
+    #[test]
+    pub fn test_search_reuses_table() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let mut table = TranspositionTable::new(1);
+
+        let first = Chess::best_move_with_table(game, depth(4), clock, &mut table);
+        let second = Chess::best_move_with_table(game, depth(4), clock, &mut table);
+
+        assert_eq!(first.score, second.score);
+        assert_eq!(first.best_move, second.best_move);
+        assert!(second.nodes < first.nodes);
+    }
+
+    #[test]
+    pub fn test_mate_score_with_table() {
+        let game = Chess::parse_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let mut table = TranspositionTable::new(1);
+
+        for _ in 0..2 {
+            let result = Chess::best_move_with_table(game, depth(3), clock, &mut table);
+
+            assert_eq!(result.best_move.unwrap().to_uci().unwrap(), "a1a8");
+            assert_eq!(result.mate_in, Some(1));
+        }
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod test_uci {
+    use crate::{pieces, Chess, ChessError, INITIAL_FEN};
+
+    #[test]
+    pub fn test_to_uci() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let req_move = Chess::san_to_move("e4", game).unwrap();
+
+        assert_eq!(req_move.to_uci(), Some("e2e4".to_string()));
+    }
+
+    #[test]
+    pub fn test_promotion_to_uci() {
+        let game = Chess::parse_fen("8/3P1k2/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let req_move = Chess::san_to_move("d8=N", game).unwrap();
+
+        assert_eq!(req_move.to_uci(), Some("d7d8n".to_string()));
+    }
+
+    #[test]
+    pub fn test_simple_move() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+        let req_move = Chess::uci_to_move("g1f3", game).unwrap();
+
+        assert_eq!(req_move.piece, pieces::PieceType::WhiteKnight);
+        assert_eq!((req_move.from_row_idx, req_move.from_col_idx), (7, 6));
+        assert_eq!((req_move.to_row_idx, req_move.to_col_idx), (5, 5));
+    }
+
+    #[test]
+    pub fn test_castling() {
+        let game = Chess::parse_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        let short_castle = Chess::uci_to_move("e1g1", game).unwrap();
+        assert!(short_castle.is_castle);
+
+        let long_castle = Chess::uci_to_move("e1c1", game).unwrap();
+        assert!(long_castle.is_castle);
+        assert_eq!(long_castle.to_uci(), Some("e1c1".to_string()));
+    }
+
+    #[test]
+    pub fn test_en_passant() {
+        let game = Chess::parse_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let req_move = Chess::uci_to_move("e5d6", game).unwrap();
+
+        assert!(req_move.is_passant);
+    }
+
+    #[test]
+    pub fn test_promotion() {
+        let game = Chess::parse_fen("4k3/8/8/8/8/8/p7/4K3 b - - 0 1").unwrap();
+        let req_move = Chess::uci_to_move("a2a1r", game).unwrap();
+
+        assert_eq!(req_move.promotion_piece, Some(pieces::PieceType::BlackRook));
+        assert!(Chess::uci_to_move("a2a1", game).is_err());
+        assert!(Chess::uci_to_move("a2a1k", game).is_err());
+    }
+
+    #[test]
+    pub fn test_invalid_uci() {
+        let game = Chess::parse_fen(INITIAL_FEN).unwrap();
+
+        for uci in ["", "e2", "e2e5", "e7e5", "z9e4", "e2e4qq", "e2e4Q"] {
+            assert!(matches!(
+                Chess::uci_to_move(uci, game),
+                Err(ChessError::InvalidMove(_))
+            ));
+        }
+    }
+}
@@ -16,7 +16,9 @@ mod validation_tests {
                 to_row_idx: 1,
                 is_castle: false,
                 is_passant: false,
+                is_drop: false,
                 piece: pieces::PieceType::BlackKing,
+                promotion_piece: None,
             },
             game,
         );
@@ -31,7 +33,9 @@ mod validation_tests {
                 to_row_idx: 4,
                 is_castle: false,
                 is_passant: false,
+                is_drop: false,
                 piece: pieces::PieceType::BlackQueen,
+                promotion_piece: None,
             },
             game,
         );
@@ -53,7 +57,9 @@ mod validation_tests {
                 to_row_idx: 2,
                 is_castle: false,
                 is_passant: false,
+                is_drop: false,
                 piece: pieces::PieceType::BlackPawn,
+                promotion_piece: None,
             },
             game,
         );
@@ -75,7 +81,9 @@ mod validation_tests {
                 to_row_idx: 7,
                 is_castle: true,
                 is_passant: false,
+                is_drop: false,
                 piece: pieces::PieceType::WhiteKing,
+                promotion_piece: None,
             },
             game,
         );
@@ -96,13 +104,114 @@ mod validation_tests {
                 to_row_idx: 6,
                 is_castle: false,
                 is_passant: false,
+                is_drop: false,
                 piece: pieces::PieceType::WhiteRook,
+                promotion_piece: None,
             },
             game,
         );
 
         assert_eq!(wrong_move.is_err(), true);
     }
+
+    #[test]
+    pub fn test_knight_promotion() {
+        let game = Chess::parse_fen("8/3P1k2/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        let promotion_move = validation::validate_move(
+            types::Move {
+                from_col_idx: 3,
+                from_row_idx: 1,
+                to_col_idx: 3,
+                to_row_idx: 0,
+                is_castle: false,
+                is_passant: false,
+                is_drop: false,
+                piece: pieces::PieceType::WhitePawn,
+                promotion_piece: Some(pieces::PieceType::WhiteKnight),
+            },
+            game,
+        );
+
+        assert!(promotion_move.is_ok());
+        assert_eq!(
+            promotion_move.unwrap().board[0][3],
+            Some(pieces::PieceType::WhiteKnight)
+        );
+    }
+
+    #[test]
+    pub fn test_promotion_without_piece() {
+        let game = Chess::parse_fen("8/3P1k2/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        let promotion_move = validation::validate_move(
+            types::Move {
+                from_col_idx: 3,
+                from_row_idx: 1,
+                to_col_idx: 3,
+                to_row_idx: 0,
+                is_castle: false,
+                is_passant: false,
+                is_drop: false,
+                piece: pieces::PieceType::WhitePawn,
+                promotion_piece: None,
+            },
+            game,
+        );
+
+        assert!(promotion_move.is_err());
+    }
+
+    #[test]
+    pub fn test_illegal_promotion_piece() {
+        let game = Chess::parse_fen("8/3P1k2/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        for promotion_piece in [
+            pieces::PieceType::WhiteKing,
+            pieces::PieceType::WhitePawn,
+            pieces::PieceType::BlackQueen,
+        ] {
+            let promotion_move = validation::validate_move(
+                types::Move {
+                    from_col_idx: 3,
+                    from_row_idx: 1,
+                    to_col_idx: 3,
+                    to_row_idx: 0,
+                    is_castle: false,
+                    is_passant: false,
+                    is_drop: false,
+                    piece: pieces::PieceType::WhitePawn,
+                    promotion_piece: Some(promotion_piece),
+                },
+                game,
+            );
+
+            assert!(promotion_move.is_err());
+        }
+    }
+
+    #[test]
+    pub fn test_promotion_piece_on_regular_move() {
+        let game =
+            Chess::parse_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+
+        let wrong_move = validation::validate_move(
+            types::Move {
+                from_col_idx: 4,
+                from_row_idx: 6,
+                to_col_idx: 4,
+                to_row_idx: 4,
+                is_castle: false,
+                is_passant: false,
+                is_drop: false,
+                piece: pieces::PieceType::WhitePawn,
+                promotion_piece: Some(pieces::PieceType::WhiteQueen),
+            },
+            game,
+        );
+
+        assert!(wrong_move.is_err());
+    }
 }
 
 mod validation_tests_against_state {
@@ -121,11 +230,39 @@ mod validation_tests_against_state {
                 to_row_idx: 2,
                 is_castle: false,
                 is_passant: false,
+                is_drop: false,
                 piece: pieces::PieceType::BlackPawn,
+                promotion_piece: None,
             },
             game,
         );
 
         assert_eq!(wrong_move.is_err(), true);
     }
+
+    #[test]
+    pub fn test_castle_ignores_other_side() {
+        // d1 is attacked by the knight, but it's not on the short castle path
+        let game =
+            Chess::parse_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8").unwrap();
+
+        assert!(Chess::uci_to_move("e1g1", game).is_ok());
+    }
+
+    #[test]
+    pub fn test_castle_out_of_check() {
+        let game = Chess::parse_fen("4k3/8/8/8/8/8/4r3/R3K2R w KQ - 0 1").unwrap();
+
+        assert!(Chess::uci_to_move("e1g1", game).is_err());
+        assert!(Chess::uci_to_move("e1c1", game).is_err());
+    }
+
+    #[test]
+    pub fn test_castle_through_pawn_attack() {
+        // f1 is attacked by the pawn on g2
+        let game = Chess::parse_fen("4k3/8/8/8/8/8/6p1/R3K2R w KQ - 0 1").unwrap();
+
+        assert!(Chess::uci_to_move("e1g1", game).is_err());
+        assert!(Chess::uci_to_move("e1c1", game).is_ok());
+    }
 }
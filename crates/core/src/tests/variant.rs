@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod test_variant {
+    use crate::tests::{assert_perft, assert_play_matches_state, depth, play, variant_game};
+    use crate::{Checks, Chess, ChessError, GameResult, VariantKind, INITIAL_FEN};
+
+    const THREE_CHECK_POSITION: &str =
+        "r1bqkbnr/pppp1ppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3 +2+1";
+    // rook check on a8 is the third check of white
+    const THIRD_CHECK: &str = "4k3/8/8/8/8/8/8/R3K3 w Q - 0 1 +2+2";
+    // white king steps on the hill from d3
+    const HILL_KINGS: &str = "4k3/8/8/8/8/3K4/8/8 w - - 0 1";
+
+    #[test]
+    pub fn test_three_check_fen() {
+        let three_check = Chess::parse_fen(THREE_CHECK_POSITION).unwrap();
+
+        assert_eq!(three_check.state.variant, VariantKind::ThreeCheck);
+        assert_eq!(three_check.state.checks, Checks { white: 2, black: 1 });
+        assert_eq!(
+            Chess::stringify(&three_check).unwrap(),
+            THREE_CHECK_POSITION
+        );
+
+        let initial = variant_game(INITIAL_FEN, VariantKind::ThreeCheck);
+        assert_eq!(
+            Chess::stringify(&initial).unwrap(),
+            format!("{} +0+0", INITIAL_FEN)
+        );
+        assert_eq!(
+            Chess::parse_fen(INITIAL_FEN).unwrap().state.variant,
+            VariantKind::Standard
+        );
+
+        // King of the Hill is not written in FEN
+        let hill = variant_game(INITIAL_FEN, VariantKind::KingOfTheHill);
+        assert_eq!(Chess::stringify(&hill).unwrap(), INITIAL_FEN);
+
+        for invalid in ["+4+0", "+1", "+a+1", "+1+1+1"] {
+            let fen = format!("{} {}", INITIAL_FEN, invalid);
+            assert!(Chess::parse_fen(&fen).is_err(), "{}", fen);
+        }
+        assert!(matches!(
+            Chess::parse_fen_with_variant(THREE_CHECK_POSITION, VariantKind::KingOfTheHill),
+            Err(ChessError::InvalidFen(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_three_check_counts_checks() {
+        let initial = variant_game(INITIAL_FEN, VariantKind::ThreeCheck);
+        let checked = play(initial, &["e2e4", "f7f6", "d1h5"]);
+
+        assert_eq!(checked.state.checks, Checks { white: 1, black: 0 });
+        assert_eq!(Chess::get_game_result(checked).unwrap(), None);
+
+        // standard games don't count checks
+        let standard = play(
+            Chess::parse_fen(INITIAL_FEN).unwrap(),
+            &["e2e4", "f7f6", "d1h5"],
+        );
+        assert_eq!(standard.state.checks, Checks::default());
+    }
+
+    #[test]
+    pub fn test_three_check_win() {
+        let game = Chess::parse_fen(THIRD_CHECK).unwrap();
+        let rook_check = Chess::uci_to_move("a1a8", game).unwrap();
+        let won = Chess::move_piece(rook_check, game).unwrap();
+
+        assert_eq!(Chess::move_to_san(rook_check, game).unwrap(), "Ra8+");
+        assert_eq!(
+            Chess::get_game_result(won).unwrap(),
+            Some(GameResult::WhiteThreeCheck)
+        );
+        assert_eq!(GameResult::WhiteThreeCheck.to_pgn_result(), "1-0");
+
+        // decided game has no moves left
+        assert!(Chess::get_all_moves(won).is_empty());
+        let king_move = crate::Move {
+            from_row_idx: 0,
+            from_col_idx: 4,
+            to_row_idx: 1,
+            to_col_idx: 4,
+            is_passant: false,
+            is_castle: false,
+            is_drop: false,
+            piece: crate::PieceType::BlackKing,
+            promotion_piece: None,
+        };
+        assert!(matches!(
+            Chess::move_piece(king_move, won),
+            Err(ChessError::InvalidMove(_))
+        ));
+
+        let result = Chess::best_move(game, depth(2));
+        assert_eq!(result.best_move, Some(rook_check));
+        assert_eq!(result.mate_in, Some(1));
+    }
+
+    #[test]
+    pub fn test_king_of_the_hill() {
+        let game = variant_game(HILL_KINGS, VariantKind::KingOfTheHill);
+
+        // bare kings can still reach the hill
+        assert_eq!(Chess::get_game_result(game).unwrap(), None);
+        assert_eq!(
+            Chess::get_game_result(Chess::parse_fen(HILL_KINGS).unwrap()).unwrap(),
+            Some(GameResult::InsufficientMaterial)
+        );
+
+        let won = play(game, &["d3e4"]);
+        assert_eq!(
+            Chess::get_game_result(won).unwrap(),
+            Some(GameResult::WhiteKingOfTheHill)
+        );
+        assert!(Chess::get_all_moves(won).is_empty());
+
+        // black king steps on the hill too
+        let black_won = play(game, &["d3c3", "e8e7", "c3b3", "e7e6", "b3a3", "e6d5"]);
+        assert_eq!(
+            Chess::get_game_result(black_won).unwrap(),
+            Some(GameResult::BlackKingOfTheHill)
+        );
+
+        let result = Chess::best_move(game, depth(2));
+        let best_move = result.best_move.unwrap();
+        assert!((3..=4).contains(&best_move.to_row_idx) && (3..=4).contains(&best_move.to_col_idx));
+        assert_eq!(result.mate_in, Some(1));
+    }
+
+    #[test]
+    pub fn test_three_check_insufficient_material() {
+        let bare_kings = variant_game("4k3/8/8/8/8/8/8/4K3 w - - 0 1", VariantKind::ThreeCheck);
+        assert_eq!(
+            Chess::get_game_result(bare_kings).unwrap(),
+            Some(GameResult::InsufficientMaterial)
+        );
+
+        // a lone bishop can still give checks
+        let bishop = variant_game("4k3/8/8/8/8/8/8/3BK3 w - - 0 1", VariantKind::ThreeCheck);
+        assert_eq!(Chess::get_game_result(bishop).unwrap(), None);
+    }
+
+    #[test]
+    pub fn test_play_matches_state() {
+        for game in [
+            Chess::parse_fen(THREE_CHECK_POSITION).unwrap(),
+            Chess::parse_fen(THIRD_CHECK).unwrap(),
+            variant_game(HILL_KINGS, VariantKind::KingOfTheHill),
+        ] {
+            assert_play_matches_state(game);
+        }
+    }
+
+    #[test]
+    pub fn test_perft() {
+        // counted against standard move generation, ending lines on variant wins
+        let three_check = Chess::parse_fen(THREE_CHECK_POSITION).unwrap();
+        let third_check = Chess::parse_fen(THIRD_CHECK).unwrap();
+        let hill = variant_game("8/8/8/2k5/8/8/4K3/8 w - - 0 1", VariantKind::KingOfTheHill);
+
+        assert_perft(three_check, &[30, 959, 28551, 907241]);
+        assert_perft(third_check, &[16, 68, 1230, 6741]);
+        assert_perft(hill, &[8, 61, 314, 2242]);
+    }
+}
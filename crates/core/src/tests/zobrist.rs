@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod test_zobrist {
+    use crate::tests::{game, play};
+    use crate::{bitboard, Chess, ParsedFen, ParsedFenState, ZobristKeys, INITIAL_FEN};
+
+    #[test]
+    pub fn test_transposition() {
+        let first = play(game(INITIAL_FEN), &["g1f3", "g8f6", "b1c3", "b8c6"]);
+        let second = play(game(INITIAL_FEN), &["b1c3", "b8c6", "g1f3", "g8f6"]);
+
+        assert_eq!(first.hash(), second.hash());
+        assert_ne!(first.hash(), Chess::parse_fen(INITIAL_FEN).unwrap().hash());
+    }
+
+    #[test]
+    pub fn test_state_changes_hash() {
+        let white = Chess::parse_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let black = Chess::parse_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+        let no_castle = Chess::parse_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1").unwrap();
+        let clocks = Chess::parse_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 12 40").unwrap();
+
+        assert_ne!(white.hash(), black.hash());
+        assert_ne!(white.hash(), no_castle.hash());
+        // move clocks are not part of the hash
+        assert_eq!(white.hash(), clocks.hash());
+    }
+
+    #[test]
+    pub fn test_en_passant_only_when_capturable() {
+        let without_en_passant = |game: ParsedFen| ParsedFen {
+            board: game.board,
+            state: ParsedFenState {
+                en_passant_square: None,
+                ..game.state
+            },
+        };
+
+        // e5 pawn can capture on d6
+        let capturable = play(game(INITIAL_FEN), &["e2e4", "a7a6", "e4e5", "d7d5"]);
+
+        assert!(capturable.state.en_passant_square.is_some());
+        assert_ne!(capturable.hash(), without_en_passant(capturable).hash());
+
+        // no white pawn next to h5
+        let not_capturable = play(game(INITIAL_FEN), &["e2e4", "a7a6", "e4e5", "h7h5"]);
+
+        assert!(not_capturable.state.en_passant_square.is_some());
+        assert_eq!(
+            not_capturable.hash(),
+            without_en_passant(not_capturable).hash()
+        );
+    }
+
+    #[test]
+    pub fn test_incremental_hash() {
+        let fens = [
+            INITIAL_FEN,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/1P4P1/8/8/8/8/1p4p1/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ];
+
+        for fen in fens {
+            let game = Chess::parse_fen(fen).unwrap();
+            let mut position = bitboard::Position::from(game);
+
+            assert_eq!(position.hash(), game.hash());
+
+            for req_move in Chess::get_all_moves(game) {
+                let undo = position.make_move(req_move);
+                let next = ParsedFen::from(position);
+
+                assert_eq!(position.hash(), next.hash(), "{} {:?}", fen, req_move);
+
+                for reply in Chess::get_all_moves(next) {
+                    let reply_undo = position.make_move(reply);
+                    assert_eq!(position.hash(), ParsedFen::from(position).hash());
+                    position.unmake_move(reply_undo);
+                }
+
+                position.unmake_move(undo);
+                assert_eq!(position.hash(), game.hash());
+            }
+        }
+    }
+
+    /// Polyglot key of the initial position with the published Random64 table
+    const POLYGLOT_START_KEY: u64 = 0x463B_9618_1691_FC9C;
+
+    #[test]
+    pub fn test_polyglot_layout() {
+        let mut random64 = [0u64; 781];
+        for (idx, key) in random64.iter_mut().enumerate() {
+            *key = (idx as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        }
+        let keys = ZobristKeys::from_polyglot_random64(&random64);
+
+        // white king e1 (piece 11, square 4), black king e8 (piece 10, square 60),
+        // white pawn e5 (piece 1, square 36), black pawn d5 (piece 0, square 35)
+        let game = Chess::parse_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let expected = random64[64 * 11 + 4]
+            ^ random64[64 * 10 + 60]
+            ^ random64[64 + 36]
+            ^ random64[35]
+            ^ random64[772 + 3]
+            ^ random64[780];
+
+        assert_eq!(game.hash_with_keys(&keys), expected);
+
+        let game = Chess::parse_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+        let expected = random64[64 * 7]
+            ^ random64[64 * 7 + 7]
+            ^ random64[64 * 11 + 4]
+            ^ random64[64 * 6 + 56]
+            ^ random64[64 * 6 + 63]
+            ^ random64[64 * 10 + 60]
+            ^ random64[768]
+            ^ random64[769]
+            ^ random64[770]
+            ^ random64[771];
+
+        assert_eq!(game.hash_with_keys(&keys), expected);
+
+        // the built-in keys are not the published Random64 table, so real books can't use them
+        let start = Chess::parse_fen(INITIAL_FEN).unwrap();
+        assert_ne!(start.hash(), POLYGLOT_START_KEY);
+    }
+}
@@ -0,0 +1,171 @@
+//! # Transposition Module
+//!
+//! This module holds a fixed size cache of search results keyed by the Zobrist hash
+//! of a position, so positions reached again (by transposition or in a later search)
+//! don't have to be searched from scratch.
+//!
+//! Size of the table is limited in megabytes. Every hash maps to a single slot,
+//! a stored entry is replaced when:
+//! - the slot holds the same position
+//! - the entry comes from an older search (`new_search` increases the age)
+//! - the new result is searched at least as deep
+//!
+//! Entries take 16 bytes: the best move is packed into 16 bits and unpacked
+//! by matching the legal moves of the position.
+//!
+use crate::types;
+use std::num::NonZeroU16;
+
+/// Table size used when none is given
+pub const DEFAULT_SIZE_MB: usize = 8;
+/// Table size of one-off searches, which throw the table away afterwards
+pub const ONE_OFF_SIZE_MB: usize = 1;
+
+const BYTES_PER_MB: usize = 1024 * 1024;
+
+/// How the stored score relates to the real score of the position
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Bound {
+    /// Score is exact
+    Exact,
+    /// Search failed high, the real score is at least the stored score
+    Lower,
+    /// Search failed low, the real score is at most the stored score
+    Upper,
+}
+
+/// Move packed into 16 bits: from square (bits 0-5), target square (bits 6-11),
+/// promotion or dropped piece kind (bits 12-14, 0 for none) and drop flag (bit 15)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PackedMove(NonZeroU16);
+
+/// Search result of a single position
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TranspositionEntry {
+    /// Full hash of the position, to detect collisions of slots
+    pub hash: u64,
+    pub score: i16,
+    pub depth: u8,
+    pub bound: Bound,
+    pub best_move: Option<PackedMove>,
+    /// Age of the search which stored the entry
+    pub age: u8,
+}
+
+impl PackedMove {
+    pub fn new(req_move: types::Move) -> Self {
+        let square = |row: usize, col: usize| (row * types::BOARD_SIZE + col) as u16;
+        let kind = match req_move.is_drop {
+            true => Some(req_move.piece),
+            false => req_move.promotion_piece,
+        };
+        let kind_code = kind.map_or(0, |piece| piece.piece_kind() as u16 + 1);
+
+        let packed = square(req_move.from_row_idx, req_move.from_col_idx)
+            | square(req_move.to_row_idx, req_move.to_col_idx) << 6
+            | kind_code << 12
+            | u16::from(req_move.is_drop) << 15;
+
+        // moves other than drops change the square, castling keeps the king only on
+        // the target column 6 or 2, so a move is never packed to zero
+        Self(NonZeroU16::new(packed).expect("packed move is never zero"))
+    }
+
+    /// Find the packed move among the moves of the position
+    pub fn unpack(self, moves: &[types::Move]) -> Option<types::Move> {
+        moves
+            .iter()
+            .copied()
+            .find(|req_move| PackedMove::new(*req_move) == self)
+    }
+}
+
+/// Fixed size cache of search results
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    entries: Vec<Option<TranspositionEntry>>,
+    age: u8,
+}
+
+impl TranspositionTable {
+    /// Create a table using at most `size_mb` megabytes, a table of 0 MB stores nothing
+    pub fn new(size_mb: usize) -> Self {
+        let capacity = size_mb * BYTES_PER_MB / std::mem::size_of::<Option<TranspositionEntry>>();
+
+        Self {
+            entries: vec![None; capacity],
+            age: 0,
+        }
+    }
+
+    /// Maximal number of stored entries
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Remove all entries and reset the age, eg. between games
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.age = 0;
+    }
+
+    /// Mark start of a new search, entries of previous searches get replaced first
+    pub fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    /// Get the stored entry of the position
+    pub fn probe(&self, hash: u64) -> Option<&TranspositionEntry> {
+        let idx = self.index(hash)?;
+
+        self.entries[idx]
+            .as_ref()
+            .filter(|entry| entry.hash == hash)
+    }
+
+    /// Store a search result of the position according to the replacement scheme.
+    /// Depth is capped at 255 and the score at the `i16` range.
+    pub fn store(
+        &mut self,
+        hash: u64,
+        depth: u32,
+        score: i32,
+        bound: Bound,
+        best_move: Option<types::Move>,
+    ) {
+        let Some(idx) = self.index(hash) else {
+            return;
+        };
+
+        let is_replaceable = match &self.entries[idx] {
+            None => true,
+            Some(entry) => {
+                entry.hash == hash || entry.age != self.age || depth >= u32::from(entry.depth)
+            }
+        };
+
+        if is_replaceable {
+            self.entries[idx] = Some(TranspositionEntry {
+                hash,
+                score: score.clamp(i16::MIN.into(), i16::MAX.into()) as i16,
+                depth: depth.min(u8::MAX.into()) as u8,
+                bound,
+                best_move: best_move.map(PackedMove::new),
+                age: self.age,
+            });
+        }
+    }
+
+    fn index(&self, hash: u64) -> Option<usize> {
+        match self.entries.len() {
+            0 => None,
+            len => Some((hash % len as u64) as usize),
+        }
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIZE_MB)
+    }
+}
@@ -2,11 +2,18 @@ use crate::err;
 use crate::pieces;
 use crate::player;
 use crate::square;
+use crate::variant;
+use crate::zobrist;
 use serde::{Deserialize, Serialize};
 
 pub const BOARD_SIZE: usize = 8;
 pub const MAX_SIZE_INDEX: usize = 7;
 
+/// Column of the king after castling short, the rook ends next to it on column 5
+pub const KING_SHORT_CASTLE_COL: usize = 6;
+/// Column of the king after castling long, the rook ends next to it on column 3
+pub const KING_LONG_CASTLE_COL: usize = 2;
+
 /// Represents the value of a square on the board
 pub type BoardValue = Option<pieces::PieceType>;
 
@@ -25,6 +32,55 @@ pub struct ParsedFenState {
     pub castle_black_long: bool,
     pub half_moves: u32,
     pub full_moves: u32,
+    /// Start columns of the castling rooks, shared by both players
+    #[serde(default)]
+    pub castle_rooks: CastleRooks,
+    /// Chess960 game, castling is written as the king taking its own rook in FEN and UCI
+    #[serde(default)]
+    pub chess960: bool,
+    /// Crazyhouse pockets and promoted pieces, `None` in games without drops
+    #[serde(default)]
+    pub pockets: Option<Pockets>,
+    /// Chess variant of the game, the standard chess by default
+    #[serde(default)]
+    pub variant: variant::VariantKind,
+    /// Checks given by each player, counted in Three-check only
+    #[serde(default)]
+    pub checks: Checks,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+/// Start columns of the rooks castling short (towards column h) and long (towards column a).
+/// Standard chess uses columns 7 and 0, Chess960 any columns on both sides of the king.
+pub struct CastleRooks {
+    pub short_col: usize,
+    pub long_col: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+/// Pieces captured by a player in Crazyhouse, which can be dropped back on the board
+pub struct Pocket {
+    pub pawns: u8,
+    pub knights: u8,
+    pub bishops: u8,
+    pub rooks: u8,
+    pub queens: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+/// Crazyhouse pockets of both players
+pub struct Pockets {
+    pub white: Pocket,
+    pub black: Pocket,
+    /// Squares of pieces promoted from pawns, they go back to a pocket as pawns when captured
+    pub promoted: [[bool; BOARD_SIZE]; BOARD_SIZE],
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+/// Checks given by each player in Three-check
+pub struct Checks {
+    pub white: u8,
+    pub black: u8,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
@@ -35,7 +91,191 @@ pub struct Move {
     pub to_row_idx: usize,
     pub is_passant: bool,
     pub is_castle: bool,
+    /// Crazyhouse drop of the piece from the pocket, the from square equals the target square
+    #[serde(default)]
+    pub is_drop: bool,
     pub piece: pieces::PieceType,
+    /// Piece the pawn promotes to, required when a pawn reaches the last rank
+    pub promotion_piece: Option<pieces::PieceType>,
+}
+
+impl Default for CastleRooks {
+    fn default() -> Self {
+        Self {
+            short_col: MAX_SIZE_INDEX,
+            long_col: 0,
+        }
+    }
+}
+
+impl CastleRooks {
+    /// Start column of the rook castling to the side
+    pub fn col(&self, is_short: bool) -> usize {
+        match is_short {
+            true => self.short_col,
+            false => self.long_col,
+        }
+    }
+}
+
+impl Pocket {
+    /// Piece kinds a pocket can hold, in the order of FEN notation
+    pub const KINDS: [pieces::PieceKind; 5] = [
+        pieces::PieceKind::Queen,
+        pieces::PieceKind::Rook,
+        pieces::PieceKind::Bishop,
+        pieces::PieceKind::Knight,
+        pieces::PieceKind::Pawn,
+    ];
+
+    /// Number of pieces of the kind in the pocket, kings are never there
+    pub fn count(&self, kind: pieces::PieceKind) -> u8 {
+        match kind {
+            pieces::PieceKind::Pawn => self.pawns,
+            pieces::PieceKind::Knight => self.knights,
+            pieces::PieceKind::Bishop => self.bishops,
+            pieces::PieceKind::Rook => self.rooks,
+            pieces::PieceKind::Queen => self.queens,
+            pieces::PieceKind::King => 0,
+        }
+    }
+
+    /// Put a piece of the kind into the pocket, kings are ignored
+    pub fn add(&mut self, kind: pieces::PieceKind) {
+        if let Some(count) = self.count_mut(kind) {
+            *count = count.saturating_add(1);
+        }
+    }
+
+    /// Take a piece of the kind from the pocket
+    pub fn remove(&mut self, kind: pieces::PieceKind) {
+        if let Some(count) = self.count_mut(kind) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        Self::KINDS.iter().all(|kind| self.count(*kind) == 0)
+    }
+
+    fn count_mut(&mut self, kind: pieces::PieceKind) -> Option<&mut u8> {
+        match kind {
+            pieces::PieceKind::Pawn => Some(&mut self.pawns),
+            pieces::PieceKind::Knight => Some(&mut self.knights),
+            pieces::PieceKind::Bishop => Some(&mut self.bishops),
+            pieces::PieceKind::Rook => Some(&mut self.rooks),
+            pieces::PieceKind::Queen => Some(&mut self.queens),
+            pieces::PieceKind::King => None,
+        }
+    }
+}
+
+impl Pockets {
+    pub fn pocket(&self, player: player::Player) -> &Pocket {
+        match player {
+            player::Player::White => &self.white,
+            player::Player::Black => &self.black,
+        }
+    }
+
+    pub fn pocket_mut(&mut self, player: player::Player) -> &mut Pocket {
+        match player {
+            player::Player::White => &mut self.white,
+            player::Player::Black => &mut self.black,
+        }
+    }
+
+    /// Update pockets and promoted squares after the move.
+    /// `captured` holds row, column and piece taken by the move, the capturing player gets
+    /// the piece, or a pawn when the piece was promoted.
+    pub fn apply_move(
+        &mut self,
+        req_move: &Move,
+        captured: Option<(usize, usize, pieces::PieceType)>,
+    ) {
+        let player = req_move.piece.color();
+
+        if let Some((row, col, piece)) = captured {
+            let kind = match self.promoted[row][col] {
+                true => pieces::PieceKind::Pawn,
+                false => piece.piece_kind(),
+            };
+
+            self.promoted[row][col] = false;
+            self.pocket_mut(player).add(kind);
+        }
+
+        if req_move.is_drop {
+            self.pocket_mut(player).remove(req_move.piece.piece_kind());
+            return;
+        }
+
+        let is_promoted = self.promoted[req_move.from_row_idx][req_move.from_col_idx];
+        self.promoted[req_move.from_row_idx][req_move.from_col_idx] = false;
+        self.promoted[req_move.to_row_idx][req_move.to_col_idx] =
+            is_promoted || req_move.promotion_piece.is_some();
+    }
+}
+
+impl Checks {
+    /// Number of checks given by the player
+    pub fn count(&self, player: player::Player) -> u8 {
+        match player {
+            player::Player::White => self.white,
+            player::Player::Black => self.black,
+        }
+    }
+
+    /// Count a check given by the player
+    pub fn add(&mut self, player: player::Player) {
+        let count = match player {
+            player::Player::White => &mut self.white,
+            player::Player::Black => &mut self.black,
+        };
+
+        *count = count.saturating_add(1);
+    }
+}
+
+impl Move {
+    /// Check whether the move is castling short, the king ends on `KING_SHORT_CASTLE_COL`.
+    /// Column comparison doesn't work in Chess960, where the king may not move at all.
+    pub fn is_short_castle(&self) -> bool {
+        self.is_castle && self.to_col_idx == KING_SHORT_CASTLE_COL
+    }
+
+    /// Converts the move into UCI long algebraic notation (eg. "e2e4", "e1g1", "e7e8q", "N@f3").
+    /// Returns `None` if the move is out of bounds.
+    pub fn to_uci(&self) -> Option<String> {
+        // drops are written with the uppercase piece letter, eg. "N@f3", "P@e4"
+        if self.is_drop {
+            let to = square::Square::new(self.to_row_idx, self.to_col_idx).to_chess_notation()?;
+            let piece =
+                pieces::PieceType::from_kind(self.piece.piece_kind(), player::Player::White);
+
+            return Some(format!("{}@{}", piece.to_char(), to));
+        }
+
+        let from = square::Square::new(self.from_row_idx, self.from_col_idx).to_chess_notation()?;
+        let to = square::Square::new(self.to_row_idx, self.to_col_idx).to_chess_notation()?;
+
+        // UCI uses lowercase letters for promotion pieces
+        let promotion = match self.promotion_piece {
+            Some(piece) => pieces::PieceType::from_kind(piece.piece_kind(), player::Player::Black)
+                .to_char()
+                .to_string(),
+            None => String::new(),
+        };
+
+        Some(format!("{}{}{}", from, to, promotion))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+/// Represents the legal moves of a single piece, grouped by its square
+pub struct SquareMoves {
+    pub square: square::Square,
+    pub moves: Vec<Move>,
 }
 
 pub type ChessResult<T> = std::result::Result<T, err::ChessError>;
@@ -49,7 +289,7 @@ pub struct ParsedFen {
 
 impl ParsedFen {
     pub fn is_in_bounds(&self, row: usize, col: usize) -> bool {
-        return row <= MAX_SIZE_INDEX && col <= MAX_SIZE_INDEX;
+        row <= MAX_SIZE_INDEX && col <= MAX_SIZE_INDEX
     }
 
     pub fn safe_access_square(&self, row: usize, col: usize) -> ChessResult<BoardValue> {
@@ -61,21 +301,18 @@ impl ParsedFen {
     }
 
     pub fn is_enemy_square(&self, row: usize, col: usize, cur_color: player::Player) -> bool {
-        return self
-            .safe_access_square(row, col)
-            .is_ok_and(|piece| piece.is_some() && piece.unwrap().color() != cur_color);
+        self.safe_access_square(row, col)
+            .is_ok_and(|piece| piece.is_some() && piece.unwrap().color() != cur_color)
     }
 
     pub fn is_own_square(&self, row: usize, col: usize, cur_color: player::Player) -> bool {
-        return self
-            .safe_access_square(row, col)
-            .is_ok_and(|piece| piece.is_some() && piece.unwrap().color() == cur_color);
+        self.safe_access_square(row, col)
+            .is_ok_and(|piece| piece.is_some() && piece.unwrap().color() == cur_color)
     }
 
     pub fn is_square_empty(&self, row: usize, col: usize) -> bool {
-        return self
-            .safe_access_square(row, col)
-            .is_ok_and(|piece| piece.is_none());
+        self.safe_access_square(row, col)
+            .is_ok_and(|piece| piece.is_none())
     }
 
     /// Assigns a value to a square on the board
@@ -89,6 +326,17 @@ impl ParsedFen {
         }
     }
 
+    /// Returns Zobrist hash of the position with the built-in keys
+    /// Uses the Polyglot layout, see `ZobristKeys` for hashes compatible with book files
+    pub fn hash(&self) -> u64 {
+        zobrist::hash(*self, &zobrist::DEFAULT_KEYS)
+    }
+
+    /// Returns Zobrist hash of the position with custom keys
+    pub fn hash_with_keys(&self, keys: &zobrist::ZobristKeys) -> u64 {
+        zobrist::hash(*self, keys)
+    }
+
     /// Returns (can_castle_short, can_castle_long)
     pub fn get_castle_ability(&self, cur_color: player::Player) -> (bool, bool) {
         match cur_color {
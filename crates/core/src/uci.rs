@@ -0,0 +1,97 @@
+//! # UCI Module
+//!
+//! This module handles parsing moves in UCI long algebraic notation (eg. `e2e4`, `e1g1`, `e7e8q`).
+//!
+//! UCI strings only carry the from and to squares and an optional promotion piece,
+//! castling and en passant flags are resolved from the legal moves of the position.
+//!
+//! Castling can be written as the king taking its own rook (eg. `e1h1`). Chess960 games
+//! use only this form, as the king move to the castling square may be an ordinary move.
+//!
+//! Crazyhouse drops are written as the uppercase piece letter and the target square (eg. `N@f3`).
+//!
+use crate::err;
+use crate::pieces;
+use crate::square;
+use crate::types;
+use crate::Chess;
+
+/// Parse UCI string into a legal move for the given game.
+pub(super) fn uci_to_move(uci: &str, game: types::ParsedFen) -> types::ChessResult<types::Move> {
+    let uci = uci.trim();
+
+    if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+        return Err(self::get_uci_error(uci));
+    }
+
+    if let Some((piece, to)) = uci.split_once('@') {
+        let kind = pieces::PieceType::from_char(piece.chars().next().unwrap_or_default())
+            .filter(|piece| piece.is_white())
+            .map(|piece| piece.piece_kind())
+            .ok_or_else(|| self::get_uci_error(uci))?;
+        let to = square::Square::from_chess_notation(to).ok_or_else(|| self::get_uci_error(uci))?;
+
+        return Chess::get_drop_moves(game)
+            .into_iter()
+            .find(|m| {
+                m.piece.piece_kind() == kind && m.to_row_idx == to.row && m.to_col_idx == to.col
+            })
+            .ok_or_else(|| self::get_uci_error(uci));
+    }
+
+    let from =
+        square::Square::from_chess_notation(&uci[0..2]).ok_or_else(|| self::get_uci_error(uci))?;
+    let to =
+        square::Square::from_chess_notation(&uci[2..4]).ok_or_else(|| self::get_uci_error(uci))?;
+
+    let promotion_piece = match uci[4..].chars().next() {
+        Some(c) => {
+            let kind = pieces::PieceType::from_char(c)
+                .filter(|piece| !piece.is_white())
+                .map(|piece| piece.piece_kind())
+                .ok_or_else(|| self::get_uci_error(uci))?;
+            Some(pieces::PieceType::from_kind(kind, game.state.on_turn))
+        }
+        None => None,
+    };
+
+    Chess::get_all_moves(game)
+        .into_iter()
+        .find(|m| {
+            let to_col = self::uci_to_col(*m, game);
+            let is_king_takes_rook = m.is_castle && to_col == to.col;
+            let is_standard_castle = m.is_castle && !game.state.chess960;
+
+            m.from_row_idx == from.row
+                && m.from_col_idx == from.col
+                && m.to_row_idx == to.row
+                && ((m.to_col_idx == to.col && (!m.is_castle || is_standard_castle))
+                    || is_king_takes_rook)
+                && m.promotion_piece == promotion_piece
+        })
+        .ok_or_else(|| self::get_uci_error(uci))
+}
+
+/// Convert the move into UCI string, castling in Chess960 is written as the king taking its rook.
+pub(super) fn move_to_uci(req_move: types::Move, game: types::ParsedFen) -> Option<String> {
+    match req_move.is_castle && game.state.chess960 {
+        true => types::Move {
+            to_col_idx: self::uci_to_col(req_move, game),
+            ..req_move
+        }
+        .to_uci(),
+        false => req_move.to_uci(),
+    }
+}
+
+/// Column of the castling rook for castling moves, target column otherwise
+fn uci_to_col(req_move: types::Move, game: types::ParsedFen) -> usize {
+    match req_move.is_castle {
+        true => game.state.castle_rooks.col(req_move.is_short_castle()),
+        false => req_move.to_col_idx,
+    }
+}
+
+fn get_uci_error(uci: &str) -> err::ChessError {
+    err::ChessError::InvalidMove(format!("{} is not a legal UCI move", uci))
+}
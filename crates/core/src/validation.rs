@@ -2,40 +2,32 @@
 //! # Validation Module
 //!
 
+use crate::bitboard;
 use crate::err;
 use crate::moves;
 use crate::pieces;
+use crate::player;
 use crate::square;
 use crate::state;
 use crate::types;
 
-/// The `validate_moves` function validates a list of moves against chess rules.
-///
-/// 1. Validates each move against the chess rules.
-///
-/// Returns a list of validated moves.
-///
-pub fn validate_moves(req_moves: Vec<types::Move>, game: types::ParsedFen) -> Vec<types::Move> {
-    let mut validated_moves = Vec::new();
-
-    for req_move in req_moves {
-        if validate_move(req_move, game).is_ok() {
-            validated_moves.push(req_move);
-        }
-    }
-
-    validated_moves
-}
-
 /// The `validate_move` function validation move agains chess rules.
 ///
 ///  1. Capturing own pieces
 ///  2. King would end up in check after the move
-///  3. Current player king not found on board
+///  3. Current player king not found on board (kings are ordinary pieces in Antichess)
 ///  4. Opponent has no pieces (corrupted game state)
-///  5. King cannot castle (no castling rights)
-///  6. Cannot castle short - path is under attack
-///  7. Cannot castle long - path is under attack
+///  5. King cannot castle (no castling rights, missing rook or blocked path)
+///  6. Cannot castle out of check or through an attacked square
+///  7. Pawn reaching the last rank without a valid promotion piece
+///  8. Promotion piece set on a move that is not a promotion
+///  9. Drop of a piece missing in the pocket, to an occupied square or of a pawn to the first or last rank
+/// 10. Move not allowed by the variant (eg. the game is already decided)
+///
+/// The king check of 2. follows the variant, eg. in Atomic a move exploding the enemy king
+/// is valid even if it leaves the own king in check.
+///
+/// Drops in check are valid only when they block the check (see 2.).
 ///
 /// If the move is valid, the function returns the next game state.
 ///
@@ -45,19 +37,50 @@ pub fn validate_move(
     req_move: types::Move,
     game: types::ParsedFen,
 ) -> types::ChessResult<types::ParsedFen> {
-    if game.is_own_square(
-        req_move.to_row_idx,
-        req_move.to_col_idx,
-        req_move.piece.color(),
-    ) {
+    // in Chess960 the castling king may land on the square of its own rook
+    if !req_move.is_castle
+        && game.is_own_square(
+            req_move.to_row_idx,
+            req_move.to_col_idx,
+            req_move.piece.color(),
+        )
+    {
         return Err(err::ChessError::InvalidMove(format!(
             "Cannot move {:?} to {:?}{:?} because it's own piece",
             req_move.piece, req_move.to_row_idx, req_move.to_col_idx
         )));
     }
 
+    if req_move.is_drop {
+        self::validate_drop(req_move, game)?;
+    }
+
+    let rules = game.state.variant.rules();
+    let position = bitboard::Position::from(game);
+    if rules.filter_moves(&position, vec![req_move]).is_empty() {
+        return Err(err::ChessError::InvalidMove(format!(
+            "Move of {:?} is not allowed in {:?}",
+            req_move.piece, game.state.variant
+        )));
+    }
+
+    self::validate_promotion(req_move, game)?;
+
+    if req_move.piece.piece_kind() == pieces::PieceKind::King && req_move.is_castle {
+        self::validate_castle(req_move, game)?;
+    }
+
     let next_game = state::get_next(req_move, game)?;
 
+    // the move deciding the game may leave the opponent without pieces, eg. exploded king
+    let player = req_move.piece.color();
+    let next_position = bitboard::Position::from(next_game);
+    let is_legal = rules.is_legal_after(&next_position, player);
+
+    if is_legal && rules.get_result(&next_position).is_some() {
+        return Ok(next_game);
+    }
+
     let mut king_position: Option<square::Square> = None;
     let mut opponent_pieces: Vec<square::Square> = Vec::new();
 
@@ -81,7 +104,7 @@ pub fn validate_move(
         }
     }
 
-    if king_position.is_none() {
+    if king_position.is_none() && rules.is_king_royal() {
         return Err(err::ChessError::InvalidMove(
             "Current player king not found".to_string(),
         ));
@@ -93,79 +116,168 @@ pub fn validate_move(
         ));
     }
 
+    if !is_legal {
+        return Err(err::ChessError::InvalidMove(
+            "King would end up in check".to_string(),
+        ));
+    }
+
+    Ok(next_game)
+}
+
+/// The `validate_castle` function validates castling against the position before the move.
+///
+///  1. King must have castling rights for the side it castles to
+///  2. Castling rook must be in place and squares passed by the king and the rook empty
+///  3. King cannot castle out of check or through an attacked square
+///
+/// The target square is covered by the check validation of the resulting position.
+///
+fn validate_castle(req_move: types::Move, game: types::ParsedFen) -> types::ChessResult<()> {
     let (can_castle_short, can_castle_long) = game.get_castle_ability(req_move.piece.color());
+    let is_short = req_move.is_short_castle();
 
-    for piece_sq in opponent_pieces {
-        let pseudo_moves = moves::get_pseudo_moves(piece_sq, next_game);
+    let (can_castle, side) = match is_short {
+        true => (can_castle_short, "short"),
+        false => (can_castle_long, "long"),
+    };
 
-        if pseudo_moves.is_empty() {
-            continue;
-        }
+    if !can_castle {
+        return Err(err::ChessError::InvalidMove(format!(
+            "King cannot castle {}.",
+            side
+        )));
+    }
 
-        if pseudo_moves
-            .iter()
-            .find(|m| {
-                m.to_col_idx == king_position.unwrap().col
-                    && m.to_row_idx == king_position.unwrap().row
-            })
-            .is_some()
-        {
-            return Err(err::ChessError::InvalidMove(
-                "King would end up in check".to_string(),
-            ));
-        }
+    let from = square::Square::new(req_move.from_row_idx, req_move.from_col_idx);
+    if !moves::get_pseudo_moves(from, game).contains(&req_move) {
+        return Err(err::ChessError::InvalidMove(format!(
+            "Cannot castle {}: rook is missing or path is blocked",
+            side
+        )));
+    }
 
-        if req_move.piece.piece_kind() == pieces::PieceKind::King && req_move.is_castle {
-            if !can_castle_short && !can_castle_long {
-                return Err(err::ChessError::InvalidMove(
-                    "King cannot castle.".to_string(),
-                ));
-            }
+    // in Chess960 the king may pass more squares or none at all
+    if bitboard::Position::from(game).is_castle_path_attacked(req_move) {
+        return Err(err::ChessError::InvalidMove(format!(
+            "Cannot castle {}: path is under attack",
+            side
+        )));
+    }
 
-            let king_row = match is_white {
-                true => 7,
-                false => 0,
-            };
-
-            if can_castle_short {
-                let short_castle_path = vec![
-                    square::Square::new(king_row, 4),
-                    square::Square::new(king_row, 5),
-                    square::Square::new(king_row, 6),
-                ];
-
-                if pseudo_moves.iter().any(|m| {
-                    short_castle_path.iter().any(|path_square| {
-                        m.to_row_idx == path_square.row && m.to_col_idx == path_square.col
-                    })
-                }) {
-                    return Err(err::ChessError::InvalidMove(
-                        "Cannot castle short: path is under attack".to_string(),
-                    ));
-                }
-            }
+    Ok(())
+}
 
-            if can_castle_long {
-                let long_castle_path = vec![
-                    square::Square::new(king_row, 4),
-                    square::Square::new(king_row, 3),
-                    square::Square::new(king_row, 2),
-                ];
-
-                if pseudo_moves.iter().any(|m| {
-                    long_castle_path.iter().any(|path_square| {
-                        m.to_row_idx == path_square.row && m.to_col_idx == path_square.col
-                    })
-                }) {
-                    return Err(err::ChessError::InvalidMove(
-                        "Cannot castle long: path is under attack".to_string(),
-                    ));
-                }
+/// The `validate_drop` function validates Crazyhouse drop against the position before the move.
+///
+///  1. Game must be Crazyhouse and the piece must be in the pocket of its player
+///  2. Target square must be empty
+///  3. Pawns cannot be dropped to the first or last rank
+///  4. Drop cannot castle, capture en passant or promote
+///  5. From square must equal the target square
+///
+fn validate_drop(req_move: types::Move, game: types::ParsedFen) -> types::ChessResult<()> {
+    let player = req_move.piece.color();
+    let in_pocket = game
+        .state
+        .pockets
+        .is_some_and(|pockets| pockets.pocket(player).count(req_move.piece.piece_kind()) > 0);
+
+    if !in_pocket {
+        return Err(err::ChessError::InvalidMove(format!(
+            "Cannot drop {:?} because it's not in the pocket",
+            req_move.piece
+        )));
+    }
+
+    if !game.is_square_empty(req_move.to_row_idx, req_move.to_col_idx) {
+        return Err(err::ChessError::InvalidMove(format!(
+            "Cannot drop {:?} to {:?}{:?} because it's not empty",
+            req_move.piece, req_move.to_row_idx, req_move.to_col_idx
+        )));
+    }
+
+    if req_move.piece.piece_kind() == pieces::PieceKind::Pawn
+        && (req_move.to_row_idx == 0 || req_move.to_row_idx == types::MAX_SIZE_INDEX)
+    {
+        return Err(err::ChessError::InvalidMove(format!(
+            "Cannot drop {:?} to the first or last rank",
+            req_move.piece
+        )));
+    }
+
+    if req_move.is_castle || req_move.is_passant || req_move.promotion_piece.is_some() {
+        return Err(err::ChessError::InvalidMove(format!(
+            "Drop of {:?} cannot castle, capture en passant or promote",
+            req_move.piece
+        )));
+    }
+
+    if req_move.from_row_idx != req_move.to_row_idx || req_move.from_col_idx != req_move.to_col_idx
+    {
+        return Err(err::ChessError::InvalidMove(format!(
+            "Drop of {:?} must start on its target square",
+            req_move.piece
+        )));
+    }
+
+    Ok(())
+}
+
+/// The `is_king_in_check` function checks whether the king of the given player
+/// is attacked by any opponent piece by the rules of the game variant.
+///
+/// Returns `false` if the player has no king on the board.
+///
+pub fn is_king_in_check(game: types::ParsedFen, player: player::Player) -> bool {
+    game.state
+        .variant
+        .rules()
+        .is_check(&bitboard::Position::from(game), player)
+}
+
+/// The `validate_promotion` function validates the promotion piece of the move.
+///
+///  1. Pawn reaching the last rank must carry a promotion piece
+///  2. Promotion piece must be a queen, rook, bishop or knight of the pawn's color
+///     (or a king in Antichess)
+///  3. Moves that are not promotions must not carry a promotion piece
+///
+fn validate_promotion(req_move: types::Move, game: types::ParsedFen) -> types::ChessResult<()> {
+    let promotion_row = match req_move.piece.color() {
+        player::Player::White => 0,
+        player::Player::Black => types::MAX_SIZE_INDEX,
+    };
+    let is_promotion = req_move.piece.piece_kind() == pieces::PieceKind::Pawn
+        && req_move.to_row_idx == promotion_row;
+
+    match (is_promotion, req_move.promotion_piece) {
+        (true, None) => Err(err::ChessError::InvalidMove(format!(
+            "{:?} reaching the last rank must choose a promotion piece",
+            req_move.piece
+        ))),
+        (true, Some(promotion_piece)) => {
+            if game
+                .state
+                .variant
+                .rules()
+                .promotion_pieces(req_move.piece.color())
+                .contains(&promotion_piece)
+            {
+                Ok(())
+            } else {
+                Err(err::ChessError::InvalidMove(format!(
+                    "{:?} cannot promote to {:?}",
+                    req_move.piece, promotion_piece
+                )))
             }
         }
+        (false, Some(promotion_piece)) => Err(err::ChessError::InvalidMove(format!(
+            "{:?} cannot promote to {:?} on this move",
+            req_move.piece, promotion_piece
+        ))),
+        (false, None) => Ok(()),
     }
-
-    Ok(next_game)
 }
 
 /// The `validate_move_against_state` function validates the move against the game state.
@@ -0,0 +1,396 @@
+//! # Variant Module
+//!
+//! This module holds rules of chess variants played on the standard board.
+//!
+//! Every variant implements the `Variant` trait, whose hooks are called by move generation,
+//! move playing and game result detection on top of the standard rules.
+//! Standard chess keeps the default implementation of every hook.
+//!
+//! Supported variants:
+//! - Standard chess
+//! - Three-check: the player giving the third check wins, checks given by each player
+//!   are written in FEN after the move counters (eg. `+2+1`)
+//! - King of the Hill: the player whose king reaches d4, e4, d5 or e5 wins
+//! - Atomic: captures explode the capturing piece and all pieces but pawns next to the target
+//!   square, the player exploding the enemy king wins. Kings can't capture, kings standing
+//!   next to each other can't be checked and a move can't explode its own king.
+//! - Antichess: captures are compulsory, there is no check nor castling and kings are ordinary
+//!   pieces, which pawns may promote to. The player losing all pieces or stalemated wins.
+//!
+//! The variant of a game is kept in `ParsedFenState::variant`. FEN doesn't tell
+//! King of the Hill apart from standard chess, see `Chess::parse_fen_with_variant`.
+//!
+use serde::{Deserialize, Serialize};
+
+use crate::bitboard;
+use crate::pieces;
+use crate::player;
+use crate::result;
+use crate::types;
+
+/// Number of checks winning a Three-check game
+pub const THREE_CHECK_WIN: u8 = 3;
+/// Kings of each player on the board in standard chess
+const EXACT_KINGS_COUNT: u8 = 1;
+
+/// d5, e5, d4 and e4, the hill of King of the Hill
+const HILL: bitboard::Bitboard = 0x18 << 24 | 0x18 << 32;
+/// Dark squares of the board, a1 included
+const DARK_SQUARES: bitboard::Bitboard = 0x55AA_55AA_55AA_55AA;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+/// Chess variant of a game
+pub enum VariantKind {
+    #[default]
+    Standard,
+    ThreeCheck,
+    KingOfTheHill,
+    Atomic,
+    Antichess,
+}
+
+impl VariantKind {
+    /// Rules of the variant
+    pub(crate) fn rules(&self) -> &'static dyn Variant {
+        match self {
+            VariantKind::Standard => &StandardRules,
+            VariantKind::ThreeCheck => &ThreeCheckRules,
+            VariantKind::KingOfTheHill => &KingOfTheHillRules,
+            VariantKind::Atomic => &AtomicRules,
+            VariantKind::Antichess => &AntichessRules,
+        }
+    }
+}
+
+/// Rules of a chess variant, every hook defaults to standard chess
+pub(crate) trait Variant: Sync {
+    /// Keep moves allowed by the variant out of legal moves.
+    /// Games decided by the variant have no moves left.
+    fn filter_moves(
+        &self,
+        position: &bitboard::Position,
+        moves: Vec<types::Move>,
+    ) -> Vec<types::Move> {
+        match self.get_result(position) {
+            Some(_) => Vec::new(),
+            None => moves,
+        }
+    }
+
+    /// Update variant state of the position after a move,
+    /// the board and the player on turn are already updated.
+    fn update_state(&self, _position: &mut bitboard::Position) {}
+
+    /// Result of the game decided by the variant, checked before the standard results
+    fn get_result(&self, _position: &bitboard::Position) -> Option<result::GameResult> {
+        None
+    }
+
+    /// Check whether the material can't decide the game anymore,
+    /// `standard` tells whether it's insufficient by the standard rules
+    fn is_insufficient_material(&self, _position: &bitboard::Position, standard: bool) -> bool {
+        standard
+    }
+
+    /// Check whether the numbers of kings on the board are valid, `are_adjacent` tells
+    /// whether kings of both players stand next to each other
+    fn are_kings_valid(&self, white_kings: u8, black_kings: u8, are_adjacent: bool) -> bool {
+        white_kings == EXACT_KINGS_COUNT && black_kings == EXACT_KINGS_COUNT && !are_adjacent
+    }
+
+    /// Check whether a king of the other player standing on the square would be in check
+    fn is_king_square_attacked(
+        &self,
+        position: &bitboard::Position,
+        sq: usize,
+        by_player: player::Player,
+    ) -> bool {
+        position.is_square_attacked(sq, by_player)
+    }
+
+    /// Check whether the king of the player is in check, `false` if the king is missing
+    fn is_check(&self, position: &bitboard::Position, player: player::Player) -> bool {
+        position
+            .king_square(player)
+            .is_some_and(|sq| self.is_king_square_attacked(position, sq, player.opponent()))
+    }
+
+    /// Check whether the last move of the player was legal, `position` is after the move
+    fn is_legal_after(&self, position: &bitboard::Position, player: player::Player) -> bool {
+        !self.is_check(position, player)
+    }
+
+    /// Squares emptied by a capture on the square besides the captured piece,
+    /// `position` is after the capturing piece landed there
+    fn explosion(&self, _position: &bitboard::Position, _sq: usize) -> bitboard::Bitboard {
+        bitboard::EMPTY
+    }
+
+    /// Check whether losing the king loses the game, positions without kings are invalid
+    /// unless the king is not royal or the game is decided
+    fn is_king_royal(&self) -> bool {
+        true
+    }
+
+    /// Pieces a pawn of the player can promote to
+    fn promotion_pieces(&self, player: player::Player) -> Vec<pieces::PieceType> {
+        pieces::PieceType::promotion_pieces(player).to_vec()
+    }
+}
+
+struct StandardRules;
+
+struct ThreeCheckRules;
+
+struct KingOfTheHillRules;
+
+struct AtomicRules;
+
+struct AntichessRules;
+
+impl Variant for StandardRules {}
+
+impl Variant for ThreeCheckRules {
+    fn update_state(&self, position: &mut bitboard::Position) {
+        let on_turn = position.state.on_turn;
+
+        if self.is_check(position, on_turn) {
+            position.state.checks.add(on_turn.opponent());
+        }
+    }
+
+    fn get_result(&self, position: &bitboard::Position) -> Option<result::GameResult> {
+        let checks = position.state.checks;
+
+        if checks.white >= THREE_CHECK_WIN {
+            Some(result::GameResult::WhiteThreeCheck)
+        } else if checks.black >= THREE_CHECK_WIN {
+            Some(result::GameResult::BlackThreeCheck)
+        } else {
+            None
+        }
+    }
+
+    // any piece besides the king can still give checks
+    fn is_insufficient_material(&self, position: &bitboard::Position, standard: bool) -> bool {
+        standard && position.all_occupancy().count_ones() <= 2
+    }
+}
+
+impl Variant for KingOfTheHillRules {
+    fn get_result(&self, position: &bitboard::Position) -> Option<result::GameResult> {
+        let on_hill = |player| {
+            let king = pieces::PieceType::from_kind(pieces::PieceKind::King, player);
+            position.pieces(king) & HILL != bitboard::EMPTY
+        };
+
+        if on_hill(player::Player::White) {
+            Some(result::GameResult::WhiteKingOfTheHill)
+        } else if on_hill(player::Player::Black) {
+            Some(result::GameResult::BlackKingOfTheHill)
+        } else {
+            None
+        }
+    }
+
+    // kings can always walk to the hill
+    fn is_insufficient_material(&self, _position: &bitboard::Position, _standard: bool) -> bool {
+        false
+    }
+}
+
+impl Variant for AtomicRules {
+    // kings can't capture, the explosion would take the capturing king too
+    fn filter_moves(
+        &self,
+        position: &bitboard::Position,
+        moves: Vec<types::Move>,
+    ) -> Vec<types::Move> {
+        if self.get_result(position).is_some() {
+            return Vec::new();
+        }
+
+        moves
+            .into_iter()
+            .filter(|m| {
+                let to = bitboard::square_bit(bitboard::square_index(m.to_row_idx, m.to_col_idx));
+                m.piece.piece_kind() != pieces::PieceKind::King
+                    || position.occupancy(m.piece.color().opponent()) & to == bitboard::EMPTY
+            })
+            .collect()
+    }
+
+    // castling rooks and kings may explode without moving
+    fn update_state(&self, position: &mut bitboard::Position) {
+        for player in [player::Player::White, player::Player::Black] {
+            let row = match player {
+                player::Player::White => types::MAX_SIZE_INDEX,
+                player::Player::Black => 0,
+            };
+            let rooks = position.state.castle_rooks;
+            let rook = pieces::PieceType::from_kind(pieces::PieceKind::Rook, player);
+            let has_rook = |col| position.piece_at(bitboard::square_index(row, col)) == Some(rook);
+            let has_king = position.king_square(player).is_some();
+
+            let short = !has_king || !has_rook(rooks.short_col);
+            let long = !has_king || !has_rook(rooks.long_col);
+            position.remove_castle_rights(player, short, long);
+        }
+    }
+
+    fn get_result(&self, position: &bitboard::Position) -> Option<result::GameResult> {
+        if position.king_square(player::Player::Black).is_none() {
+            Some(result::GameResult::WhiteExplosion)
+        } else if position.king_square(player::Player::White).is_none() {
+            Some(result::GameResult::BlackExplosion)
+        } else {
+            None
+        }
+    }
+
+    // a lone king can't explode anything, two minor pieces may explode each other next to a king
+    fn is_insufficient_material(&self, position: &bitboard::Position, standard: bool) -> bool {
+        standard && position.all_occupancy().count_ones() <= 3
+    }
+
+    // exploded king ends the game, kings may stand next to each other
+    fn are_kings_valid(&self, white_kings: u8, black_kings: u8, _are_adjacent: bool) -> bool {
+        white_kings <= EXACT_KINGS_COUNT && black_kings <= EXACT_KINGS_COUNT
+    }
+
+    // enemy king can't capture, nothing can capture next to the enemy king
+    fn is_king_square_attacked(
+        &self,
+        position: &bitboard::Position,
+        sq: usize,
+        by_player: player::Player,
+    ) -> bool {
+        let king = pieces::PieceType::from_kind(pieces::PieceKind::King, by_player);
+
+        bitboard::king_attacks(sq) & position.pieces(king) == bitboard::EMPTY
+            && position.attackers_of(sq, by_player) & !position.pieces(king) != bitboard::EMPTY
+    }
+
+    // exploding the enemy king wins even when the own king is in check
+    fn is_legal_after(&self, position: &bitboard::Position, player: player::Player) -> bool {
+        position.king_square(player).is_some()
+            && (position.king_square(player.opponent()).is_none()
+                || !self.is_check(position, player))
+    }
+
+    fn explosion(&self, position: &bitboard::Position, sq: usize) -> bitboard::Bitboard {
+        let pawns = position.pieces(pieces::PieceType::WhitePawn)
+            | position.pieces(pieces::PieceType::BlackPawn);
+
+        bitboard::square_bit(sq) | (bitboard::king_attacks(sq) & position.all_occupancy() & !pawns)
+    }
+}
+
+impl Variant for AntichessRules {
+    // captures are compulsory, castling is not allowed
+    fn filter_moves(
+        &self,
+        position: &bitboard::Position,
+        moves: Vec<types::Move>,
+    ) -> Vec<types::Move> {
+        if self.get_result(position).is_some() {
+            return Vec::new();
+        }
+
+        // moves may come from a single square, captures are looked up on the whole board
+        let must_capture = moves.first().is_some_and(|m| {
+            position
+                .pseudo_moves(m.piece.color(), bitboard::FULL)
+                .iter()
+                .any(|m| self::is_capture(position, m))
+        });
+
+        moves
+            .into_iter()
+            .filter(|m| !m.is_castle && (!must_capture || self::is_capture(position, m)))
+            .collect()
+    }
+
+    fn get_result(&self, position: &bitboard::Position) -> Option<result::GameResult> {
+        let on_turn = position.state.on_turn;
+        let is_stuck = position
+            .pseudo_moves(on_turn, bitboard::FULL)
+            .iter()
+            .all(|m| m.is_castle);
+
+        let winner = if is_stuck {
+            on_turn
+        } else if position.occupancy(on_turn.opponent()) == bitboard::EMPTY {
+            on_turn.opponent()
+        } else {
+            return None;
+        };
+
+        match winner {
+            player::Player::White => Some(result::GameResult::WhiteAntichess),
+            player::Player::Black => Some(result::GameResult::BlackAntichess),
+        }
+    }
+
+    // bishops of both players on squares of different colors can't capture each other
+    fn is_insufficient_material(&self, position: &bitboard::Position, _standard: bool) -> bool {
+        // dark and light squares a player may capture on
+        let square_colors = |player| {
+            let bishop = pieces::PieceType::from_kind(pieces::PieceKind::Bishop, player);
+            let occupancy = position.occupancy(player);
+
+            match position.pieces(bishop) == occupancy {
+                true => [
+                    occupancy & DARK_SQUARES != bitboard::EMPTY,
+                    occupancy & !DARK_SQUARES != bitboard::EMPTY,
+                ],
+                false => [true, true],
+            }
+        };
+        let [white_dark, white_light] = square_colors(player::Player::White);
+        let [black_dark, black_light] = square_colors(player::Player::Black);
+
+        let can_meet = (white_dark && black_dark) || (white_light && black_light);
+
+        !can_meet
+    }
+
+    // kings are ordinary pieces, any number of them may stand anywhere
+    fn are_kings_valid(&self, _white_kings: u8, _black_kings: u8, _are_adjacent: bool) -> bool {
+        true
+    }
+
+    // there is no check, kings may be taken like any other piece
+    fn is_king_square_attacked(
+        &self,
+        _position: &bitboard::Position,
+        _sq: usize,
+        _by_player: player::Player,
+    ) -> bool {
+        false
+    }
+
+    fn is_king_royal(&self) -> bool {
+        false
+    }
+
+    fn promotion_pieces(&self, player: player::Player) -> Vec<pieces::PieceType> {
+        let mut promotion_pieces = pieces::PieceType::promotion_pieces(player).to_vec();
+        promotion_pieces.push(pieces::PieceType::from_kind(
+            pieces::PieceKind::King,
+            player,
+        ));
+        promotion_pieces
+    }
+}
+
+/// Check whether the move takes a piece of the opponent
+fn is_capture(position: &bitboard::Position, req_move: &types::Move) -> bool {
+    let to = bitboard::square_bit(bitboard::square_index(
+        req_move.to_row_idx,
+        req_move.to_col_idx,
+    ));
+
+    req_move.is_passant
+        || position.occupancy(req_move.piece.color().opponent()) & to != bitboard::EMPTY
+}
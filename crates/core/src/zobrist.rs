@@ -0,0 +1,289 @@
+//! # Zobrist Module
+//!
+//! This module computes Zobrist hashes of positions, used for repetition detection,
+//! transposition tables and opening book lookups.
+//!
+//! The hash follows the Polyglot layout:
+//! - one key per piece and square
+//! - one key per castling right
+//! - one key per en passant file, only used when a pawn of the player on turn
+//!   stands next to the pawn that just moved two squares (the capture may still be illegal)
+//! - one key xored when White is on turn
+//!
+//! Crazyhouse pockets add one key per player and pocket piece kind, multiplied by the count
+//! of pieces in the pocket. Three-check adds one key per player and number of checks given.
+//! Polyglot has no such keys, they are zero in Polyglot layout.
+//!
+//! Built-in keys are generated by a fixed pseudo random sequence, so hashes are stable
+//! between runs and platforms. To match hashes of Polyglot book files, create keys from
+//! the standard Polyglot `Random64` table with `ZobristKeys::from_polyglot_random64`.
+//!
+use crate::pieces;
+use crate::player;
+use crate::types;
+
+/// Number of keys in the Polyglot `Random64` table
+pub const POLYGLOT_RANDOM64_LENGTH: usize = 781;
+
+const SQUARES: usize = types::BOARD_SIZE * types::BOARD_SIZE;
+const SEED: u64 = 0x7469_6E79_6368_6573;
+
+/// Increment of the SplitMix64 state between outputs
+pub const SPLIT_MIX_INCREMENT: u64 = 0x9E37_79B9_7F4A_7C15;
+
+const POLYGLOT_CASTLE_OFFSET: usize = 768;
+const POLYGLOT_EN_PASSANT_OFFSET: usize = 772;
+const POLYGLOT_TURN_OFFSET: usize = 780;
+
+/// Built-in keys used by `ParsedFen::hash`
+///
+/// The keys are generated with SplitMix64 and are not compatible with real Polyglot `.bin`
+/// books, which are keyed by the published `Random64` table. Load that table with
+/// `ZobristKeys::from_polyglot_random64` to look up positions in such books.
+pub static DEFAULT_KEYS: ZobristKeys = ZobristKeys::generate(SEED);
+
+/// Zobrist keys of all position features
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ZobristKeys {
+    /// Indexed by `PieceType` discriminant and `row * 8 + col`
+    pieces: [[u64; SQUARES]; 12],
+    /// White short, white long, black short, black long
+    castling: [u64; 4],
+    /// Indexed by column of the en passant square
+    en_passant: [u64; types::BOARD_SIZE],
+    /// Xored when White is on turn
+    white_turn: u64,
+    /// Indexed by player (White first) and position of the kind in `Pocket::KINDS`
+    pockets: [[u64; 5]; 2],
+    /// Indexed by player (White first) and number of checks given minus one
+    checks: [[u64; 3]; 2],
+}
+
+impl ZobristKeys {
+    /// Create keys from the standard Polyglot `Random64` table
+    pub fn from_polyglot_random64(random64: &[u64; POLYGLOT_RANDOM64_LENGTH]) -> Self {
+        let mut keys = Self {
+            pieces: [[0; SQUARES]; 12],
+            castling: [0; 4],
+            en_passant: [0; types::BOARD_SIZE],
+            white_turn: random64[POLYGLOT_TURN_OFFSET],
+            pockets: [[0; 5]; 2],
+            checks: [[0; 3]; 2],
+        };
+
+        for piece in pieces::PieceType::ALL {
+            for sq in 0..SQUARES {
+                keys.pieces[piece as usize][sq] = random64[self::polyglot_piece_index(piece, sq)];
+            }
+        }
+
+        keys.castling
+            .copy_from_slice(&random64[POLYGLOT_CASTLE_OFFSET..POLYGLOT_EN_PASSANT_OFFSET]);
+        keys.en_passant
+            .copy_from_slice(&random64[POLYGLOT_EN_PASSANT_OFFSET..POLYGLOT_TURN_OFFSET]);
+
+        keys
+    }
+
+    /// Key of a piece standing on the square index
+    pub fn piece(&self, piece: pieces::PieceType, sq: usize) -> u64 {
+        self.pieces[piece as usize][sq]
+    }
+
+    /// Combined key of the castling rights in the state
+    pub fn castling(&self, state: &types::ParsedFenState) -> u64 {
+        let rights = [
+            state.castle_white_short,
+            state.castle_white_long,
+            state.castle_black_short,
+            state.castle_black_long,
+        ];
+
+        rights
+            .iter()
+            .zip(self.castling)
+            .filter(|(allowed, _)| **allowed)
+            .fold(0, |hash, (_, key)| hash ^ key)
+    }
+
+    /// Key of the en passant column
+    pub fn en_passant(&self, col: usize) -> u64 {
+        self.en_passant[col]
+    }
+
+    /// Combined key of the Crazyhouse pockets in the state
+    pub fn pockets(&self, state: &types::ParsedFenState) -> u64 {
+        let Some(pockets) = state.pockets else {
+            return 0;
+        };
+
+        [pockets.white, pockets.black]
+            .iter()
+            .zip(self.pockets)
+            .fold(0, |hash, (pocket, keys)| {
+                types::Pocket::KINDS
+                    .iter()
+                    .zip(keys)
+                    .fold(hash, |hash, (kind, key)| {
+                        hash ^ key.wrapping_mul(u64::from(pocket.count(*kind)))
+                    })
+            })
+    }
+
+    /// Combined key of the Three-check checks in the state
+    pub fn checks(&self, state: &types::ParsedFenState) -> u64 {
+        if state.checks == types::Checks::default() {
+            return 0;
+        }
+
+        [state.checks.white, state.checks.black]
+            .iter()
+            .zip(self.checks)
+            .filter(|(count, _)| **count > 0)
+            .fold(0, |hash, (count, keys)| {
+                hash ^ keys[usize::from(*count).min(keys.len()) - 1]
+            })
+    }
+
+    /// Key of the player on turn
+    pub fn turn(&self, player: player::Player) -> u64 {
+        match player {
+            player::Player::White => self.white_turn,
+            player::Player::Black => 0,
+        }
+    }
+
+    const fn generate(seed: u64) -> Self {
+        let mut state = seed;
+        let mut keys = Self {
+            pieces: [[0; SQUARES]; 12],
+            castling: [0; 4],
+            en_passant: [0; types::BOARD_SIZE],
+            white_turn: 0,
+            pockets: [[0; 5]; 2],
+            checks: [[0; 3]; 2],
+        };
+
+        let mut piece = 0;
+        while piece < 12 {
+            let mut sq = 0;
+            while sq < SQUARES {
+                state = state.wrapping_add(SPLIT_MIX_INCREMENT);
+                keys.pieces[piece][sq] = self::split_mix(state);
+                sq += 1;
+            }
+            piece += 1;
+        }
+
+        let mut idx = 0;
+        while idx < 4 {
+            state = state.wrapping_add(SPLIT_MIX_INCREMENT);
+            keys.castling[idx] = self::split_mix(state);
+            idx += 1;
+        }
+
+        let mut col = 0;
+        while col < types::BOARD_SIZE {
+            state = state.wrapping_add(SPLIT_MIX_INCREMENT);
+            keys.en_passant[col] = self::split_mix(state);
+            col += 1;
+        }
+
+        state = state.wrapping_add(SPLIT_MIX_INCREMENT);
+        keys.white_turn = self::split_mix(state);
+
+        let mut player = 0;
+        while player < 2 {
+            let mut kind = 0;
+            while kind < 5 {
+                state = state.wrapping_add(SPLIT_MIX_INCREMENT);
+                keys.pockets[player][kind] = self::split_mix(state);
+                kind += 1;
+            }
+            player += 1;
+        }
+
+        let mut player = 0;
+        while player < 2 {
+            let mut count = 0;
+            while count < 3 {
+                state = state.wrapping_add(SPLIT_MIX_INCREMENT);
+                keys.checks[player][count] = self::split_mix(state);
+                count += 1;
+            }
+            player += 1;
+        }
+
+        keys
+    }
+}
+
+/// Compute the hash of a position from scratch
+pub fn hash(game: types::ParsedFen, keys: &ZobristKeys) -> u64 {
+    let mut hash = 0;
+
+    for (row_idx, row) in game.board.iter().enumerate() {
+        for (col_idx, piece) in row.iter().enumerate() {
+            if let Some(piece) = piece {
+                hash ^= keys.piece(*piece, row_idx * types::BOARD_SIZE + col_idx);
+            }
+        }
+    }
+
+    hash ^= keys.castling(&game.state);
+    hash ^= keys.turn(game.state.on_turn);
+    hash ^= keys.pockets(&game.state);
+    hash ^= keys.checks(&game.state);
+
+    if let Some(col) = self::en_passant_column(game) {
+        hash ^= keys.en_passant(col);
+    }
+
+    hash
+}
+
+/// Get column of the en passant square if a pawn of the player on turn can capture on it
+fn en_passant_column(game: types::ParsedFen) -> Option<usize> {
+    let en_passant = game.state.en_passant_square?;
+    let on_turn = game.state.on_turn;
+    let pawn = pieces::PieceType::from_kind(pieces::PieceKind::Pawn, on_turn);
+
+    // capturing pawn stands on the same row as the pawn that moved two squares
+    let pawn_row = match on_turn {
+        player::Player::White => en_passant.row + 1,
+        player::Player::Black => en_passant.row.checked_sub(1)?,
+    };
+
+    [en_passant.col.checked_sub(1), Some(en_passant.col + 1)]
+        .into_iter()
+        .flatten()
+        .any(|col| game.safe_access_square(pawn_row, col) == Ok(Some(pawn)))
+        .then_some(en_passant.col)
+}
+
+/// Index of a piece on the square in the Polyglot `Random64` table.
+/// Polyglot orders pieces black pawn, white pawn, black knight, white knight, ...
+/// and squares from a1 to h8.
+fn polyglot_piece_index(piece: pieces::PieceType, sq: usize) -> usize {
+    let kind = match piece.piece_kind() {
+        pieces::PieceKind::Pawn => 0,
+        pieces::PieceKind::Knight => 1,
+        pieces::PieceKind::Bishop => 2,
+        pieces::PieceKind::Rook => 3,
+        pieces::PieceKind::Queen => 4,
+        pieces::PieceKind::King => 5,
+    };
+    let polyglot_piece = 2 * kind + usize::from(piece.is_white());
+    let row = sq / types::BOARD_SIZE;
+    let col = sq % types::BOARD_SIZE;
+
+    64 * polyglot_piece + 8 * (types::MAX_SIZE_INDEX - row) + col
+}
+
+/// SplitMix64 output function
+pub const fn split_mix(state: u64) -> u64 {
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -1,4 +1,5 @@
 use serde::Serialize;
+use std::collections::BTreeMap;
 use tiny_chess_core::*;
 use wasm_bindgen::prelude::*;
 
@@ -23,6 +24,37 @@ export type ParsedFenState = {
   castle_black_long: boolean;
   half_moves: number;
   full_moves: number;
+  castle_rooks?: CastleRooks;
+  chess960?: boolean;
+  pockets?: Pockets | null;
+  variant?: Variant;
+  checks?: Checks;
+}
+
+export type Variant = "Standard" | "ThreeCheck" | "KingOfTheHill" | "Atomic" | "Antichess";
+
+export type Checks = {
+  white: number;
+  black: number;
+}
+
+export type Pocket = {
+  pawns: number;
+  knights: number;
+  bishops: number;
+  rooks: number;
+  queens: number;
+}
+
+export type Pockets = {
+  white: Pocket;
+  black: Pocket;
+  promoted: boolean[][];
+}
+
+export type CastleRooks = {
+  short_col: number;
+  long_col: number;
 }
 
 export type Square = {
@@ -30,6 +62,8 @@ export type Square = {
   col: number;
 }
 
+export type Squares = Square[];
+
 export type Move = {
   from_col_idx: number;
   from_row_idx: number;
@@ -37,12 +71,73 @@ export type Move = {
   to_row_idx: number;
   is_passant: boolean;
   is_castle: boolean;
+  is_drop?: boolean;
   piece: PieceType;
+  promotion_piece?: PieceType | null;
 }
 
 export type Moves = Move[];
 
-export type GameResult = "WhiteCheckmate" | "BlackCheckmate" | "Stalemate" | "InsufficientMaterial" | "FiftyMoveRule" | "ThreefoldRepetition" | "WhiteResignation" | "BlackResignation" | null;
+export type SquareMoves = {
+  square: Square;
+  moves: Moves;
+}
+
+export type GameResult = "WhiteCheckmate" | "BlackCheckmate" | "Stalemate" | "InsufficientMaterial" | "FiftyMoveRule" | "SeventyFiveMoveRule" | "ThreefoldRepetition" | "FivefoldRepetition" | "WhiteResignation" | "BlackResignation" | "DrawByAgreement" | "Aborted" | "WhiteThreeCheck" | "BlackThreeCheck" | "WhiteKingOfTheHill" | "BlackKingOfTheHill" | "WhiteExplosion" | "BlackExplosion" | "WhiteAntichess" | "BlackAntichess" | "WhiteTablebaseWin" | "BlackTablebaseWin" | "TablebaseDraw" | null;
+
+export type PgnTags = Record<string, string>;
+
+export type PhaseScore = {
+  middlegame: number;
+  endgame: number;
+}
+
+export type SquareTable = number[][];
+
+export type PhaseTable = {
+  middlegame: SquareTable;
+  endgame: SquareTable;
+}
+
+export type EvaluationWeights = {
+  material: Record<"pawn" | "knight" | "bishop" | "rook" | "queen", PhaseScore>;
+  piece_square_tables: Record<"pawn" | "knight" | "bishop" | "rook" | "queen" | "king", PhaseTable>;
+  doubled_pawn: PhaseScore;
+  isolated_pawn: PhaseScore;
+  passed_pawn: PhaseScore[];
+  pawn_shield: PhaseScore;
+  king_open_file: PhaseScore;
+  king_zone_attack: PhaseScore;
+  mobility: Record<"knight" | "bishop" | "rook" | "queen", PhaseScore>;
+}
+
+export type BookMove = {
+  req_move: Move;
+  weight: number;
+  learn: number;
+}
+
+export type Wdl = "Loss" | "BlessedLoss" | "Draw" | "CursedWin" | "Win";
+
+export type TablebaseProbe = {
+  wdl: Wdl;
+  dtz: number | null;
+}
+
+export type SearchLimits = {
+  depth?: number;
+  nodes?: number;
+  time_ms?: number;
+}
+
+export type SearchResult = {
+  best_move: Move | null;
+  score: number;
+  mate_in: number | null;
+  principal_variation: Moves;
+  nodes: number;
+  depth: number;
+}
 
 export type Player = "White" | "Black";
 
@@ -62,14 +157,47 @@ extern "C" {
     #[wasm_bindgen(typescript_type = "Move")]
     pub type MoveJs;
 
+    #[wasm_bindgen(typescript_type = "SquareMoves[]")]
+    pub type SquareMovesJs;
+
     #[wasm_bindgen(typescript_type = "GameResult")]
     pub type GameResultJs;
 
     #[wasm_bindgen(typescript_type = "Square")]
     pub type SquareJs;
 
+    #[wasm_bindgen(typescript_type = "Squares")]
+    pub type SquaresJs;
+
     #[wasm_bindgen(typescript_type = "BoardValue")]
     pub type BoardValueJs;
+
+    #[wasm_bindgen(typescript_type = "PgnTags")]
+    pub type PgnTagsJs;
+
+    #[wasm_bindgen(typescript_type = "BookMove[]")]
+    pub type BookMovesJs;
+
+    #[wasm_bindgen(typescript_type = "TablebaseProbe | null")]
+    pub type TablebaseProbeJs;
+
+    #[wasm_bindgen(typescript_type = "EvaluationWeights")]
+    pub type EvaluationWeightsJs;
+
+    #[wasm_bindgen(typescript_type = "SearchLimits")]
+    pub type SearchLimitsJs;
+
+    #[wasm_bindgen(typescript_type = "SearchResult")]
+    pub type SearchResultJs;
+
+    #[wasm_bindgen(typescript_type = "Player")]
+    pub type PlayerJs;
+
+    #[wasm_bindgen(typescript_type = "Player | null")]
+    pub type OptionalPlayerJs;
+
+    #[wasm_bindgen(typescript_type = "Variant")]
+    pub type VariantJs;
 }
 
 //
@@ -80,14 +208,20 @@ extern "C" {
 
 #[wasm_bindgen]
 pub fn parse_fen(fen: &str) -> Result<ParsedFenJs, JsValue> {
-    let result = Chess::parse_fen(fen).map_err(|e| format_error(e))?;
+    let result = Chess::parse_fen(fen).map_err(format_error)?;
     Ok(result.serialize(&CHESS_SERIALIZER)?.into())
 }
 
 #[wasm_bindgen]
 pub fn stringify_fen(game: ParsedFenJs) -> Result<String, JsValue> {
     let parsed_game = parse_game_js(game)?;
-    Chess::stringify(&parsed_game).map_err(|e| format_error(e))
+    Chess::stringify(&parsed_game).map_err(format_error)
+}
+
+#[wasm_bindgen]
+pub fn chess960_fen(index: u16) -> Result<String, JsValue> {
+    let game = Chess::chess960_position(index).map_err(format_error)?;
+    Chess::stringify(&game).map_err(format_error)
 }
 
 #[wasm_bindgen]
@@ -110,46 +244,82 @@ pub fn square_from_chess_notation(notation: &str) -> Option<SquareJs> {
 #[wasm_bindgen]
 pub struct WasmChess {
     game: ParsedFen,
+    /// Variant of the game, loaded FENs are parsed with it
+    variant: VariantKind,
     history: Vec<String>,
+    /// Parsed history positions, `positions[i]` is `history[i]`
+    positions: Vec<ParsedFen>,
+    /// Repetition keys of the history positions, results don't need to parse the history again
+    repetitions: Vec<RepetitionKey>,
+    /// Moves between history positions, `moves[i]` leads from `history[i]` to `history[i + 1]`
+    moves: Vec<Move>,
     current_position: usize,
+    /// Result declared by the players (eg. claimed draw), ends the game regardless of the board
+    declared_result: Option<GameResult>,
+    /// Player who offered a draw that is still pending
+    draw_offer: Option<Player>,
+    /// Search results shared by `suggest_move` and `bot_move`
+    table: TranspositionTable,
+    /// Opening book played by `bot_move` before searching
+    book: Option<PolyglotBook>,
+    /// Endgame tables answering `suggest_move`, `probe_tablebase` and `get_tablebase_result`
+    tablebase: Tablebase,
 }
 
 #[wasm_bindgen]
 impl WasmChess {
     #[wasm_bindgen(constructor)]
-    pub fn new(fen: Option<String>) -> Result<WasmChess, JsValue> {
+    pub fn new(
+        fen: Option<String>,
+        table_size_mb: Option<usize>,
+        variant: Option<VariantJs>,
+    ) -> Result<WasmChess, JsValue> {
         let mut initial_history_vec = Vec::new();
+        let fen_str = fen.unwrap_or_else(|| INITIAL_FEN.to_string());
 
-        let game = if let Some(fen_str) = fen {
-            let result = Chess::parse_fen(&fen_str).map_err(|e| format_error(e))?;
-            initial_history_vec.push(fen_str);
-            result
-        } else {
-            let result = Chess::parse_fen(INITIAL_FEN).map_err(|e| format_error(e))?;
-            initial_history_vec.push(INITIAL_FEN.to_string());
-            result
-        };
+        // without a variant, FEN decides between standard chess and Three-check
+        let game = match variant {
+            Some(variant) => Chess::parse_fen_with_variant(&fen_str, parse_variant_js(variant)?),
+            None => Chess::parse_fen(&fen_str),
+        }
+        .map_err(format_error)?;
+        initial_history_vec.push(fen_str);
 
         Ok(WasmChess {
             game,
+            variant: game.state.variant,
             history: initial_history_vec,
+            positions: vec![game],
+            repetitions: vec![Chess::get_repetition_key(game)],
+            moves: Vec::new(),
             current_position: 0,
+            declared_result: None,
+            draw_offer: None,
+            table: table_size_mb.map_or_else(TranspositionTable::default, TranspositionTable::new),
+            book: None,
+            tablebase: Tablebase::default(),
         })
     }
 
     #[wasm_bindgen]
     pub fn load_new_fen(&mut self, fen: String) -> Result<(), JsValue> {
-        let result = Chess::parse_fen(&fen).map_err(|e| format_error(e))?;
+        let result = self.parse_fen(&fen)?;
 
         self.game = result;
         self.history = vec![fen];
+        self.positions = vec![result];
+        self.repetitions = vec![Chess::get_repetition_key(result)];
+        self.moves = Vec::new();
         self.current_position = 0;
+        self.declared_result = None;
+        self.draw_offer = None;
+        self.table.clear();
         Ok(())
     }
 
     #[wasm_bindgen]
     pub fn to_fen(&self) -> Result<String, JsValue> {
-        Chess::stringify(&self.game).map_err(|e| format_error(e))
+        Chess::stringify(&self.game).map_err(format_error)
     }
 
     #[wasm_bindgen]
@@ -163,6 +333,24 @@ impl WasmChess {
         Ok(serde_wasm_bindgen::to_value(&moves)?.into())
     }
 
+    #[wasm_bindgen]
+    pub fn get_all_moves(&self) -> Result<MovesJs, JsValue> {
+        let moves = Chess::get_all_moves(self.game);
+        Ok(serde_wasm_bindgen::to_value(&moves)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_drop_moves(&self) -> Result<MovesJs, JsValue> {
+        let moves = Chess::get_drop_moves(self.game);
+        Ok(serde_wasm_bindgen::to_value(&moves)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_all_moves_by_square(&self) -> Result<SquareMovesJs, JsValue> {
+        let moves = Chess::get_all_moves_by_square(self.game);
+        Ok(serde_wasm_bindgen::to_value(&moves)?.into())
+    }
+
     #[wasm_bindgen]
     pub fn get_pseudo_moves(&self, row: usize, col: usize) -> Result<MovesJs, JsValue> {
         let moves = Chess::get_pseudo_moves(Square::new(row, col), self.game);
@@ -171,17 +359,33 @@ impl WasmChess {
 
     #[wasm_bindgen]
     pub fn move_piece(&mut self, req_move: MoveJs) -> Result<(), JsValue> {
+        self.ensure_game_in_progress()?;
+
         let parsed_move = parse_move_js(req_move)?;
-        let result = Chess::move_piece(parsed_move, self.game).map_err(|e| format_error(e))?;
-        let new_fen = Chess::stringify(&result).map_err(|e| format_error(e))?;
+        let result = Chess::move_piece(parsed_move, self.game).map_err(format_error)?;
+        let new_fen = Chess::stringify(&result).map_err(format_error)?;
 
         self.game = result;
 
         if self.current_position < self.history.len() - 1 {
             self.history.truncate(self.current_position + 1);
+            self.positions.truncate(self.current_position + 1);
+            self.repetitions.truncate(self.current_position + 1);
+            self.moves.truncate(self.current_position);
         }
 
         self.history.push(new_fen);
+        self.positions.push(result);
+        self.repetitions.push(Chess::get_repetition_key(result));
+        self.moves.push(parsed_move);
+
+        // Moving instead of answering a draw offer declines it
+        if self
+            .draw_offer
+            .is_some_and(|player| player != parsed_move.piece.color())
+        {
+            self.draw_offer = None;
+        }
         self.current_position = self.history.len() - 1;
 
         Ok(())
@@ -190,17 +394,164 @@ impl WasmChess {
     #[wasm_bindgen]
     pub fn validate_move(&self, req_move: MoveJs) -> Result<bool, JsValue> {
         let parsed_move = parse_move_js(req_move)?;
-        let result = Chess::validate_move(parsed_move, self.game).map_err(|e| format_error(e))?;
+        let result = Chess::validate_move(parsed_move, self.game).map_err(format_error)?;
         Ok(result)
     }
 
+    #[wasm_bindgen]
+    pub fn move_to_san(&self, req_move: MoveJs) -> Result<String, JsValue> {
+        let parsed_move = parse_move_js(req_move)?;
+        Chess::move_to_san(parsed_move, self.game).map_err(format_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn san_to_move(&self, san: &str) -> Result<MoveJs, JsValue> {
+        let result = Chess::san_to_move(san, self.game).map_err(format_error)?;
+        Ok(serde_wasm_bindgen::to_value(&result)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn move_to_uci(&self, req_move: MoveJs) -> Result<Option<String>, JsValue> {
+        let parsed_move = parse_move_js(req_move)?;
+        Ok(Chess::move_to_uci(parsed_move, self.game))
+    }
+
+    #[wasm_bindgen]
+    pub fn uci_to_move(&self, uci: &str) -> Result<MoveJs, JsValue> {
+        let result = Chess::uci_to_move(uci, self.game).map_err(format_error)?;
+        Ok(serde_wasm_bindgen::to_value(&result)?.into())
+    }
+
     #[wasm_bindgen]
     pub fn get_game_result(&self) -> Result<GameResultJs, JsValue> {
-        let result = Chess::get_game_result(self.game).map_err(|e| format_error(e))?;
+        let result = self.get_current_result()?;
 
         Ok(result.serialize(&CHESS_SERIALIZER)?.into())
     }
 
+    #[wasm_bindgen]
+    pub fn get_claimable_draw(&self) -> Result<GameResultJs, JsValue> {
+        let result = match self.declared_result {
+            Some(_) => None,
+            None => Chess::get_claimable_draw_with_repetitions(self.game, self.get_repetitions())
+                .map_err(format_error)?,
+        };
+
+        Ok(result.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn claim_draw(&mut self) -> Result<GameResultJs, JsValue> {
+        self.ensure_game_in_progress()?;
+
+        let result = Chess::get_claimable_draw_with_repetitions(self.game, self.get_repetitions())
+            .map_err(format_error)?
+            .ok_or_else(|| format_error("No draw can be claimed."))?;
+
+        self.declared_result = Some(result);
+
+        Ok(result.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    //
+    //
+    // # Game lifecycle
+    //
+    //
+
+    #[wasm_bindgen]
+    pub fn is_game_over(&self) -> Result<bool, JsValue> {
+        Ok(self.get_current_result()?.is_some())
+    }
+
+    #[wasm_bindgen]
+    pub fn resign(&mut self, player: PlayerJs) -> Result<GameResultJs, JsValue> {
+        self.ensure_game_in_progress()?;
+
+        let player = parse_player_js(player)?;
+        let result = GameResult::resignation(player);
+        self.declared_result = Some(result);
+        self.draw_offer = None;
+
+        Ok(result.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn offer_draw(&mut self, player: PlayerJs) -> Result<(), JsValue> {
+        self.ensure_game_in_progress()?;
+
+        let player = parse_player_js(player)?;
+        if self.draw_offer.is_some() {
+            return Err(format_error("Draw offer is already pending."));
+        }
+
+        self.draw_offer = Some(player);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_draw_offer(&self) -> Result<OptionalPlayerJs, JsValue> {
+        Ok(self.draw_offer.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn accept_draw(&mut self, player: PlayerJs) -> Result<GameResultJs, JsValue> {
+        self.ensure_game_in_progress()?;
+        self.ensure_draw_offered_to(parse_player_js(player)?)?;
+
+        let result = GameResult::DrawByAgreement;
+        self.declared_result = Some(result);
+        self.draw_offer = None;
+
+        Ok(result.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn decline_draw(&mut self, player: PlayerJs) -> Result<(), JsValue> {
+        self.ensure_game_in_progress()?;
+        self.ensure_draw_offered_to(parse_player_js(player)?)?;
+
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn abort(&mut self) -> Result<GameResultJs, JsValue> {
+        self.ensure_game_in_progress()?;
+
+        let result = GameResult::Aborted;
+        self.declared_result = Some(result);
+        self.draw_offer = None;
+
+        Ok(result.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn to_pgn(&self, tags: Option<PgnTagsJs>) -> Result<String, JsValue> {
+        let tags: BTreeMap<String, String> = match tags {
+            Some(tags) => serde_wasm_bindgen::from_value(tags.into()).map_err(format_error)?,
+            None => BTreeMap::new(),
+        };
+
+        let positions = self.positions[..=self.current_position].to_vec();
+
+        let result = self
+            .get_current_result()?
+            .map_or("*", |result| result.to_pgn_result());
+
+        let game = PgnGame {
+            tags: tags
+                .into_iter()
+                .map(|(name, value)| PgnTag { name, value })
+                .collect(),
+            moves: self.moves[..self.current_position].to_vec(),
+            positions,
+            result: Some(result.to_string()),
+        };
+
+        Chess::to_pgn(&game).map_err(format_error)
+    }
+
     //
     //
     // # Square utils
@@ -212,10 +563,15 @@ impl WasmChess {
         let result = self
             .game
             .safe_access_square(row, col)
-            .map_err(|e| format_error(e))?;
+            .map_err(format_error)?;
         Ok(result.serialize(&CHESS_SERIALIZER)?.into())
     }
 
+    #[wasm_bindgen]
+    pub fn clear_transposition_table(&mut self) {
+        self.table.clear();
+    }
+
     #[wasm_bindgen]
     pub fn is_enemy_square(&self, row: usize, col: usize) -> bool {
         self.game.is_enemy_square(row, col, self.game.state.on_turn)
@@ -231,6 +587,155 @@ impl WasmChess {
         self.game.is_square_empty(row, col)
     }
 
+    #[wasm_bindgen]
+    pub fn is_square_attacked(
+        &self,
+        row: usize,
+        col: usize,
+        player: PlayerJs,
+    ) -> Result<bool, JsValue> {
+        let player = parse_player_js(player)?;
+        Ok(Chess::is_square_attacked(
+            Square::new(row, col),
+            self.game,
+            player,
+        ))
+    }
+
+    #[wasm_bindgen]
+    pub fn attackers_of(
+        &self,
+        row: usize,
+        col: usize,
+        player: PlayerJs,
+    ) -> Result<SquaresJs, JsValue> {
+        let player = parse_player_js(player)?;
+        let attackers = Chess::attackers_of(Square::new(row, col), self.game, player);
+        Ok(serde_wasm_bindgen::to_value(&attackers)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_attacked_squares(&self, player: PlayerJs) -> Result<SquaresJs, JsValue> {
+        let player = parse_player_js(player)?;
+        let squares = Chess::get_attacked_squares(self.game, player);
+        Ok(serde_wasm_bindgen::to_value(&squares)?.into())
+    }
+
+    //
+    //
+    // # Engine block
+    //
+    //
+
+    #[wasm_bindgen]
+    pub fn evaluate(&self, weights: Option<EvaluationWeightsJs>) -> Result<i32, JsValue> {
+        match weights {
+            Some(weights) => {
+                let weights = serde_wasm_bindgen::from_value::<EvaluationWeights>(weights.into())
+                    .map_err(format_error)?;
+                Ok(Chess::evaluate_with_weights(self.game, &weights))
+            }
+            None => Ok(Chess::evaluate(self.game)),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn suggest_move(
+        &mut self,
+        limits: Option<SearchLimitsJs>,
+    ) -> Result<SearchResultJs, JsValue> {
+        self.ensure_game_in_progress()?;
+
+        let limits = match limits {
+            Some(limits) => serde_wasm_bindgen::from_value::<SearchLimits>(limits.into())
+                .map_err(format_error)?,
+            None => SearchLimits::default(),
+        };
+
+        let result = Chess::best_move_with_tablebase(
+            self.game,
+            limits,
+            js_sys::Date::now,
+            &mut self.table,
+            &self.tablebase,
+        );
+        Ok(result.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn bot_move(&mut self, level: u8, seed: u32) -> Result<MoveJs, JsValue> {
+        self.ensure_game_in_progress()?;
+
+        let book_move = self
+            .book
+            .as_ref()
+            .and_then(|book| book.pick_move(self.game, u64::from(seed)));
+
+        let result = match book_move {
+            Some(book_move) => book_move,
+            None => Chess::bot_move_with_table(self.game, level, u64::from(seed), &mut self.table)
+                .map_err(format_error)?,
+        };
+        Ok(result.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn load_opening_book(&mut self, book: &[u8], random64: &[u64]) -> Result<(), JsValue> {
+        let random64 = <&[u64; POLYGLOT_RANDOM64_LENGTH]>::try_from(random64).map_err(|_| {
+            format_error(format!(
+                "Random64 table must have {} keys, got {}.",
+                POLYGLOT_RANDOM64_LENGTH,
+                random64.len()
+            ))
+        })?;
+
+        let keys = ZobristKeys::from_polyglot_random64(random64);
+        self.book = Some(PolyglotBook::from_bytes(book, keys).map_err(format_error)?);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn unload_opening_book(&mut self) {
+        self.book = None;
+    }
+
+    #[wasm_bindgen]
+    pub fn get_book_moves(&self) -> Result<BookMovesJs, JsValue> {
+        let moves = self
+            .book
+            .as_ref()
+            .map(|book| book.moves(self.game))
+            .unwrap_or_default();
+        Ok(moves.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn add_tablebase_file(&mut self, bytes: &[u8]) -> Result<(), JsValue> {
+        self.tablebase.add_table(bytes).map_err(format_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn set_tablebase_max_pieces(&mut self, max_pieces: usize) {
+        self.tablebase.set_max_pieces(max_pieces);
+    }
+
+    #[wasm_bindgen]
+    pub fn probe_tablebase(&self) -> Result<TablebaseProbeJs, JsValue> {
+        let probe = Chess::probe_tablebase(self.game, &self.tablebase);
+        Ok(probe.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_tablebase_result(&self) -> Result<GameResultJs, JsValue> {
+        let result = match self.get_current_result()? {
+            Some(result) => Some(result),
+            None => Chess::get_game_result_with_tablebase(self.game, &self.tablebase)
+                .map_err(format_error)?,
+        };
+
+        Ok(result.serialize(&CHESS_SERIALIZER)?.into())
+    }
+
     //
     //
     // # History block
@@ -269,8 +774,7 @@ impl WasmChess {
         }
 
         self.current_position -= 1;
-        let fen = &self.history[self.current_position];
-        self.game = Chess::parse_fen(fen).map_err(|e| format_error(e))?;
+        self.game = self.positions[self.current_position];
 
         Ok(())
     }
@@ -282,8 +786,7 @@ impl WasmChess {
         }
 
         self.current_position += 1;
-        let fen = &self.history[self.current_position];
-        self.game = Chess::parse_fen(fen).map_err(|e| format_error(e))?;
+        self.game = self.positions[self.current_position];
 
         Ok(())
     }
@@ -299,13 +802,49 @@ impl WasmChess {
         }
 
         self.current_position = index;
-        let fen = &self.history[index];
-        self.game = Chess::parse_fen(fen).map_err(|e| format_error(e))?;
+        self.game = self.positions[index];
 
         Ok(())
     }
 }
 
+impl WasmChess {
+    /// Gets the declared result, or the result of the board at the current position
+    fn get_current_result(&self) -> Result<Option<GameResult>, JsValue> {
+        if let Some(result) = self.declared_result {
+            return Ok(Some(result));
+        }
+
+        Chess::get_game_result_with_repetitions(self.game, self.get_repetitions())
+            .map_err(format_error)
+    }
+
+    fn ensure_game_in_progress(&self) -> Result<(), JsValue> {
+        match self.get_current_result()? {
+            Some(_) => Err(format_error("Game is over.")),
+            None => Ok(()),
+        }
+    }
+
+    fn ensure_draw_offered_to(&self, player: Player) -> Result<(), JsValue> {
+        match self.draw_offer {
+            Some(offered_by) if offered_by != player => Ok(()),
+            Some(_) => Err(format_error("Cant answer own draw offer.")),
+            None => Err(format_error("No draw offer is pending.")),
+        }
+    }
+
+    /// Repetition keys of history positions up to the current position
+    fn get_repetitions(&self) -> &[RepetitionKey] {
+        &self.repetitions[..=self.current_position]
+    }
+
+    /// Parses a FEN into a position of the game variant
+    fn parse_fen(&self, fen: &str) -> Result<ParsedFen, JsValue> {
+        Chess::parse_fen_with_variant(fen, self.variant).map_err(format_error)
+    }
+}
+
 //
 //
 // #  Private functions
@@ -316,11 +855,19 @@ static CHESS_SERIALIZER: serde_wasm_bindgen::Serializer =
     serde_wasm_bindgen::Serializer::new().serialize_missing_as_null(true);
 
 fn parse_game_js(game: ParsedFenJs) -> Result<ParsedFen, JsValue> {
-    serde_wasm_bindgen::from_value::<ParsedFen>(game.into()).map_err(|e| format_error(e))
+    serde_wasm_bindgen::from_value::<ParsedFen>(game.into()).map_err(format_error)
+}
+
+fn parse_player_js(player: PlayerJs) -> Result<Player, JsValue> {
+    serde_wasm_bindgen::from_value::<Player>(player.into()).map_err(format_error)
+}
+
+fn parse_variant_js(variant: VariantJs) -> Result<VariantKind, JsValue> {
+    serde_wasm_bindgen::from_value::<VariantKind>(variant.into()).map_err(format_error)
 }
 
 fn parse_move_js(req_move: MoveJs) -> Result<Move, JsValue> {
-    serde_wasm_bindgen::from_value::<Move>(req_move.into()).map_err(|e| format_error(e))
+    serde_wasm_bindgen::from_value::<Move>(req_move.into()).map_err(format_error)
 }
 
 fn format_error<T: std::fmt::Debug>(error: T) -> JsValue {